@@ -0,0 +1,652 @@
+//! Zero-copy parsing of class files from a byte slice.
+//!
+//! `parse_class_file_ref` only checks the layout of the class file. Utf8
+//! constants, attribute bodies and code stay borrowed from the slice, and
+//! fields, methods and attributes are decoded when they are iterated. Use
+//! `ClassFileRef::to_class_file` to get a fully parsed `ClassFile`; it is not
+//! called `to_owned` so that it does not shadow `ToOwned::to_owned` of the
+//! `Clone` implementation.
+
+use std::borrow::Cow;
+use std::io;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::attributes::ExceptionTableEntry;
+use crate::model::constants::{Constant, ConstantIndex, ConstantPool};
+use crate::model::java_string::decode_modified_utf8;
+use crate::model::{
+    Attributes, ClassAccessFlags, ClassFile, Field, FieldAccessFlags, FlagBits, Method,
+    MethodAccessFlags,
+};
+use crate::parse::{
+    check_limit, decode_attribute, parse_constant, ParseContext, ParseLimits, ParseOptions,
+};
+
+/// Reader of a borrowed byte slice, which locates errors by the offset in the
+/// class file.
+#[derive(Debug, Clone)]
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Offset of `bytes` in the class file.
+    base: usize,
+    limits: ParseLimits,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(bytes: &'a [u8], base: usize, limits: ParseLimits) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            base,
+            limits,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.base + self.position
+    }
+
+    fn take(&mut self, length: usize) -> JvmParseResult<&'a [u8]> {
+        let rest = &self.bytes[self.position..];
+        if length > rest.len() {
+            let error = io::Error::from(io::ErrorKind::UnexpectedEof);
+            return Err(JvmParseError::from(error).at(self.offset() as u64));
+        }
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn u8(&mut self) -> JvmParseResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> JvmParseResult<u16> {
+        Ok(BigEndian::read_u16(self.take(2)?))
+    }
+
+    fn u32(&mut self) -> JvmParseResult<u32> {
+        Ok(BigEndian::read_u32(self.take(4)?))
+    }
+
+    fn invalid(&self, bytes_back: usize, message: String) -> JvmParseError {
+        JvmParseError::InvalidFormat(message).at((self.offset() - bytes_back) as u64)
+    }
+}
+
+/// Parses the layout of a class file without copying its contents.
+///
+/// Errors are located like the ones of `parse_class_file`, but without the
+/// class name. Malformed constants, members and attributes are only detected
+/// when they are accessed.
+pub fn parse_class_file_ref(bytes: &[u8]) -> JvmParseResult<ClassFileRef<'_>> {
+    parse_class_file_ref_with(bytes, &ParseLimits::default())
+}
+
+/// Parses the layout of a class file like `parse_class_file_ref` with the
+/// given limits, which also apply to `ClassFileRef::to_class_file`.
+pub fn parse_class_file_ref_with<'a>(
+    bytes: &'a [u8],
+    limits: &ParseLimits,
+) -> JvmParseResult<ClassFileRef<'a>> {
+    let limits = *limits;
+    let mut scanner = Scanner::new(bytes, 0, limits);
+    let magic = scanner.u32()?;
+    if magic != 0xCAFEBABE {
+        return Err(scanner.invalid(4, "invalid magic".into()));
+    }
+
+    let minor_version = scanner.u16()?;
+    let major_version = scanner.u16()?;
+    if major_version < 45 {
+        return Err(scanner.invalid(
+            4,
+            format!(
+                "version must be at least 45.0, but got {}.{}",
+                major_version, minor_version
+            ),
+        ));
+    }
+
+    let constants = scan_constants(&mut scanner)?;
+    let access_flags = ClassAccessFlags::from_bits_retain(scanner.u16()?);
+    let this_class = ConstantIndex(scanner.u16()?);
+    let super_class = ConstantIndex(scanner.u16()?);
+    let interfaces_count = scanner.u16()?;
+    let interfaces = scanner.take(interfaces_count as usize * 2)?;
+    let fields = scan_members(&mut scanner, "fields")?;
+    let methods = scan_members(&mut scanner, "methods")?;
+    let attributes = scan_attributes(&mut scanner)?;
+
+    Ok(ClassFileRef {
+        bytes: &bytes[..scanner.position],
+        minor_version,
+        major_version,
+        constants,
+        access_flags,
+        this_class,
+        super_class,
+        interfaces,
+        fields,
+        methods,
+        attributes,
+        limits,
+    })
+}
+
+fn scan_constants<'a>(scanner: &mut Scanner<'a>) -> JvmParseResult<ConstantPoolRef<'a>> {
+    let constant_pool_count = scanner.u16()?;
+    if constant_pool_count == 0 {
+        return Err(scanner.invalid(2, "constant pool count must be at least 1".into()));
+    }
+    check_limit(
+        "constant pool count",
+        constant_pool_count as u64 - 1,
+        scanner.limits.max_constants as u64,
+    )
+    .map_err(|error| error.at(scanner.offset() as u64 - 2))?;
+
+    let mut entries = Vec::with_capacity(constant_pool_count as usize - 1);
+    let mut i = 1;
+    while i < constant_pool_count {
+        let start = scanner.position;
+        let tag = scanner.u8()?;
+        let length = match tag {
+            7 | 8 | 16 | 19 | 20 => 2,
+            15 => 3,
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 4,
+            5 | 6 => 8,
+            1 => scanner.u16()? as usize,
+            _ => {
+                return Err(scanner
+                    .invalid(1, format!("unknown constant pool tag at {}: {}", i, tag))
+                    .within(format!("constant_pool[{}]", i)))
+            }
+        };
+        scanner
+            .take(length)
+            .map_err(|error| error.within(format!("constant_pool[{}]", i)))?;
+        entries.push(Some(&scanner.bytes[start..scanner.position]));
+
+        if tag == 5 || tag == 6 {
+            if i + 1 >= constant_pool_count {
+                return Err(JvmParseError::InvalidFormat(format!(
+                    "long/double constant is missing second constant pool index {}",
+                    i + 1
+                ))
+                .at(scanner.offset() as u64));
+            }
+            entries.push(None);
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    Ok(ConstantPoolRef { entries })
+}
+
+fn scan_members<'a>(scanner: &mut Scanner<'a>, name: &str) -> JvmParseResult<MembersRef<'a>> {
+    let count = scanner.u16()?;
+    let start = scanner.clone();
+    for i in 0..count {
+        scanner
+            .take(6)
+            .and_then(|_| scan_attributes(scanner))
+            .map_err(|error| error.within(format!("{}[{}]", name, i)))?;
+    }
+    Ok(MembersRef {
+        scanner: start,
+        count,
+    })
+}
+
+fn scan_attributes<'a>(scanner: &mut Scanner<'a>) -> JvmParseResult<AttributesRef<'a>> {
+    let count = scanner.u16()?;
+    let start = scanner.clone();
+    for _ in 0..count {
+        next_attribute(scanner).map_err(|error| error.within("attributes"))?;
+    }
+    Ok(AttributesRef {
+        scanner: start,
+        count,
+    })
+}
+
+fn next_attribute<'a>(scanner: &mut Scanner<'a>) -> JvmParseResult<AttributeRef<'a>> {
+    let name_index = ConstantIndex(scanner.u16()?);
+    let length = scanner.u32()?;
+    let offset = scanner.offset();
+    check_limit(
+        "attribute length",
+        length as u64,
+        scanner.limits.max_attribute_length as u64,
+    )
+    .map_err(|error| error.at(offset as u64 - 4))?;
+    let info = scanner.take(length as usize)?;
+    Ok(AttributeRef {
+        name_index,
+        info,
+        offset,
+        limits: scanner.limits,
+    })
+}
+
+/// Class file borrowing its contents from the parsed bytes.
+#[derive(Debug, Clone)]
+pub struct ClassFileRef<'a> {
+    bytes: &'a [u8],
+    minor_version: u16,
+    major_version: u16,
+    constants: ConstantPoolRef<'a>,
+    access_flags: ClassAccessFlags,
+    this_class: ConstantIndex,
+    super_class: ConstantIndex,
+    /// Class constant indices as u16 each.
+    interfaces: &'a [u8],
+    fields: MembersRef<'a>,
+    methods: MembersRef<'a>,
+    attributes: AttributesRef<'a>,
+    limits: ParseLimits,
+}
+
+impl<'a> ClassFileRef<'a> {
+    /// Bytes of the class file, without trailing bytes of the parsed slice.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+
+    pub fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    pub fn access_flags(&self) -> ClassAccessFlags {
+        self.access_flags
+    }
+
+    pub fn constant_pool(&self) -> &ConstantPoolRef<'a> {
+        &self.constants
+    }
+
+    pub fn this_class(&self) -> ConstantIndex {
+        self.this_class
+    }
+
+    pub fn super_class(&self) -> ConstantIndex {
+        self.super_class
+    }
+
+    /// Internal name of the class like `java/lang/String`.
+    pub fn class_name(&self) -> JvmParseResult<Cow<'a, str>> {
+        self.constants.resolve_class_name(self.this_class)
+    }
+
+    pub fn interfaces(&self) -> impl ExactSizeIterator<Item = ConstantIndex> + 'a {
+        self.interfaces
+            .chunks(2)
+            .map(|index| ConstantIndex(BigEndian::read_u16(index)))
+    }
+
+    pub fn fields(&self) -> impl ExactSizeIterator<Item = FieldRef<'a>> {
+        self.fields.clone().map(|member| FieldRef {
+            access_flags: FieldAccessFlags::from_bits_retain(member.access_flags),
+            name_index: member.name_index,
+            descriptor_index: member.descriptor_index,
+            attributes: member.attributes,
+        })
+    }
+
+    pub fn methods(&self) -> impl ExactSizeIterator<Item = MethodRef<'a>> {
+        self.methods.clone().map(|member| MethodRef {
+            access_flags: MethodAccessFlags::from_bits_retain(member.access_flags),
+            name_index: member.name_index,
+            descriptor_index: member.descriptor_index,
+            attributes: member.attributes,
+        })
+    }
+
+    pub fn attributes(&self) -> AttributesRef<'a> {
+        self.attributes.clone()
+    }
+
+    /// Decodes the constants, members and attributes into an owned
+    /// `ClassFile`, like `parse_class_file` does for the same bytes.
+    pub fn to_class_file(&self) -> JvmParseResult<ClassFile> {
+        let class_name = self.class_name().ok().map(Cow::into_owned);
+        self.decode().map_err(|error| error.in_class(class_name))
+    }
+
+    fn decode(&self) -> JvmParseResult<ClassFile> {
+        let options = ParseOptions {
+            lenient: false,
+            limits: self.limits,
+        };
+        let context = ParseContext::new(&options);
+        let cpool = ConstantPool::new(self.constants.decode(self.bytes)?);
+        let fields = self
+            .fields()
+            .enumerate()
+            .map(|(i, field)| {
+                Ok(Field {
+                    access_flags: field.access_flags,
+                    name_index: field.name_index,
+                    descriptor_index: field.descriptor_index,
+                    attributes: decode_attributes(field.attributes, &cpool, &context)
+                        .map_err(|error| error.within(format!("fields[{}]", i)))?,
+                })
+            })
+            .collect::<JvmParseResult<_>>()?;
+        let methods = self
+            .methods()
+            .enumerate()
+            .map(|(i, method)| {
+                Ok(Method {
+                    access_flags: method.access_flags,
+                    name_index: method.name_index,
+                    descriptor_index: method.descriptor_index,
+                    attributes: decode_attributes(method.attributes, &cpool, &context)
+                        .map_err(|error| error.within(format!("methods[{}]", i)))?,
+                })
+            })
+            .collect::<JvmParseResult<_>>()?;
+        let attributes = decode_attributes(self.attributes(), &cpool, &context)?;
+        Ok(ClassFile {
+            magic: 0xCAFEBABE,
+            minor_version: self.minor_version,
+            major_version: self.major_version,
+            constants: cpool,
+            access_flags: self.access_flags,
+            this_class: self.this_class,
+            super_class: self.super_class,
+            interfaces: self.interfaces().collect(),
+            fields,
+            methods,
+            attributes,
+        })
+    }
+}
+
+/// Attributes parsed from their borrowed info bytes.
+fn decode_attributes(
+    attributes: AttributesRef<'_>,
+    cpool: &ConstantPool,
+    context: &ParseContext,
+) -> JvmParseResult<Attributes> {
+    let (name_indices, attributes) = attributes
+        .map(|attribute| {
+            let end = (attribute.offset + attribute.info.len()) as u64;
+            let info = attribute.info.to_vec();
+            let decoded = decode_attribute(attribute.name_index, info, end, context, cpool)?;
+            Ok((attribute.name_index, decoded))
+        })
+        .collect::<JvmParseResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    Ok(Attributes::parsed(attributes, name_indices))
+}
+
+/// Constant pool which keeps the bytes of its constants.
+#[derive(Debug, Clone)]
+pub struct ConstantPoolRef<'a> {
+    /// Bytes of each constant including the tag, `None` for the unusable
+    /// slots after long and double constants.
+    entries: Vec<Option<&'a [u8]>>,
+}
+
+impl<'a> ConstantPoolRef<'a> {
+    fn entry(&self, index: ConstantIndex) -> JvmParseResult<&'a [u8]> {
+        (index.0 as usize)
+            .checked_sub(1)
+            .and_then(|i| self.entries.get(i).copied().flatten())
+            .ok_or(JvmParseError::MissingConstant(index))
+    }
+
+    /// Decodes the constant at the given index.
+    pub fn get(&self, index: ConstantIndex) -> JvmParseResult<Constant> {
+        let mut entry = self.entry(index)?;
+        parse_constant(&mut entry, index.0)
+    }
+
+    /// Decodes all constants, with errors located in `class_bytes`.
+    fn decode(&self, class_bytes: &[u8]) -> JvmParseResult<Vec<Constant>> {
+        (1..)
+            .zip(&self.entries)
+            .map(|(i, entry)| match *entry {
+                Some(bytes) => {
+                    let mut reader = bytes;
+                    parse_constant(&mut reader, i).map_err(|error| {
+                        let offset = reader.as_ptr() as usize - class_bytes.as_ptr() as usize;
+                        error
+                            .at(offset as u64)
+                            .within(format!("constant_pool[{}]", i))
+                    })
+                }
+                None => Ok(Constant::InvalidConstant),
+            })
+            .collect()
+    }
+
+    /// Modified UTF-8 bytes of a Utf8 constant.
+    pub fn utf8_bytes(&self, index: ConstantIndex) -> JvmParseResult<&'a [u8]> {
+        match self.entry(index)? {
+            [1, _, _, bytes @ ..] => Ok(bytes),
+            _ => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Utf8".into(),
+            )),
+        }
+    }
+
+    /// Value of a Utf8 constant, only copied if it is not valid UTF-8 as is.
+    /// Fails for strings with unpaired surrogates like
+    /// `ConstantPool::resolve_utf8`.
+    pub fn resolve_utf8(&self, index: ConstantIndex) -> JvmParseResult<Cow<'a, str>> {
+        decode_modified_utf8(self.utf8_bytes(index)?).ok_or_else(|| {
+            JvmParseError::InvalidFormat(format!("invalid string for constant {}", index.0))
+        })
+    }
+
+    pub fn resolve_class(&self, index: ConstantIndex) -> JvmParseResult<ConstantIndex> {
+        match self.entry(index)? {
+            [7, name_index @ ..] => Ok(ConstantIndex(BigEndian::read_u16(name_index))),
+            _ => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected class".into(),
+            )),
+        }
+    }
+
+    /// Internal name of a class constant.
+    pub fn resolve_class_name(&self, index: ConstantIndex) -> JvmParseResult<Cow<'a, str>> {
+        self.resolve_utf8(self.resolve_class(index)?)
+    }
+}
+
+/// Fields or methods, which are decoded while iterating.
+#[derive(Debug, Clone)]
+struct MembersRef<'a> {
+    scanner: Scanner<'a>,
+    count: u16,
+}
+
+struct MemberInfo<'a> {
+    access_flags: u16,
+    name_index: ConstantIndex,
+    descriptor_index: ConstantIndex,
+    attributes: AttributesRef<'a>,
+}
+
+impl<'a> Iterator for MembersRef<'a> {
+    type Item = MemberInfo<'a>;
+
+    fn next(&mut self) -> Option<MemberInfo<'a>> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        // the layout was checked by `scan_members`
+        let header = self.scanner.take(6).ok()?;
+        Some(MemberInfo {
+            access_flags: BigEndian::read_u16(&header[0..2]),
+            name_index: ConstantIndex(BigEndian::read_u16(&header[2..4])),
+            descriptor_index: ConstantIndex(BigEndian::read_u16(&header[4..6])),
+            attributes: scan_attributes(&mut self.scanner).ok()?,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count as usize, Some(self.count as usize))
+    }
+}
+
+impl ExactSizeIterator for MembersRef<'_> {}
+
+#[derive(Debug, Clone)]
+pub struct FieldRef<'a> {
+    pub access_flags: FieldAccessFlags,
+    pub name_index: ConstantIndex,
+    pub descriptor_index: ConstantIndex,
+    pub attributes: AttributesRef<'a>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodRef<'a> {
+    pub access_flags: MethodAccessFlags,
+    pub name_index: ConstantIndex,
+    pub descriptor_index: ConstantIndex,
+    pub attributes: AttributesRef<'a>,
+}
+
+impl<'a> MethodRef<'a> {
+    /// Body of the method, `None` for abstract and native methods.
+    pub fn code(&self, cpool: &ConstantPoolRef<'a>) -> JvmParseResult<Option<CodeRef<'a>>> {
+        self.attributes
+            .clone()
+            .find_named(cpool, "Code")
+            .map(|attribute| attribute.code())
+            .transpose()
+    }
+}
+
+/// Attributes of a class, member or code, which are decoded while iterating.
+#[derive(Debug, Clone)]
+pub struct AttributesRef<'a> {
+    scanner: Scanner<'a>,
+    count: u16,
+}
+
+impl<'a> AttributesRef<'a> {
+    /// First attribute with the given name.
+    pub fn find_named(
+        mut self,
+        cpool: &ConstantPoolRef<'a>,
+        name: &str,
+    ) -> Option<AttributeRef<'a>> {
+        // names of known attributes are ASCII, which has the same encoding in
+        // Modified UTF-8
+        self.find(|attribute| cpool.utf8_bytes(attribute.name_index).ok() == Some(name.as_bytes()))
+    }
+}
+
+impl<'a> Iterator for AttributesRef<'a> {
+    type Item = AttributeRef<'a>;
+
+    fn next(&mut self) -> Option<AttributeRef<'a>> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        // the layout was checked by `scan_attributes`
+        next_attribute(&mut self.scanner).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count as usize, Some(self.count as usize))
+    }
+}
+
+impl ExactSizeIterator for AttributesRef<'_> {}
+
+/// Attribute with its undecoded info bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeRef<'a> {
+    pub name_index: ConstantIndex,
+    pub info: &'a [u8],
+    /// Offset of the info in the class file.
+    offset: usize,
+    limits: ParseLimits,
+}
+
+impl<'a> AttributeRef<'a> {
+    pub fn name(&self, cpool: &ConstantPoolRef<'a>) -> JvmParseResult<Cow<'a, str>> {
+        cpool.resolve_utf8(self.name_index)
+    }
+
+    /// Offset of the info in the class file.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Decodes the info as `Code` attribute.
+    pub fn code(&self) -> JvmParseResult<CodeRef<'a>> {
+        self.scan_code()
+            .map_err(|error| error.within("attributes[Code]"))
+    }
+
+    fn scan_code(&self) -> JvmParseResult<CodeRef<'a>> {
+        let mut scanner = Scanner::new(self.info, self.offset, self.limits);
+        let max_stack = scanner.u16()?;
+        let max_locals = scanner.u16()?;
+        let code_length = scanner.u32()?;
+        check_limit(
+            "code length",
+            code_length as u64,
+            self.limits.max_code_length as u64,
+        )
+        .map_err(|error| error.at(scanner.offset() as u64 - 4))?;
+        let code = scanner.take(code_length as usize)?;
+        let exception_table_length = scanner.u16()?;
+        let exception_table = scanner.take(exception_table_length as usize * 8)?;
+        let attributes = scan_attributes(&mut scanner)?;
+        let trailing = self.info.len() - scanner.position;
+        if trailing > 0 {
+            return Err(scanner.invalid(0, format!("{} trailing bytes", trailing)));
+        }
+        Ok(CodeRef {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        })
+    }
+}
+
+/// Body of a method with the bytecode borrowed from the class file.
+#[derive(Debug, Clone)]
+pub struct CodeRef<'a> {
+    pub max_stack: u16,
+    pub max_locals: u16,
+    /// Bytecode, which can be decoded with `rustjvm_opcode::disasm`.
+    pub code: &'a [u8],
+    exception_table: &'a [u8],
+    pub attributes: AttributesRef<'a>,
+}
+
+impl<'a> CodeRef<'a> {
+    pub fn exception_table(&self) -> impl ExactSizeIterator<Item = ExceptionTableEntry> + 'a {
+        self.exception_table
+            .chunks(8)
+            .map(|entry| ExceptionTableEntry {
+                start_pc: BigEndian::read_u16(&entry[0..2]),
+                end_pc: BigEndian::read_u16(&entry[2..4]),
+                handler_pc: BigEndian::read_u16(&entry[4..6]),
+                catch_type: BigEndian::read_u16(&entry[6..8]),
+            })
+    }
+}
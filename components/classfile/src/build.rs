@@ -0,0 +1,572 @@
+use std::collections::HashMap;
+
+use rustjvm_opcode::Bytecode;
+
+use crate::error::{JvmWriteError, JvmWriteResult};
+use crate::model::attributes::{Code, ConstantValue, ExceptionTableEntry};
+use crate::model::constants::{Constant, ConstantIndex, ConstantPool};
+use crate::model::{
+    Attribute, Attributes, ClassAccessFlags, ClassFile, Field, FieldAccessFlags, Method,
+    MethodAccessFlags, ReferenceKind,
+};
+
+/// Identity of a constant, floats are compared by their bits.
+#[derive(PartialEq, Eq, Hash)]
+enum ConstantKey {
+    Class(ConstantIndex),
+    Fieldref(ConstantIndex, ConstantIndex),
+    Methodref(ConstantIndex, ConstantIndex),
+    InterfaceMethodref(ConstantIndex, ConstantIndex),
+    String(ConstantIndex),
+    Integer(i32),
+    Float(u32),
+    Long(i64),
+    Double(u64),
+    NameAndType(ConstantIndex, ConstantIndex),
+    Utf8(String),
+    MethodHandle(u8, ConstantIndex),
+    MethodType(ConstantIndex),
+    Dynamic(u16, ConstantIndex),
+    InvokeDynamic(u16, ConstantIndex),
+    Module(ConstantIndex),
+    Package(ConstantIndex),
+}
+
+/// Constant pool under construction.
+///
+/// Equal constants are only added once. Indices returned after the pool has
+/// overflowed are meaningless, the overflow is reported by `build`.
+#[derive(Default)]
+pub struct ConstantPoolBuilder {
+    constants: Vec<Constant>,
+    indices: HashMap<ConstantKey, ConstantIndex>,
+}
+
+impl ConstantPoolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of used constant pool slots.
+    pub fn len(&self) -> usize {
+        self.constants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constants.is_empty()
+    }
+
+    fn intern(&mut self, key: ConstantKey, constant: Constant) -> ConstantIndex {
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+
+        let index = ConstantIndex((self.constants.len() + 1) as u16);
+        let wide = matches!(constant, Constant::Long(_) | Constant::Double(_));
+        self.constants.push(constant);
+        if wide {
+            self.constants.push(Constant::InvalidConstant);
+        }
+        self.indices.insert(key, index);
+        index
+    }
+
+    pub fn utf8(&mut self, value: &str) -> ConstantIndex {
+        self.intern(
+            ConstantKey::Utf8(value.to_string()),
+            Constant::Utf8(value.into()),
+        )
+    }
+
+    /// Class constant for an internal name like `java/lang/Object`.
+    pub fn class(&mut self, name: &str) -> ConstantIndex {
+        let name_index = self.utf8(name);
+        self.intern(
+            ConstantKey::Class(name_index),
+            Constant::Class { name_index },
+        )
+    }
+
+    pub fn string(&mut self, value: &str) -> ConstantIndex {
+        let string_index = self.utf8(value);
+        self.intern(
+            ConstantKey::String(string_index),
+            Constant::String(string_index),
+        )
+    }
+
+    pub fn integer(&mut self, value: i32) -> ConstantIndex {
+        self.intern(ConstantKey::Integer(value), Constant::Integer(value))
+    }
+
+    pub fn float(&mut self, value: f32) -> ConstantIndex {
+        self.intern(ConstantKey::Float(value.to_bits()), Constant::Float(value))
+    }
+
+    pub fn long(&mut self, value: i64) -> ConstantIndex {
+        self.intern(ConstantKey::Long(value), Constant::Long(value))
+    }
+
+    pub fn double(&mut self, value: f64) -> ConstantIndex {
+        self.intern(
+            ConstantKey::Double(value.to_bits()),
+            Constant::Double(value),
+        )
+    }
+
+    pub fn name_and_type(&mut self, name: &str, descriptor: &str) -> ConstantIndex {
+        let name_index = self.utf8(name);
+        let descriptor_index = self.utf8(descriptor);
+        self.intern(
+            ConstantKey::NameAndType(name_index, descriptor_index),
+            Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            },
+        )
+    }
+
+    pub fn field_ref(&mut self, class: &str, name: &str, descriptor: &str) -> ConstantIndex {
+        let class_index = self.class(class);
+        let name_and_type_index = self.name_and_type(name, descriptor);
+        self.intern(
+            ConstantKey::Fieldref(class_index, name_and_type_index),
+            Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            },
+        )
+    }
+
+    pub fn method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> ConstantIndex {
+        let class_index = self.class(class);
+        let name_and_type_index = self.name_and_type(name, descriptor);
+        self.intern(
+            ConstantKey::Methodref(class_index, name_and_type_index),
+            Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            },
+        )
+    }
+
+    pub fn interface_method_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> ConstantIndex {
+        let class_index = self.class(class);
+        let name_and_type_index = self.name_and_type(name, descriptor);
+        self.intern(
+            ConstantKey::InterfaceMethodref(class_index, name_and_type_index),
+            Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            },
+        )
+    }
+
+    /// Method handle for a field or method reference constant.
+    pub fn method_handle(
+        &mut self,
+        reference_kind: ReferenceKind,
+        reference_index: ConstantIndex,
+    ) -> ConstantIndex {
+        self.intern(
+            ConstantKey::MethodHandle(reference_kind as u8, reference_index),
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            },
+        )
+    }
+
+    pub fn method_type(&mut self, descriptor: &str) -> ConstantIndex {
+        let descriptor_index = self.utf8(descriptor);
+        self.intern(
+            ConstantKey::MethodType(descriptor_index),
+            Constant::MethodType { descriptor_index },
+        )
+    }
+
+    /// Dynamically-computed constant using the bootstrap method with the given
+    /// index in the `BootstrapMethods` attribute.
+    pub fn dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> ConstantIndex {
+        let name_and_type_index = self.name_and_type(name, descriptor);
+        self.intern(
+            ConstantKey::Dynamic(bootstrap_method_attr_index, name_and_type_index),
+            Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            },
+        )
+    }
+
+    /// Call site of an `invokedynamic` instruction using the bootstrap method
+    /// with the given index in the `BootstrapMethods` attribute.
+    pub fn invoke_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> ConstantIndex {
+        let name_and_type_index = self.name_and_type(name, descriptor);
+        self.intern(
+            ConstantKey::InvokeDynamic(bootstrap_method_attr_index, name_and_type_index),
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            },
+        )
+    }
+
+    /// Module constant for a name like `java.base`.
+    pub fn module(&mut self, name: &str) -> ConstantIndex {
+        let name_index = self.utf8(name);
+        self.intern(
+            ConstantKey::Module(name_index),
+            Constant::Module { name_index },
+        )
+    }
+
+    /// Package constant for an internal name like `java/lang`.
+    pub fn package(&mut self, name: &str) -> ConstantIndex {
+        let name_index = self.utf8(name);
+        self.intern(
+            ConstantKey::Package(name_index),
+            Constant::Package { name_index },
+        )
+    }
+
+    /// Adds the names of the attribute and its nested attributes.
+    fn attribute_names(&mut self, attribute: &Attribute) {
+        if let Some(name) = attribute.known_name() {
+            self.utf8(name);
+        }
+        match attribute {
+            Attribute::Code(code) => {
+                for attribute in &code.attributes {
+                    self.attribute_names(attribute);
+                }
+            }
+            Attribute::Record(components) => {
+                for attribute in components.iter().flat_map(|c| &c.attributes) {
+                    self.attribute_names(attribute);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn build(self) -> JvmWriteResult<ConstantPool> {
+        if self.constants.len() >= u16::MAX as usize {
+            return Err(JvmWriteError::InvalidFormat(format!(
+                "too many constants: {}",
+                self.constants.len()
+            )));
+        }
+        Ok(ConstantPool::new(self.constants))
+    }
+}
+
+/// Builds a class file from names and descriptors.
+///
+/// The constants needed for names, descriptors and attribute names are added
+/// to the constant pool on the fly. Instructions reference constants by index,
+/// these can be added through `constants`.
+pub struct ClassBuilder {
+    minor_version: u16,
+    major_version: u16,
+    constants: ConstantPoolBuilder,
+    access_flags: ClassAccessFlags,
+    this_class: ConstantIndex,
+    super_class: Option<ConstantIndex>,
+    interfaces: Vec<ConstantIndex>,
+    fields: Vec<Field>,
+    methods: Vec<Method>,
+    attributes: Attributes,
+}
+
+impl ClassBuilder {
+    /// Starts a class for Java 8 with `java/lang/Object` as super class.
+    pub fn new(access_flags: ClassAccessFlags, name: &str) -> Self {
+        let mut constants = ConstantPoolBuilder::new();
+        let this_class = constants.class(name);
+        Self {
+            minor_version: 0,
+            major_version: 52,
+            constants,
+            access_flags,
+            this_class,
+            super_class: None,
+            interfaces: vec![],
+            fields: vec![],
+            methods: vec![],
+            attributes: Attributes::new(),
+        }
+    }
+
+    pub fn constants(&mut self) -> &mut ConstantPoolBuilder {
+        &mut self.constants
+    }
+
+    pub fn version(&mut self, major_version: u16, minor_version: u16) -> &mut Self {
+        self.major_version = major_version;
+        self.minor_version = minor_version;
+        self
+    }
+
+    pub fn super_class(&mut self, name: &str) -> &mut Self {
+        self.super_class = Some(self.constants.class(name));
+        self
+    }
+
+    pub fn interface(&mut self, name: &str) -> &mut Self {
+        let interface = self.constants.class(name);
+        self.interfaces.push(interface);
+        self
+    }
+
+    pub fn source_file(&mut self, name: &str) -> &mut Self {
+        let sourcefile_index = self.constants.utf8(name);
+        self.attribute(Attribute::SourceFile(sourcefile_index))
+    }
+
+    pub fn attribute(&mut self, attribute: Attribute) -> &mut Self {
+        self.constants.attribute_names(&attribute);
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn field(
+        &mut self,
+        access_flags: FieldAccessFlags,
+        name: &str,
+        descriptor: &str,
+    ) -> FieldBuilder<'_> {
+        let field = Field {
+            access_flags,
+            name_index: self.constants.utf8(name),
+            descriptor_index: self.constants.utf8(descriptor),
+            attributes: Attributes::new(),
+        };
+        self.fields.push(field);
+        FieldBuilder {
+            index: self.fields.len() - 1,
+            class: self,
+        }
+    }
+
+    pub fn method(
+        &mut self,
+        access_flags: MethodAccessFlags,
+        name: &str,
+        descriptor: &str,
+    ) -> MethodBuilder<'_> {
+        let method = Method {
+            access_flags,
+            name_index: self.constants.utf8(name),
+            descriptor_index: self.constants.utf8(descriptor),
+            attributes: Attributes::new(),
+        };
+        self.methods.push(method);
+        MethodBuilder {
+            index: self.methods.len() - 1,
+            class: self,
+        }
+    }
+
+    pub fn build(mut self) -> JvmWriteResult<ClassFile> {
+        let super_class = match self.super_class {
+            Some(super_class) => super_class,
+            None => self.constants.class("java/lang/Object"),
+        };
+        Ok(ClassFile {
+            magic: 0xCAFEBABE,
+            minor_version: self.minor_version,
+            major_version: self.major_version,
+            constants: self.constants.build()?,
+            access_flags: self.access_flags,
+            this_class: self.this_class,
+            super_class,
+            interfaces: self.interfaces,
+            fields: self.fields,
+            methods: self.methods,
+            attributes: self.attributes,
+        })
+    }
+}
+
+/// Adds attributes to a field which is already part of the class.
+pub struct FieldBuilder<'a> {
+    class: &'a mut ClassBuilder,
+    index: usize,
+}
+
+impl<'a> FieldBuilder<'a> {
+    pub fn constants(&mut self) -> &mut ConstantPoolBuilder {
+        &mut self.class.constants
+    }
+
+    /// Initial value of a static field, which must be a constant of the
+    /// field's type.
+    pub fn constant_value(&mut self, constantvalue_index: ConstantIndex) -> &mut Self {
+        self.attribute(Attribute::ConstantValue(ConstantValue {
+            constantvalue_index,
+        }))
+    }
+
+    pub fn attribute(&mut self, attribute: Attribute) -> &mut Self {
+        self.class.constants.attribute_names(&attribute);
+        self.class.fields[self.index].attributes.push(attribute);
+        self
+    }
+}
+
+/// Adds the body and attributes to a method which is already part of the class.
+pub struct MethodBuilder<'a> {
+    class: &'a mut ClassBuilder,
+    index: usize,
+}
+
+impl<'a> MethodBuilder<'a> {
+    pub fn constants(&mut self) -> &mut ConstantPoolBuilder {
+        &mut self.class.constants
+    }
+
+    /// Sets the method body, replacing any previous one, and returns a builder
+    /// for its exception handlers and attributes.
+    pub fn code(&mut self, max_stack: u16, max_locals: u16, code: Bytecode) -> CodeBuilder<'_> {
+        let class = &mut *self.class;
+        class.constants.utf8("Code");
+        let attributes = &mut class.methods[self.index].attributes;
+        attributes.retain(|attribute| !matches!(attribute, Attribute::Code(_)));
+        attributes.push(Attribute::Code(Code {
+            max_stack,
+            max_locals,
+            code,
+            exception_table: vec![],
+            attributes: Attributes::new(),
+        }));
+        let code = match attributes.last_mut() {
+            Some(Attribute::Code(code)) => code,
+            _ => unreachable!(),
+        };
+        CodeBuilder {
+            constants: &mut class.constants,
+            code,
+        }
+    }
+
+    /// Adds a checked exception to the throws clause.
+    pub fn exception(&mut self, name: &str) -> &mut Self {
+        let exception = self.class.constants.class(name);
+        let attributes = &mut self.class.methods[self.index].attributes;
+        let exceptions = attributes.iter_mut().find_map(|attribute| match attribute {
+            Attribute::Exceptions(exceptions) => Some(exceptions),
+            _ => None,
+        });
+        match exceptions {
+            Some(exceptions) => exceptions.push(exception),
+            None => {
+                self.class.constants.utf8("Exceptions");
+                self.class.methods[self.index]
+                    .attributes
+                    .push(Attribute::Exceptions(vec![exception]));
+            }
+        }
+        self
+    }
+
+    pub fn attribute(&mut self, attribute: Attribute) -> &mut Self {
+        self.class.constants.attribute_names(&attribute);
+        self.class.methods[self.index].attributes.push(attribute);
+        self
+    }
+}
+
+/// Adds exception handlers and attributes to the body of a method.
+pub struct CodeBuilder<'a> {
+    constants: &'a mut ConstantPoolBuilder,
+    code: &'a mut Code,
+}
+
+impl<'a> CodeBuilder<'a> {
+    pub fn constants(&mut self) -> &mut ConstantPoolBuilder {
+        self.constants
+    }
+
+    /// Adds an exception handler for the range `start_pc..end_pc`. Without
+    /// catch type the handler catches all exceptions.
+    pub fn exception_handler(
+        &mut self,
+        start_pc: u16,
+        end_pc: u16,
+        handler_pc: u16,
+        catch_type: Option<&str>,
+    ) -> &mut Self {
+        let catch_type = catch_type.map_or(0, |name| self.constants.class(name).0);
+        self.code.exception_table.push(ExceptionTableEntry {
+            start_pc,
+            end_pc,
+            handler_pc,
+            catch_type,
+        });
+        self
+    }
+
+    pub fn attribute(&mut self, attribute: Attribute) -> &mut Self {
+        self.constants.attribute_names(&attribute);
+        self.code.attributes.push(attribute);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning() {
+        let mut constants = ConstantPoolBuilder::new();
+        let method = constants.method_ref("Foo", "bar", "()V");
+        assert_eq!(constants.method_ref("Foo", "bar", "()V"), method);
+        assert_eq!(constants.utf8("Foo"), ConstantIndex(1));
+        assert_eq!(constants.class("Foo"), ConstantIndex(2));
+        assert_ne!(constants.field_ref("Foo", "bar", "()V"), method);
+        assert_ne!(constants.utf8("bar"), constants.string("bar"));
+        assert_eq!(constants.len(), 8);
+    }
+
+    #[test]
+    fn wide_constants() {
+        let mut constants = ConstantPoolBuilder::new();
+        assert_eq!(constants.long(1), ConstantIndex(1));
+        assert_eq!(constants.double(1.0), ConstantIndex(3));
+        assert_eq!(constants.integer(1), ConstantIndex(5));
+        assert_eq!(constants.long(1), ConstantIndex(1));
+        assert_eq!(constants.len(), 5);
+    }
+
+    #[test]
+    fn floats_by_bits() {
+        let mut constants = ConstantPoolBuilder::new();
+        assert_ne!(constants.float(0.0), constants.float(-0.0));
+        assert_eq!(constants.double(f64::NAN), constants.double(f64::NAN));
+    }
+
+    #[test]
+    fn too_many_constants() {
+        let mut constants = ConstantPoolBuilder::new();
+        for i in 0..u16::MAX as i32 {
+            constants.integer(i);
+        }
+        assert!(constants.build().is_err());
+    }
+}
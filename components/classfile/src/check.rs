@@ -0,0 +1,741 @@
+//! Format checking of parsed class files (JVMS 4.8).
+//!
+//! Parsing only rejects class files which can not be read at all. The checks
+//! here cover the static constraints on the constant pool, the access flags
+//! and the members, but not the verification of the code.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::descriptor::{
+    is_internal_class_name, is_unqualified_method_name, is_unqualified_name,
+    parse_field_descriptor, parse_method_descriptor, MethodDescriptor,
+};
+use crate::error::JvmParseResult;
+use crate::model::constants::{Constant, ConstantIndex};
+use crate::model::{
+    Attribute, ClassAccessFlags, ClassFile, FieldAccessFlags, MethodAccessFlags, ReferenceKind,
+};
+
+/// Part of the class file a violation was found in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    Class,
+    Constant(ConstantIndex),
+    /// Index into the fields of the class.
+    Field(usize),
+    /// Index into the methods of the class.
+    Method(usize),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Class => f.write_str("class"),
+            Location::Constant(index) => write!(f, "constant {:?}", index),
+            Location::Field(index) => write!(f, "field {}", index),
+            Location::Method(index) => write!(f, "method {}", index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatViolation {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for FormatViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Checks the class file against the format constraints of JVMS 4.8 and
+/// returns all violations found, an empty list for a well-formed class.
+pub fn check_format(class_file: &ClassFile) -> Vec<FormatViolation> {
+    let mut checker = Checker {
+        class_file,
+        violations: vec![],
+    };
+    checker.check_header();
+    checker.check_constants();
+    checker.check_class();
+    checker.check_fields();
+    checker.check_methods();
+    checker.violations
+}
+
+/// First class file version with interface method references in method
+/// handles and static interface methods.
+const JAVA_8: u16 = 52;
+
+struct Checker<'a> {
+    class_file: &'a ClassFile,
+    violations: Vec<FormatViolation>,
+}
+
+impl<'a> Checker<'a> {
+    fn report(&mut self, location: Location, message: String) {
+        self.violations.push(FormatViolation { location, message });
+    }
+
+    /// Reports a failed resolution, `None` in that case.
+    fn resolved<T>(&mut self, location: &Location, result: JvmParseResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                let message = error.to_string();
+                self.report(location.clone(), message);
+                None
+            }
+        }
+    }
+
+    fn check_header(&mut self) {
+        if self.class_file.magic != 0xCAFE_BABE {
+            self.report(
+                Location::Class,
+                format!("invalid magic: {:#x}", self.class_file.magic),
+            );
+        }
+    }
+
+    fn check_constants(&mut self) {
+        let cpool = &self.class_file.constants;
+        for (index, constant) in cpool.all() {
+            let location = Location::Constant(index);
+            match constant {
+                Constant::Class { name_index } => {
+                    if let Some(name) = self.resolved(&location, cpool.resolve_utf8(*name_index)) {
+                        let valid = if name.starts_with('[') {
+                            parse_field_descriptor(name).is_some()
+                        } else {
+                            is_internal_class_name(name)
+                        };
+                        if !valid {
+                            self.report(location, format!("invalid class name: {}", name));
+                        }
+                    }
+                }
+                Constant::Fieldref {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    self.resolved(&location, cpool.resolve_class(*class_index));
+                    if let Some(name_and_type) =
+                        self.resolved(&location, cpool.resolve_name_and_type(*name_and_type_index))
+                    {
+                        self.check_field_name(&location, name_and_type.name);
+                        self.check_field_descriptor(&location, name_and_type.descriptor);
+                    }
+                }
+                Constant::Methodref {
+                    class_index,
+                    name_and_type_index,
+                }
+                | Constant::InterfaceMethodref {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    self.resolved(&location, cpool.resolve_class(*class_index));
+                    if let Some(name_and_type) =
+                        self.resolved(&location, cpool.resolve_name_and_type(*name_and_type_index))
+                    {
+                        self.check_method_ref_name(&location, name_and_type.name);
+                        if let Some(descriptor) =
+                            self.check_method_descriptor(&location, name_and_type.descriptor)
+                        {
+                            if name_and_type.name == "<init>" && descriptor.rty.is_some() {
+                                self.report(location, "constructor must return void".into());
+                            }
+                        }
+                    }
+                }
+                Constant::String(string_index) => {
+                    // string values may contain unpaired surrogates
+                    self.resolved(&location, cpool.resolve_java_string(*string_index));
+                }
+                Constant::NameAndType {
+                    name_index,
+                    descriptor_index,
+                } => {
+                    self.resolved(&location, cpool.resolve_utf8(*name_index));
+                    self.resolved(&location, cpool.resolve_utf8(*descriptor_index));
+                }
+                Constant::MethodHandle {
+                    reference_kind,
+                    reference_index,
+                } => self.check_method_handle(&location, *reference_kind, *reference_index),
+                Constant::MethodType { descriptor_index } => {
+                    if let Some(descriptor) =
+                        self.resolved(&location, cpool.resolve_utf8(*descriptor_index))
+                    {
+                        self.check_method_descriptor(&location, descriptor);
+                    }
+                }
+                Constant::Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } => {
+                    self.check_bootstrap_method(&location, *bootstrap_method_attr_index);
+                    if let Some(name_and_type) =
+                        self.resolved(&location, cpool.resolve_name_and_type(*name_and_type_index))
+                    {
+                        self.check_field_name(&location, name_and_type.name);
+                        self.check_field_descriptor(&location, name_and_type.descriptor);
+                    }
+                }
+                Constant::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } => {
+                    self.check_bootstrap_method(&location, *bootstrap_method_attr_index);
+                    if let Some(name_and_type) =
+                        self.resolved(&location, cpool.resolve_name_and_type(*name_and_type_index))
+                    {
+                        self.check_field_name(&location, name_and_type.name);
+                        self.check_method_descriptor(&location, name_and_type.descriptor);
+                    }
+                }
+                Constant::Module { name_index } | Constant::Package { name_index } => {
+                    self.resolved(&location, cpool.resolve_utf8(*name_index));
+                    if !self
+                        .class_file
+                        .access_flags
+                        .contains(ClassAccessFlags::MODULE)
+                    {
+                        self.report(
+                            location,
+                            "module and package constants are only allowed in modules".into(),
+                        );
+                    }
+                }
+                Constant::Integer(_)
+                | Constant::Float(_)
+                | Constant::Long(_)
+                | Constant::Double(_)
+                | Constant::Utf8(_)
+                | Constant::InvalidConstant => {}
+            }
+        }
+    }
+
+    fn check_field_name(&mut self, location: &Location, name: &str) {
+        if !is_unqualified_name(name) {
+            self.report(location.clone(), format!("invalid name: {}", name));
+        }
+    }
+
+    fn check_method_ref_name(&mut self, location: &Location, name: &str) {
+        if !is_unqualified_method_name(name) || name == "<clinit>" {
+            self.report(location.clone(), format!("invalid method name: {}", name));
+        }
+    }
+
+    fn check_field_descriptor(&mut self, location: &Location, descriptor: &str) {
+        if parse_field_descriptor(descriptor).is_none() {
+            self.report(
+                location.clone(),
+                format!("invalid field descriptor: {}", descriptor),
+            );
+        }
+    }
+
+    fn check_method_descriptor(
+        &mut self,
+        location: &Location,
+        descriptor: &str,
+    ) -> Option<MethodDescriptor> {
+        let parsed = parse_method_descriptor(descriptor);
+        if parsed.is_none() {
+            self.report(
+                location.clone(),
+                format!("invalid method descriptor: {}", descriptor),
+            );
+        }
+        parsed
+    }
+
+    fn check_bootstrap_method(&mut self, location: &Location, bootstrap_method_attr_index: u16) {
+        if bootstrap_method_attr_index as usize >= self.class_file.bootstrap_methods().len() {
+            self.report(
+                location.clone(),
+                format!("missing bootstrap method {}", bootstrap_method_attr_index),
+            );
+        }
+    }
+
+    fn check_method_handle(
+        &mut self,
+        location: &Location,
+        reference_kind: ReferenceKind,
+        reference_index: ConstantIndex,
+    ) {
+        let cpool = &self.class_file.constants;
+        let reference = match cpool.get(reference_index) {
+            Some(reference) => reference,
+            None => {
+                self.report(
+                    location.clone(),
+                    format!("missing constant {:?}", reference_index),
+                );
+                return;
+            }
+        };
+
+        let interface_allowed = self.class_file.major_version >= JAVA_8;
+        let valid = match reference_kind {
+            ReferenceKind::GetField
+            | ReferenceKind::GetStatic
+            | ReferenceKind::PutField
+            | ReferenceKind::PutStatic => matches!(reference, Constant::Fieldref { .. }),
+            ReferenceKind::InvokeVirtual | ReferenceKind::NewInvokeSpecial => {
+                matches!(reference, Constant::Methodref { .. })
+            }
+            ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial => match reference {
+                Constant::Methodref { .. } => true,
+                Constant::InterfaceMethodref { .. } => interface_allowed,
+                _ => false,
+            },
+            ReferenceKind::InvokeInterface => {
+                matches!(reference, Constant::InterfaceMethodref { .. })
+            }
+        };
+        if !valid {
+            self.report(
+                location.clone(),
+                format!(
+                    "constant {:?} can not be referenced by {:?}",
+                    reference_index, reference_kind
+                ),
+            );
+            return;
+        }
+
+        if let Ok(member) = cpool.resolve_member_ref(reference_index) {
+            let valid = match reference_kind {
+                ReferenceKind::NewInvokeSpecial => member.name == "<init>",
+                ReferenceKind::InvokeVirtual
+                | ReferenceKind::InvokeStatic
+                | ReferenceKind::InvokeSpecial
+                | ReferenceKind::InvokeInterface => !member.name.starts_with('<'),
+                _ => true,
+            };
+            if !valid {
+                self.report(
+                    location.clone(),
+                    format!(
+                        "method {} can not be referenced by {:?}",
+                        member.name, reference_kind
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_class(&mut self) {
+        let class_file = self.class_file;
+        let cpool = &class_file.constants;
+        let flags = class_file.access_flags;
+
+        let this_class = self.resolved(
+            &Location::Class,
+            cpool.resolve_class_name(class_file.this_class),
+        );
+        if class_file.super_class.0 == 0 {
+            if this_class != Some("java/lang/Object") && !flags.contains(ClassAccessFlags::MODULE) {
+                self.report(Location::Class, "missing super class".into());
+            }
+        } else if let Some(super_class) = self.resolved(
+            &Location::Class,
+            cpool.resolve_class_name(class_file.super_class),
+        ) {
+            if flags.contains(ClassAccessFlags::INTERFACE) && super_class != "java/lang/Object" {
+                self.report(
+                    Location::Class,
+                    format!(
+                        "super class of interface must be java/lang/Object, not {}",
+                        super_class
+                    ),
+                );
+            }
+        }
+        for interface in &class_file.interfaces {
+            self.resolved(&Location::Class, cpool.resolve_class(*interface));
+        }
+
+        if flags.contains(ClassAccessFlags::MODULE) {
+            if flags != ClassAccessFlags::MODULE {
+                self.report(
+                    Location::Class,
+                    format!("module with further flags: {:?}", flags),
+                );
+            }
+        } else if flags.contains(ClassAccessFlags::INTERFACE) {
+            if !flags.contains(ClassAccessFlags::ABSTRACT) {
+                self.report(Location::Class, "interface must be abstract".into());
+            }
+            if flags.intersects(
+                ClassAccessFlags::FINAL | ClassAccessFlags::SUPER | ClassAccessFlags::ENUM,
+            ) {
+                self.report(
+                    Location::Class,
+                    format!("invalid interface flags: {:?}", flags),
+                );
+            }
+        } else {
+            if flags.contains(ClassAccessFlags::ANNOTATION) {
+                self.report(Location::Class, "annotation must be an interface".into());
+            }
+            if flags.contains(ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT) {
+                self.report(Location::Class, "class is both final and abstract".into());
+            }
+        }
+    }
+
+    fn check_fields(&mut self) {
+        let class_file = self.class_file;
+        let cpool = &class_file.constants;
+        let is_interface = class_file
+            .access_flags
+            .contains(ClassAccessFlags::INTERFACE);
+        let mut seen = HashSet::new();
+
+        for (i, field) in class_file.fields.iter().enumerate() {
+            let location = Location::Field(i);
+            let name = self.resolved(&location, cpool.resolve_utf8(field.name_index));
+            let descriptor = self.resolved(&location, cpool.resolve_utf8(field.descriptor_index));
+            if let Some(name) = name {
+                self.check_field_name(&location, name);
+            }
+            if let Some(descriptor) = descriptor {
+                self.check_field_descriptor(&location, descriptor);
+            }
+            if let (Some(name), Some(descriptor)) = (name, descriptor) {
+                if !seen.insert((name, descriptor)) {
+                    self.report(
+                        location.clone(),
+                        format!("duplicate field {} {}", name, descriptor),
+                    );
+                }
+            }
+
+            let flags = field.access_flags;
+            let visibility =
+                FieldAccessFlags::PUBLIC | FieldAccessFlags::PRIVATE | FieldAccessFlags::PROTECTED;
+            if (flags & visibility).bits().count_ones() > 1 {
+                self.report(
+                    location.clone(),
+                    format!("conflicting visibility: {:?}", flags),
+                );
+            }
+            if flags.contains(FieldAccessFlags::FINAL | FieldAccessFlags::VOLATILE) {
+                self.report(location.clone(), "field is both final and volatile".into());
+            }
+            let interface_flags =
+                FieldAccessFlags::PUBLIC | FieldAccessFlags::STATIC | FieldAccessFlags::FINAL;
+            if is_interface
+                && (!flags.contains(interface_flags)
+                    || !(interface_flags | FieldAccessFlags::SYNTHETIC).contains(flags))
+            {
+                self.report(
+                    location,
+                    format!("invalid interface field flags: {:?}", flags),
+                );
+            }
+        }
+    }
+
+    fn check_methods(&mut self) {
+        let class_file = self.class_file;
+        let cpool = &class_file.constants;
+        let is_interface = class_file
+            .access_flags
+            .contains(ClassAccessFlags::INTERFACE);
+        let mut seen = HashSet::new();
+
+        for (i, method) in class_file.methods.iter().enumerate() {
+            let location = Location::Method(i);
+            let name = self.resolved(&location, cpool.resolve_utf8(method.name_index));
+            let descriptor = self.resolved(&location, cpool.resolve_utf8(method.descriptor_index));
+            if let Some(name) = name {
+                if !is_unqualified_method_name(name) {
+                    self.report(location.clone(), format!("invalid method name: {}", name));
+                }
+            }
+            let parsed = descriptor
+                .and_then(|descriptor| self.check_method_descriptor(&location, descriptor));
+            if let (Some(name), Some(descriptor)) = (name, descriptor) {
+                if !seen.insert((name, descriptor)) {
+                    self.report(
+                        location.clone(),
+                        format!("duplicate method {}{}", name, descriptor),
+                    );
+                }
+            }
+
+            let flags = method.access_flags;
+            match name {
+                // other flags of class initializers are ignored
+                Some("<clinit>") => {
+                    if descriptor != Some("()V") {
+                        self.report(
+                            location.clone(),
+                            "class initializer must have descriptor ()V".into(),
+                        );
+                    }
+                }
+                Some("<init>") => {
+                    if parsed.is_some_and(|parsed| parsed.rty.is_some()) {
+                        self.report(location.clone(), "constructor must return void".into());
+                    }
+                    if is_interface {
+                        self.report(location.clone(), "interface with constructor".into());
+                    }
+                    let forbidden = MethodAccessFlags::STATIC
+                        | MethodAccessFlags::FINAL
+                        | MethodAccessFlags::SYNCHRONIZED
+                        | MethodAccessFlags::BRIDGE
+                        | MethodAccessFlags::NATIVE
+                        | MethodAccessFlags::ABSTRACT;
+                    if flags.intersects(forbidden) {
+                        self.report(
+                            location.clone(),
+                            format!("invalid constructor flags: {:?}", flags),
+                        );
+                    }
+                    self.check_method_flags(&location, flags, is_interface);
+                }
+                _ => self.check_method_flags(&location, flags, is_interface),
+            }
+
+            let code_attributes = method
+                .attributes
+                .iter()
+                .filter(|attribute| matches!(attribute, Attribute::Code(_)))
+                .count();
+            if flags.intersects(MethodAccessFlags::ABSTRACT | MethodAccessFlags::NATIVE) {
+                if code_attributes > 0 {
+                    self.report(location, "abstract or native method with code".into());
+                }
+            } else if code_attributes != 1 {
+                self.report(
+                    location,
+                    format!("expected one Code attribute, found {}", code_attributes),
+                );
+            }
+        }
+    }
+
+    fn check_method_flags(
+        &mut self,
+        location: &Location,
+        flags: MethodAccessFlags,
+        is_interface: bool,
+    ) {
+        let visibility =
+            MethodAccessFlags::PUBLIC | MethodAccessFlags::PRIVATE | MethodAccessFlags::PROTECTED;
+        if (flags & visibility).bits().count_ones() > 1 {
+            self.report(
+                location.clone(),
+                format!("conflicting visibility: {:?}", flags),
+            );
+        }
+
+        let not_abstract = MethodAccessFlags::PRIVATE
+            | MethodAccessFlags::STATIC
+            | MethodAccessFlags::FINAL
+            | MethodAccessFlags::SYNCHRONIZED
+            | MethodAccessFlags::NATIVE;
+        if flags.contains(MethodAccessFlags::ABSTRACT) && flags.intersects(not_abstract) {
+            self.report(
+                location.clone(),
+                format!("invalid abstract method flags: {:?}", flags),
+            );
+        }
+
+        if is_interface {
+            let forbidden = MethodAccessFlags::PROTECTED
+                | MethodAccessFlags::FINAL
+                | MethodAccessFlags::SYNCHRONIZED
+                | MethodAccessFlags::NATIVE;
+            let required = if self.class_file.major_version < JAVA_8 {
+                MethodAccessFlags::PUBLIC | MethodAccessFlags::ABSTRACT
+            } else {
+                MethodAccessFlags::empty()
+            };
+            if flags.intersects(forbidden)
+                || !flags.contains(required)
+                || !flags.intersects(MethodAccessFlags::PUBLIC | MethodAccessFlags::PRIVATE)
+            {
+                self.report(
+                    location.clone(),
+                    format!("invalid interface method flags: {:?}", flags),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::ClassBuilder;
+    use rustjvm_opcode::{Assembler, Opcode};
+
+    fn class(access_flags: ClassAccessFlags) -> ClassBuilder {
+        ClassBuilder::new(access_flags, "Test")
+    }
+
+    fn code() -> rustjvm_opcode::Bytecode {
+        let mut asm = Assembler::new();
+        asm.push(Opcode::Return);
+        asm.finish().unwrap()
+    }
+
+    fn messages(class: ClassBuilder) -> Vec<String> {
+        check_format(&class.build().unwrap())
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn well_formed() {
+        let mut class = class(ClassAccessFlags::PUBLIC | ClassAccessFlags::SUPER);
+        class.field(FieldAccessFlags::PRIVATE, "value", "I");
+        class
+            .method(MethodAccessFlags::PUBLIC, "<init>", "()V")
+            .code(1, 1, code());
+        let mut main = class.method(
+            MethodAccessFlags::PUBLIC | MethodAccessFlags::STATIC,
+            "main",
+            "([Ljava/lang/String;)V",
+        );
+        main.constants()
+            .method_ref("java/lang/Object", "<init>", "()V");
+        main.code(0, 1, code());
+        assert_eq!(messages(class), Vec::<String>::new());
+    }
+
+    #[test]
+    fn this_class_not_a_class() {
+        let mut class_file = class(ClassAccessFlags::PUBLIC).build().unwrap();
+        let name_index = class_file
+            .constants
+            .resolve_class(class_file.this_class)
+            .unwrap();
+        class_file.this_class = name_index;
+        let violations = check_format(&class_file);
+        assert_eq!(
+            violations,
+            vec![FormatViolation {
+                location: Location::Class,
+                message: format!("wrong type of constant {:?}, expected class", name_index),
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_references() {
+        let mut class = class(ClassAccessFlags::PUBLIC);
+        let constants = class.constants();
+        let method = constants.method_ref("java/lang/Object", "a.b", "()V");
+        let constructor = constants.method_ref("java/lang/Object", "<init>", "()I");
+        let field = constants.field_ref("java/lang/Object", "value", "X");
+        let handle = constants.method_handle(ReferenceKind::InvokeVirtual, field);
+        assert_eq!(
+            messages(class),
+            vec![
+                format!("constant {:?}: invalid method name: a.b", method),
+                format!("constant {:?}: constructor must return void", constructor),
+                format!("constant {:?}: invalid field descriptor: X", field),
+                format!(
+                    "constant {:?}: constant {:?} can not be referenced by InvokeVirtual",
+                    handle, field
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_flags() {
+        let mut class =
+            class(ClassAccessFlags::PUBLIC | ClassAccessFlags::FINAL | ClassAccessFlags::ABSTRACT);
+        class.field(
+            FieldAccessFlags::PUBLIC | FieldAccessFlags::PRIVATE,
+            "value",
+            "I",
+        );
+        class.field(
+            FieldAccessFlags::FINAL | FieldAccessFlags::VOLATILE,
+            "other",
+            "I",
+        );
+        class.method(
+            MethodAccessFlags::ABSTRACT | MethodAccessFlags::STATIC,
+            "run",
+            "()V",
+        );
+        assert_eq!(
+            messages(class),
+            vec![
+                "class: class is both final and abstract",
+                "field 0: conflicting visibility: PUBLIC | PRIVATE",
+                "field 1: field is both final and volatile",
+                "method 0: invalid abstract method flags: STATIC | ABSTRACT",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_interface() {
+        let mut class = class(ClassAccessFlags::INTERFACE | ClassAccessFlags::FINAL);
+        class.field(FieldAccessFlags::PUBLIC, "VALUE", "I");
+        class.method(
+            MethodAccessFlags::PROTECTED | MethodAccessFlags::ABSTRACT,
+            "run",
+            "()V",
+        );
+        assert_eq!(
+            messages(class),
+            vec![
+                "class: interface must be abstract",
+                "class: invalid interface flags: FINAL | INTERFACE",
+                "field 0: invalid interface field flags: PUBLIC",
+                "method 0: invalid interface method flags: PROTECTED | ABSTRACT",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_methods() {
+        let mut class = class(ClassAccessFlags::PUBLIC | ClassAccessFlags::ABSTRACT);
+        class
+            .method(MethodAccessFlags::PUBLIC, "run", "()V")
+            .code(0, 1, code());
+        class
+            .method(MethodAccessFlags::PUBLIC, "run", "()V")
+            .code(0, 1, code());
+        class
+            .method(MethodAccessFlags::ABSTRACT, "stop", "()V")
+            .code(0, 1, code());
+        class.method(MethodAccessFlags::PUBLIC, "start", "()V");
+        class
+            .method(MethodAccessFlags::STATIC, "<init>", "()V")
+            .code(0, 0, code());
+        class.method(MethodAccessFlags::PUBLIC, "broken", "(V)");
+        assert_eq!(
+            messages(class),
+            vec![
+                "method 1: duplicate method run()V",
+                "method 2: abstract or native method with code",
+                "method 3: expected one Code attribute, found 0",
+                "method 4: invalid constructor flags: STATIC",
+                "method 5: invalid method descriptor: (V)",
+                "method 5: expected one Code attribute, found 0",
+            ]
+        );
+    }
+}
@@ -1,7 +1,8 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Write;
 
-#[derive(PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ComponentType {
     Byte,
     Char,
@@ -14,12 +15,65 @@ pub enum ComponentType {
     Boolean,
 }
 
+impl ComponentType {
+    /// Number of local variable or operand stack slots of a value.
+    pub fn slots(&self) -> u16 {
+        match self {
+            ComponentType::Long | ComponentType::Double => 2,
+            _ => 1,
+        }
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub struct FieldType {
     dim: u8,
     ty: ComponentType,
 }
 
+impl FieldType {
+    pub fn new(dim: u8, ty: ComponentType) -> Self {
+        Self { dim, ty }
+    }
+
+    /// Non-array type.
+    pub fn scalar(ty: ComponentType) -> Self {
+        Self { dim: 0, ty }
+    }
+
+    /// Class type from an internal name like `java/lang/Object`.
+    pub fn object(class_name: &str) -> Self {
+        Self::scalar(ComponentType::Reference(class_name.to_string()))
+    }
+
+    /// Number of array dimensions, 0 for non-array types.
+    pub fn dim(&self) -> u8 {
+        self.dim
+    }
+
+    /// Element type of an array after all dimensions, otherwise the type itself.
+    pub fn ty(&self) -> &ComponentType {
+        &self.ty
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.dim > 0
+    }
+
+    pub fn is_reference(&self) -> bool {
+        self.is_array() || matches!(self.ty, ComponentType::Reference(_))
+    }
+
+    /// Number of local variable or operand stack slots of a value.
+    pub fn slots(&self) -> u16 {
+        if self.is_array() {
+            1
+        } else {
+            self.ty.slots()
+        }
+    }
+}
+
 impl fmt::Display for FieldType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for _ in 0..self.dim {
@@ -66,14 +120,44 @@ impl fmt::Debug for FieldType {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
 pub struct MethodDescriptor {
     pub params: Vec<FieldType>,
+    /// Return type, `None` for void.
     pub rty: Option<FieldType>,
 }
 
+impl MethodDescriptor {
+    pub fn new(params: Vec<FieldType>, rty: Option<FieldType>) -> Self {
+        Self { params, rty }
+    }
+
+    /// Number of local variable slots of the parameters, without `this`.
+    pub fn param_slots(&self) -> u16 {
+        self.params.iter().map(FieldType::slots).sum()
+    }
+}
+
+impl fmt::Display for MethodDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('(')?;
+        for param in &self.params {
+            fmt::Display::fmt(param, f)?;
+        }
+        f.write_char(')')?;
+        match &self.rty {
+            Some(rty) => fmt::Display::fmt(rty, f),
+            None => f.write_char('V'),
+        }
+    }
+}
+
+/// Parses a field descriptor at the start of `x`, returning the rest. Arrays
+/// may have at most 255 dimensions (JVMS 4.4.1).
 pub fn parse_field_descriptor_incomplete(x: &str) -> Option<(&str, FieldType)> {
     let dim = x.find(|c| c != '[')?;
     let x = &x[dim..];
+    let dim = u8::try_from(dim).ok()?;
 
     let (x, ty) = match x.chars().next()? {
         'B' => (&x[1..], ComponentType::Byte),
@@ -85,7 +169,10 @@ pub fn parse_field_descriptor_incomplete(x: &str) -> Option<(&str, FieldType)> {
         'S' => (&x[1..], ComponentType::Short),
         'Z' => (&x[1..], ComponentType::Boolean),
         'L' => {
-            let colon = x[1..].find(|c| c == ';')? + 1;
+            let colon = x[1..].find(';')? + 1;
+            if colon == 1 {
+                return None;
+            }
             (
                 &x[colon + 1..],
                 ComponentType::Reference(x[1..colon].to_string()),
@@ -94,7 +181,7 @@ pub fn parse_field_descriptor_incomplete(x: &str) -> Option<(&str, FieldType)> {
         _ => return None,
     };
 
-    Some((x, FieldType { dim: dim as u8, ty }))
+    Some((x, FieldType { dim, ty }))
 }
 
 pub fn parse_field_descriptor(x: &str) -> Option<FieldType> {
@@ -104,6 +191,61 @@ pub fn parse_field_descriptor(x: &str) -> Option<FieldType> {
     }
 }
 
+/// Parses a method descriptor like `(ILjava/lang/String;)V`.
+pub fn parse_method_descriptor(x: &str) -> Option<MethodDescriptor> {
+    let mut x = x.strip_prefix('(')?;
+    let mut params = vec![];
+    while !x.starts_with(')') {
+        let (rest, param) = parse_field_descriptor_incomplete(x)?;
+        params.push(param);
+        x = rest;
+    }
+
+    let rty = match &x[1..] {
+        "V" => None,
+        rty => Some(parse_field_descriptor(rty)?),
+    };
+    Some(MethodDescriptor { params, rty })
+}
+
+/// Converts an internal name like `java/lang/Object` to a binary name like
+/// `java.lang.Object`.
+pub fn internal_to_binary_name(x: &str) -> String {
+    x.replace('/', ".")
+}
+
+/// Converts a binary name like `java.lang.Object` to an internal name like
+/// `java/lang/Object`.
+pub fn binary_to_internal_name(x: &str) -> String {
+    x.replace('.', "/")
+}
+
+/// Converts an internal name like `java/util/Map$Entry` to the name used in
+/// Java source like `java.util.Map.Entry`.
+///
+/// Every `$` is taken as nested class separator, which is wrong for classes
+/// with `$` in their simple name.
+pub fn internal_to_source_name(x: &str) -> String {
+    x.replace(['/', '$'], ".")
+}
+
+/// Whether the name is a valid unqualified name of a field or local variable
+/// (JVMS 4.2.2).
+pub fn is_unqualified_name(x: &str) -> bool {
+    !x.is_empty() && !x.contains(&['.', ';', '[', '/'][..])
+}
+
+/// Whether the name is a valid unqualified name of a method (JVMS 4.2.2).
+pub fn is_unqualified_method_name(x: &str) -> bool {
+    x == "<init>" || x == "<clinit>" || (is_unqualified_name(x) && !x.contains(&['<', '>'][..]))
+}
+
+/// Whether the name is a valid binary class name in internal form like
+/// `java/lang/Object` (JVMS 4.2.1).
+pub fn is_internal_class_name(x: &str) -> bool {
+    x.split('/').all(is_unqualified_name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +330,23 @@ mod tests {
         assert_eq!(i, None);
     }
 
+    #[test]
+    fn empty_class_name() {
+        assert_eq!(parse_field_descriptor("L;"), None);
+        assert_eq!(parse_field_descriptor("[L;"), None);
+        assert_eq!(parse_method_descriptor("(L;)V"), None);
+    }
+
+    #[test]
+    fn max_dimensions() {
+        let field_type = parse_field_descriptor(&format!("{}I", "[".repeat(255))).unwrap();
+        assert_eq!(field_type.dim, 255);
+        assert_eq!(
+            parse_field_descriptor(&format!("{}I", "[".repeat(256))),
+            None
+        );
+    }
+
     #[test]
     fn trailing() {
         let i = parse_field_descriptor_incomplete("[Ljava/lang/Object;[[Ljava/lang/Object;");
@@ -208,4 +367,110 @@ mod tests {
         let i = parse_field_descriptor("[Ljava/lang/Object;[[Ljava/lang/Object;");
         assert_eq!(i, None);
     }
+
+    #[test]
+    fn method() {
+        let descriptor = parse_method_descriptor("(I[JLjava/lang/String;)V").unwrap();
+        assert_eq!(descriptor.params.len(), 3);
+        assert_eq!(&format!("{}", descriptor.params[2]), "Ljava/lang/String;");
+        assert_eq!(descriptor.rty, None);
+
+        let descriptor = parse_method_descriptor("()[I").unwrap();
+        assert!(descriptor.params.is_empty());
+        assert_eq!(&format!("{:?}", descriptor.rty.unwrap()), "int[]");
+    }
+
+    #[test]
+    fn invalid_method() {
+        assert_eq!(parse_method_descriptor("I)V"), None);
+        assert_eq!(parse_method_descriptor("(I"), None);
+        assert_eq!(parse_method_descriptor("(I)"), None);
+        assert_eq!(parse_method_descriptor("(V)V"), None);
+        assert_eq!(parse_method_descriptor("()VV"), None);
+    }
+
+    #[test]
+    fn accessors() {
+        let ty = parse_field_descriptor("[[Ljava/lang/String;").unwrap();
+        assert_eq!(ty.dim(), 2);
+        assert_eq!(
+            ty.ty(),
+            &ComponentType::Reference("java/lang/String".into())
+        );
+        assert!(ty.is_array());
+        assert!(ty.is_reference());
+        assert_eq!(
+            ty,
+            FieldType::new(2, ComponentType::Reference("java/lang/String".into()))
+        );
+
+        let ty = FieldType::object("java/lang/Object");
+        assert_eq!(&format!("{}", ty), "Ljava/lang/Object;");
+        assert!(!ty.is_array());
+        assert!(ty.is_reference());
+        assert!(!FieldType::scalar(ComponentType::Int).is_reference());
+    }
+
+    #[test]
+    fn slots() {
+        assert_eq!(FieldType::scalar(ComponentType::Long).slots(), 2);
+        assert_eq!(FieldType::scalar(ComponentType::Double).slots(), 2);
+        assert_eq!(FieldType::scalar(ComponentType::Int).slots(), 1);
+        assert_eq!(FieldType::new(1, ComponentType::Long).slots(), 1);
+
+        let descriptor = parse_method_descriptor("(IJLjava/lang/Object;D[J)V").unwrap();
+        assert_eq!(descriptor.param_slots(), 7);
+        assert_eq!(parse_method_descriptor("()V").unwrap().param_slots(), 0);
+    }
+
+    #[test]
+    fn method_display() {
+        for x in &[
+            "(I[JLjava/lang/String;)V",
+            "()[[D",
+            "(ZBCSF)Ljava/lang/Object;",
+        ] {
+            assert_eq!(&parse_method_descriptor(x).unwrap().to_string(), x);
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            internal_to_binary_name("java/util/Map$Entry"),
+            "java.util.Map$Entry"
+        );
+        assert_eq!(
+            binary_to_internal_name("java.util.Map$Entry"),
+            "java/util/Map$Entry"
+        );
+        assert_eq!(
+            internal_to_source_name("java/util/Map$Entry"),
+            "java.util.Map.Entry"
+        );
+    }
+
+    #[test]
+    fn unqualified_names() {
+        assert!(is_unqualified_name("value"));
+        assert!(is_unqualified_name("this$0"));
+        assert!(is_unqualified_name("<weird>"));
+        assert!(!is_unqualified_name(""));
+        assert!(!is_unqualified_name("a.b"));
+        assert!(!is_unqualified_name("a;"));
+        assert!(!is_unqualified_name("[a"));
+        assert!(!is_unqualified_name("a/b"));
+
+        assert!(is_unqualified_method_name("<init>"));
+        assert!(is_unqualified_method_name("<clinit>"));
+        assert!(is_unqualified_method_name("lambda$main$0"));
+        assert!(!is_unqualified_method_name("<weird>"));
+        assert!(!is_unqualified_method_name("a>"));
+
+        assert!(is_internal_class_name("java/lang/Object"));
+        assert!(is_internal_class_name("Foo"));
+        assert!(!is_internal_class_name("java.lang.Object"));
+        assert!(!is_internal_class_name("java//Object"));
+        assert!(!is_internal_class_name("/Object"));
+    }
 }
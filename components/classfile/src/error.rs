@@ -1,5 +1,7 @@
 use crate::model::constants::ConstantIndex;
-use rustjvm_opcode::DisasmError;
+use rustjvm_opcode::{AsmError, DisasmError};
+use std::error::Error;
+use std::fmt;
 use std::io;
 
 #[derive(Debug)]
@@ -9,10 +11,114 @@ pub enum JvmParseError {
     MissingConstant(ConstantIndex),
     WrongConstantType(ConstantIndex, String),
     InvalidCode(DisasmError),
+    /// A size or depth in the class file exceeds the `ParseLimits` of the
+    /// parse.
+    LimitExceeded {
+        what: &'static str,
+        value: u64,
+        limit: u64,
+    },
+    /// Error raised by `parse_class_file` with the position it was detected
+    /// at.
+    Located(Box<LocatedError>),
 }
 
 pub type JvmParseResult<T> = Result<T, JvmParseError>;
 
+impl JvmParseError {
+    /// The error without its location.
+    pub fn kind(&self) -> &JvmParseError {
+        match self {
+            JvmParseError::Located(located) => &located.error,
+            error => error,
+        }
+    }
+
+    pub fn location(&self) -> Option<&LocatedError> {
+        match self {
+            JvmParseError::Located(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    /// Adds the path segment of the structure the error occurred in. Segments
+    /// are added from the innermost structure outwards.
+    pub(crate) fn within(self, segment: impl Into<String>) -> Self {
+        let mut located = self.into_located();
+        located.path.push(segment.into());
+        JvmParseError::Located(located)
+    }
+
+    /// Moves the position of the error the given number of bytes back, for
+    /// errors which are passed on after further bytes have been consumed.
+    pub(crate) fn before(self, bytes: usize) -> Self {
+        let mut located = self.into_located();
+        if !located.resolved {
+            located.offset += bytes as u64;
+        }
+        JvmParseError::Located(located)
+    }
+
+    /// Resolves the position to an absolute offset, given the position of the
+    /// reader in the class file when the error was passed on. Errors which
+    /// are already resolved are kept.
+    pub(crate) fn at(self, position: u64) -> Self {
+        let mut located = self.into_located();
+        if !located.resolved {
+            located.offset = position.saturating_sub(located.offset);
+            located.resolved = true;
+        }
+        JvmParseError::Located(located)
+    }
+
+    pub(crate) fn in_class(self, class_name: Option<String>) -> Self {
+        let mut located = self.into_located();
+        located.class_name = class_name;
+        JvmParseError::Located(located)
+    }
+
+    fn into_located(self) -> Box<LocatedError> {
+        match self {
+            JvmParseError::Located(located) => located,
+            error => Box::new(LocatedError {
+                error,
+                offset: 0,
+                resolved: false,
+                path: vec![],
+                class_name: None,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for JvmParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JvmParseError::Io(err) => write!(f, "I/O error: {}", err),
+            JvmParseError::InvalidFormat(message) => f.write_str(message),
+            JvmParseError::MissingConstant(index) => write!(f, "missing constant {:?}", index),
+            JvmParseError::WrongConstantType(index, expected) => {
+                write!(f, "wrong type of constant {:?}, {}", index, expected)
+            }
+            JvmParseError::InvalidCode(err) => write!(f, "invalid code: {}", err),
+            JvmParseError::LimitExceeded { what, value, limit } => {
+                write!(f, "{} {} exceeds limit {}", what, value, limit)
+            }
+            JvmParseError::Located(located) => located.fmt(f),
+        }
+    }
+}
+
+impl Error for JvmParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind() {
+            JvmParseError::Io(err) => Some(err),
+            JvmParseError::InvalidCode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 impl From<io::Error> for JvmParseError {
     fn from(err: io::Error) -> Self {
         JvmParseError::Io(err)
@@ -24,3 +130,74 @@ impl From<DisasmError> for JvmParseError {
         JvmParseError::InvalidCode(err)
     }
 }
+
+/// Parse error with its position in the class file.
+#[derive(Debug)]
+pub struct LocatedError {
+    error: JvmParseError,
+    /// Absolute offset once resolved by `JvmParseError::at`, before that the
+    /// distance back from the current position of the reader.
+    offset: u64,
+    resolved: bool,
+    /// Path segments, innermost first.
+    path: Vec<String>,
+    class_name: Option<String>,
+}
+
+impl LocatedError {
+    pub fn error(&self) -> &JvmParseError {
+        &self.error
+    }
+
+    /// Byte offset in the class file at which the error was detected.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Path to the failing structure like
+    /// `methods[3].attributes[Code].code@pc 17`, empty for errors outside of
+    /// members and attributes.
+    pub fn path(&self) -> String {
+        let segments: Vec<&str> = self.path.iter().rev().map(|s| s.as_str()).collect();
+        segments.join(".")
+    }
+
+    /// Internal name of the class, if the error occurred after it was read.
+    pub fn class_name(&self) -> Option<&str> {
+        self.class_name.as_deref()
+    }
+}
+
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(class_name) = &self.class_name {
+            write!(f, "{}: ", class_name)?;
+        }
+        write!(f, "{} at byte {}", self.error, self.offset)?;
+        if !self.path.is_empty() {
+            write!(f, " in {}", self.path())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum JvmWriteError {
+    Io(io::Error),
+    InvalidFormat(String),
+    InvalidCode(AsmError),
+}
+
+pub type JvmWriteResult<T> = Result<T, JvmWriteError>;
+
+impl From<io::Error> for JvmWriteError {
+    fn from(err: io::Error) -> Self {
+        JvmWriteError::Io(err)
+    }
+}
+
+impl From<AsmError> for JvmWriteError {
+    fn from(err: AsmError) -> Self {
+        JvmWriteError::InvalidCode(err)
+    }
+}
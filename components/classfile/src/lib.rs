@@ -1,4 +1,9 @@
+pub mod borrowed;
+pub mod build;
+pub mod check;
 pub mod descriptor;
 pub mod error;
 pub mod model;
 pub mod parse;
+pub mod signature;
+pub mod write;
@@ -0,0 +1,267 @@
+use crate::model::constants::{ConstantIndex, ConstantPool};
+use crate::model::Attribute;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// Field descriptor of the annotation type like `Ljava/lang/Deprecated;`.
+    pub type_index: ConstantIndex,
+    pub elements: Vec<ElementValuePair>,
+}
+
+impl Annotation {
+    /// Value of the element with the given name, if it is not defaulted.
+    pub fn element(&self, cpool: &ConstantPool, name: &str) -> Option<&ElementValue> {
+        self.elements
+            .iter()
+            .find(|pair| cpool.resolve_utf8(pair.name_index).ok() == Some(name))
+            .map(|pair| &pair.value)
+    }
+
+    fn has_type(&self, cpool: &ConstantPool, descriptor: &str) -> bool {
+        cpool.resolve_utf8(self.type_index).ok() == Some(descriptor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementValuePair {
+    pub name_index: ConstantIndex,
+    pub value: ElementValue,
+}
+
+/// Type of a constant element value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstKind {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    String,
+}
+
+impl ConstKind {
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        Some(match tag {
+            b'B' => ConstKind::Byte,
+            b'C' => ConstKind::Char,
+            b'D' => ConstKind::Double,
+            b'F' => ConstKind::Float,
+            b'I' => ConstKind::Int,
+            b'J' => ConstKind::Long,
+            b'S' => ConstKind::Short,
+            b'Z' => ConstKind::Boolean,
+            b's' => ConstKind::String,
+            _ => return None,
+        })
+    }
+
+    pub fn tag(self) -> u8 {
+        match self {
+            ConstKind::Byte => b'B',
+            ConstKind::Char => b'C',
+            ConstKind::Double => b'D',
+            ConstKind::Float => b'F',
+            ConstKind::Int => b'I',
+            ConstKind::Long => b'J',
+            ConstKind::Short => b'S',
+            ConstKind::Boolean => b'Z',
+            ConstKind::String => b's',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementValue {
+    /// Primitive constant or Utf8 constant for strings.
+    Const(ConstKind, ConstantIndex),
+    Enum {
+        /// Field descriptor of the enum type.
+        type_name_index: ConstantIndex,
+        const_name_index: ConstantIndex,
+    },
+    /// Return descriptor like `Ljava/lang/Object;` or `V`.
+    Class(ConstantIndex),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+/// Annotation on a type use (JSR 308).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeAnnotation {
+    pub target: TypeAnnotationTarget,
+    pub type_path: TypePath,
+    pub annotation: Annotation,
+}
+
+/// Range of a local variable in a `LocalVariable` or `ResourceVariable` target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalVarTargetEntry {
+    pub start_pc: u16,
+    pub length: u16,
+    /// Slot in the local variable array.
+    pub index: u16,
+}
+
+/// Kind of type use together with the target_info of the class file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeAnnotationTarget {
+    /// Index of a type parameter of the class or interface.
+    ClassTypeParameter(u8),
+    /// Index of a type parameter of the method.
+    MethodTypeParameter(u8),
+    /// Index into the interfaces of the class, 65535 for the super class.
+    Supertype(u16),
+    ClassTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    MethodTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    Field,
+    /// Return type of a method or type of a newly constructed object.
+    MethodReturn,
+    MethodReceiver,
+    FormalParameter(u8),
+    /// Index into the exceptions of the `Exceptions` attribute.
+    Throws(u16),
+    LocalVariable(Vec<LocalVarTargetEntry>),
+    ResourceVariable(Vec<LocalVarTargetEntry>),
+    /// Index into the exception table of the code.
+    ExceptionParameter(u16),
+    /// Offset of the `instanceof` instruction.
+    Instanceof(u16),
+    /// Offset of the `new` instruction.
+    New(u16),
+    ConstructorReference(u16),
+    MethodReference(u16),
+    Cast {
+        offset: u16,
+        type_argument_index: u8,
+    },
+    ConstructorInvocationTypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+    MethodInvocationTypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+    ConstructorReferenceTypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+    MethodReferenceTypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+impl TypeAnnotationTarget {
+    /// target_type value of the class file.
+    pub fn target_type(&self) -> u8 {
+        match self {
+            TypeAnnotationTarget::ClassTypeParameter(_) => 0x00,
+            TypeAnnotationTarget::MethodTypeParameter(_) => 0x01,
+            TypeAnnotationTarget::Supertype(_) => 0x10,
+            TypeAnnotationTarget::ClassTypeParameterBound { .. } => 0x11,
+            TypeAnnotationTarget::MethodTypeParameterBound { .. } => 0x12,
+            TypeAnnotationTarget::Field => 0x13,
+            TypeAnnotationTarget::MethodReturn => 0x14,
+            TypeAnnotationTarget::MethodReceiver => 0x15,
+            TypeAnnotationTarget::FormalParameter(_) => 0x16,
+            TypeAnnotationTarget::Throws(_) => 0x17,
+            TypeAnnotationTarget::LocalVariable(_) => 0x40,
+            TypeAnnotationTarget::ResourceVariable(_) => 0x41,
+            TypeAnnotationTarget::ExceptionParameter(_) => 0x42,
+            TypeAnnotationTarget::Instanceof(_) => 0x43,
+            TypeAnnotationTarget::New(_) => 0x44,
+            TypeAnnotationTarget::ConstructorReference(_) => 0x45,
+            TypeAnnotationTarget::MethodReference(_) => 0x46,
+            TypeAnnotationTarget::Cast { .. } => 0x47,
+            TypeAnnotationTarget::ConstructorInvocationTypeArgument { .. } => 0x48,
+            TypeAnnotationTarget::MethodInvocationTypeArgument { .. } => 0x49,
+            TypeAnnotationTarget::ConstructorReferenceTypeArgument { .. } => 0x4A,
+            TypeAnnotationTarget::MethodReferenceTypeArgument { .. } => 0x4B,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypePathKind {
+    /// Deeper in an array type.
+    ArrayElement = 0,
+    /// Deeper in a nested type.
+    Nested = 1,
+    /// On the bound of a wildcard type argument.
+    WildcardBound = 2,
+    /// On a type argument of a parameterized type.
+    TypeArgument = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypePathEntry {
+    pub kind: TypePathKind,
+    /// Index of the type argument for `TypeArgument` entries, otherwise 0.
+    pub type_argument_index: u8,
+}
+
+/// Location of the annotation in the type of the target, empty when the
+/// annotation is on the type itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypePath {
+    pub path: Vec<TypePathEntry>,
+}
+
+/// Visible and invisible annotations of a class, field or method.
+pub(crate) fn annotations(attributes: &[Attribute]) -> impl Iterator<Item = &Annotation> {
+    attributes
+        .iter()
+        .filter_map(|attribute| match attribute {
+            Attribute::RuntimeVisibleAnnotations(annotations)
+            | Attribute::RuntimeInvisibleAnnotations(annotations) => Some(annotations),
+            _ => None,
+        })
+        .flatten()
+}
+
+pub(crate) fn find_annotation<'a>(
+    attributes: &'a [Attribute],
+    cpool: &ConstantPool,
+    descriptor: &str,
+) -> Option<&'a Annotation> {
+    annotations(attributes).find(|annotation| annotation.has_type(cpool, descriptor))
+}
+
+/// Visible and invisible annotations of a method parameter.
+pub(crate) fn parameter_annotations(
+    attributes: &[Attribute],
+    parameter: usize,
+) -> impl Iterator<Item = &Annotation> {
+    attributes
+        .iter()
+        .filter_map(move |attribute| match attribute {
+            Attribute::RuntimeVisibleParameterAnnotations(parameters)
+            | Attribute::RuntimeInvisibleParameterAnnotations(parameters) => {
+                parameters.get(parameter)
+            }
+            _ => None,
+        })
+        .flatten()
+}
+
+/// Visible and invisible type annotations of a class, field, method or code.
+pub(crate) fn type_annotations(attributes: &[Attribute]) -> impl Iterator<Item = &TypeAnnotation> {
+    attributes
+        .iter()
+        .filter_map(|attribute| match attribute {
+            Attribute::RuntimeVisibleTypeAnnotations(annotations)
+            | Attribute::RuntimeInvisibleTypeAnnotations(annotations) => Some(annotations),
+            _ => None,
+        })
+        .flatten()
+}
@@ -1,13 +1,70 @@
-use rustjvm_opcode::Opcode;
+use rustjvm_opcode::{Bytecode, Opcode};
 
-use crate::model::constants::ConstantIndex;
-use crate::model::Attribute;
+use crate::descriptor::{FieldType, MethodDescriptor};
+use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::annotations::{self, TypeAnnotation};
+use crate::model::constants::{ConstantIndex, ConstantPool, Loadable, MethodHandle};
+use crate::model::{Attribute, Attributes, InnerClassAccessFlags};
 
 #[derive(Debug)]
 pub struct ConstantValue {
     pub constantvalue_index: ConstantIndex,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapMethod {
+    /// MethodHandle constant of the bootstrap method.
+    pub bootstrap_method_ref: ConstantIndex,
+    /// Loadable constants passed as static arguments.
+    pub bootstrap_arguments: Vec<ConstantIndex>,
+}
+
+/// Entry of the `InnerClasses` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerClass {
+    pub inner_class_info_index: ConstantIndex,
+    /// Declaring class, `None` for local and anonymous classes.
+    pub outer_class_info_index: Option<ConstantIndex>,
+    /// Simple name, `None` for anonymous classes.
+    pub inner_name_index: Option<ConstantIndex>,
+    pub inner_class_access_flags: InnerClassAccessFlags,
+}
+
+/// Content of the `EnclosingMethod` attribute of a local or anonymous class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnclosingMethod {
+    pub class_index: ConstantIndex,
+    /// NameAndType constant, `None` when the class is not enclosed by a method
+    /// or constructor, for example in a field initializer.
+    pub method_index: Option<ConstantIndex>,
+}
+
+/// Component of a record class.
+#[derive(Debug)]
+pub struct RecordComponent {
+    pub name_index: ConstantIndex,
+    pub descriptor_index: ConstantIndex,
+    pub attributes: Attributes,
+}
+
+/// Resolved call site of an `invokedynamic` instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallSite<'a> {
+    pub bootstrap_method: MethodHandle<'a>,
+    pub static_arguments: Vec<Loadable<'a>>,
+    pub name: &'a str,
+    pub descriptor: MethodDescriptor,
+}
+
+/// Resolved dynamically-computed constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicConstant<'a> {
+    pub bootstrap_method: MethodHandle<'a>,
+    pub static_arguments: Vec<Loadable<'a>>,
+    pub name: &'a str,
+    pub descriptor: FieldType,
+}
+
 #[derive(Debug)]
 pub struct ExceptionTableEntry {
     pub start_pc: u16,
@@ -16,11 +73,210 @@ pub struct ExceptionTableEntry {
     pub catch_type: u16,
 }
 
+impl ExceptionTableEntry {
+    /// Whether the handler is active for the instruction at `pc`.
+    pub fn covers(&self, pc: u16) -> bool {
+        self.start_pc <= pc && pc < self.end_pc
+    }
+}
+
 #[derive(Debug)]
 pub struct Code {
     pub max_stack: u16,
     pub max_locals: u16,
-    pub code: Vec<Opcode>,
+    pub code: Bytecode,
     pub exception_table: Vec<ExceptionTableEntry>,
-    pub attributes: Vec<Attribute>,
+    pub attributes: Attributes,
+}
+
+impl Code {
+    /// Exception handlers active for the instruction at `pc` in the order they
+    /// must be searched.
+    pub fn handlers_for(&self, pc: u16) -> impl Iterator<Item = &ExceptionTableEntry> {
+        self.exception_table
+            .iter()
+            .filter(move |entry| entry.covers(pc))
+    }
+
+    /// Source line of the instruction at `pc`.
+    pub fn line_for_pc(&self, pc: u16) -> Option<u16> {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                Attribute::LineNumberTable(line_numbers) => Some(line_numbers),
+                _ => None,
+            })
+            .flatten()
+            .filter(|line_number| line_number.start_pc <= pc)
+            .max_by_key(|line_number| line_number.start_pc)
+            .map(|line_number| line_number.line_number)
+    }
+
+    /// Local variables which have a value at `pc`.
+    pub fn locals_at(&self, pc: u16) -> impl Iterator<Item = &LocalVariable> {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                Attribute::LocalVariableTable(local_variables) => Some(local_variables),
+                _ => None,
+            })
+            .flatten()
+            .filter(move |local_variable| local_variable.covers(pc))
+    }
+
+    /// Generic signatures of local variables which have a value at `pc`.
+    pub fn local_types_at(&self, pc: u16) -> impl Iterator<Item = &LocalVariableType> {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                Attribute::LocalVariableTypeTable(local_variable_types) => {
+                    Some(local_variable_types)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter(move |local_variable_type| local_variable_type.covers(pc))
+    }
+
+    /// Visible and invisible type annotations in the method body.
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation> {
+        annotations::type_annotations(&self.attributes)
+    }
+
+    pub fn stack_map_table(&self) -> Option<&StackMapTable> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::StackMapTable(table) => Some(table),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineNumber {
+    /// Offset of the first instruction of the line.
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalVariable {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: ConstantIndex,
+    pub descriptor_index: ConstantIndex,
+    /// Slot in the local variable array.
+    pub index: u16,
+}
+
+impl LocalVariable {
+    /// Whether the variable has a value at `pc`.
+    pub fn covers(&self, pc: u16) -> bool {
+        self.start_pc <= pc && u32::from(pc) < u32::from(self.start_pc) + u32::from(self.length)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalVariableType {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: ConstantIndex,
+    pub signature_index: ConstantIndex,
+    /// Slot in the local variable array.
+    pub index: u16,
+}
+
+impl LocalVariableType {
+    /// Whether the variable has a value at `pc`.
+    pub fn covers(&self, pc: u16) -> bool {
+        self.start_pc <= pc && u32::from(pc) < u32::from(self.start_pc) + u32::from(self.length)
+    }
+}
+
+/// Type of a local variable or operand stack entry in a stack map frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    /// Class constant of an initialized object.
+    Object(ConstantIndex),
+    /// Object created by the `new` instruction at the given offset, whose
+    /// constructor was not called yet.
+    Uninitialized(u16),
+}
+
+impl VerificationTypeInfo {
+    /// Internal name of the class for `Object` and `Uninitialized` entries.
+    pub fn class_name<'a>(
+        &self,
+        code: &Code,
+        cpool: &'a ConstantPool,
+    ) -> JvmParseResult<Option<&'a str>> {
+        let class_index = match self {
+            VerificationTypeInfo::Object(class_index) => *class_index,
+            VerificationTypeInfo::Uninitialized(offset) => match code.code.at(*offset) {
+                Some(instruction) => match instruction.opcode {
+                    Opcode::New(class_index) => ConstantIndex(class_index),
+                    _ => {
+                        return Err(JvmParseError::InvalidFormat(format!(
+                            "no new instruction at uninitialized offset {}",
+                            offset
+                        )))
+                    }
+                },
+                None => {
+                    return Err(JvmParseError::InvalidFormat(format!(
+                        "invalid uninitialized offset {}",
+                        offset
+                    )))
+                }
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(cpool.resolve_utf8(cpool.resolve_class(class_index)?)?))
+    }
+}
+
+/// Frame types of the class file are kept, so that a table is written back
+/// unchanged. Chop contains the number of removed locals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackMapFrameKind {
+    Same,
+    SameLocals1StackItem(VerificationTypeInfo),
+    SameLocals1StackItemExtended(VerificationTypeInfo),
+    Chop(u8),
+    SameExtended,
+    Append(Vec<VerificationTypeInfo>),
+    Full {
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackMapFrame {
+    /// Absolute offset of the instruction the frame applies to.
+    pub offset: u16,
+    pub kind: StackMapFrameKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackMapTable {
+    pub entries: Vec<StackMapFrame>,
+}
+
+impl StackMapTable {
+    /// Frame for the instruction at `offset`.
+    pub fn frame_at(&self, offset: u16) -> Option<&StackMapFrame> {
+        self.entries
+            .binary_search_by_key(&offset, |frame| frame.offset)
+            .ok()
+            .map(|index| &self.entries[index])
+    }
 }
@@ -2,6 +2,7 @@ use std::convert::TryFrom;
 use std::fmt;
 
 use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::java_string::JavaString;
 use crate::model::ReferenceKind;
 
 pub struct ConstantPool(Vec<Constant>);
@@ -11,6 +12,11 @@ impl ConstantPool {
         Self(constant_pool)
     }
 
+    /// All entries including the unusable slots after long and double constants.
+    pub(crate) fn constants(&self) -> &[Constant] {
+        &self.0
+    }
+
     pub fn all(&self) -> Vec<(ConstantIndex, &Constant)> {
         self.0
             .iter()
@@ -20,16 +26,38 @@ impl ConstantPool {
             .collect()
     }
 
+    /// Constant at the given index. The unusable slots after long and double
+    /// constants are treated as missing.
     pub fn get(&self, index: ConstantIndex) -> Option<&Constant> {
         if index.0 == 0 {
             None
         } else {
-            // TODO: check for invalid constant
-            self.0.get((index.0 - 1) as usize)
+            self.0
+                .get((index.0 - 1) as usize)
+                .filter(|constant| constant.is_valid())
         }
     }
 
+    /// Index of the first Utf8 constant with the given value.
+    pub fn find_utf8(&self, value: &str) -> Option<ConstantIndex> {
+        self.0
+            .iter()
+            .position(|constant| matches!(constant, Constant::Utf8(utf8) if utf8 == value))
+            .map(|i| ConstantIndex(i as u16 + 1))
+    }
+
+    /// Value of a Utf8 constant, which fails for strings with unpaired
+    /// surrogates. Use `resolve_java_string` to access them.
     pub fn resolve_utf8(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        self.resolve_java_string(index)?.as_str().ok_or_else(|| {
+            JvmParseError::InvalidFormat(format!(
+                "constant {} contains unpaired surrogates",
+                index.0
+            ))
+        })
+    }
+
+    pub fn resolve_java_string(&self, index: ConstantIndex) -> JvmParseResult<&JavaString> {
         match self.get(index) {
             Some(Constant::Utf8(utf8)) => Ok(utf8),
             Some(_) => Err(JvmParseError::WrongConstantType(
@@ -42,7 +70,7 @@ impl ConstantPool {
 
     pub fn resolve_class(&self, index: ConstantIndex) -> JvmParseResult<ConstantIndex> {
         match self.get(index) {
-            Some(Constant::Class { name_index }) => Ok(name_index.clone()),
+            Some(Constant::Class { name_index }) => Ok(*name_index),
             Some(_) => Err(JvmParseError::WrongConstantType(
                 index,
                 "expected class".into(),
@@ -50,6 +78,330 @@ impl ConstantPool {
             None => Err(JvmParseError::MissingConstant(index)),
         }
     }
+
+    /// Internal name of a class constant.
+    pub fn resolve_class_name(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        self.resolve_utf8(self.resolve_class(index)?)
+    }
+
+    pub fn resolve_name_and_type(&self, index: ConstantIndex) -> JvmParseResult<NameAndType<'_>> {
+        match self.get(index) {
+            Some(Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            }) => Ok(NameAndType {
+                name: self.resolve_utf8(*name_index)?,
+                descriptor: self.resolve_utf8(*descriptor_index)?,
+            }),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected NameAndType".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_string(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        match self.get(index) {
+            Some(Constant::String(string_index)) => self.resolve_utf8(*string_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected String".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_integer(&self, index: ConstantIndex) -> JvmParseResult<i32> {
+        match self.get(index) {
+            Some(Constant::Integer(value)) => Ok(*value),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Integer".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_float(&self, index: ConstantIndex) -> JvmParseResult<f32> {
+        match self.get(index) {
+            Some(Constant::Float(value)) => Ok(*value),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Float".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_long(&self, index: ConstantIndex) -> JvmParseResult<i64> {
+        match self.get(index) {
+            Some(Constant::Long(value)) => Ok(*value),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Long".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_double(&self, index: ConstantIndex) -> JvmParseResult<f64> {
+        match self.get(index) {
+            Some(Constant::Double(value)) => Ok(*value),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Double".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_field_ref(&self, index: ConstantIndex) -> JvmParseResult<MemberRef<'_>> {
+        match self.get(index) {
+            Some(Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            }) => self.member_ref(*class_index, *name_and_type_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Fieldref".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_method_ref(&self, index: ConstantIndex) -> JvmParseResult<MemberRef<'_>> {
+        match self.get(index) {
+            Some(Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            }) => self.member_ref(*class_index, *name_and_type_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Methodref".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    pub fn resolve_interface_method_ref(
+        &self,
+        index: ConstantIndex,
+    ) -> JvmParseResult<MemberRef<'_>> {
+        match self.get(index) {
+            Some(Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            }) => self.member_ref(*class_index, *name_and_type_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected InterfaceMethodref".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    /// Field, method or interface method reference.
+    pub fn resolve_member_ref(&self, index: ConstantIndex) -> JvmParseResult<MemberRef<'_>> {
+        match self.get(index) {
+            Some(Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            })
+            | Some(Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            })
+            | Some(Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            }) => self.member_ref(*class_index, *name_and_type_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected member reference".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    fn member_ref(
+        &self,
+        class_index: ConstantIndex,
+        name_and_type_index: ConstantIndex,
+    ) -> JvmParseResult<MemberRef<'_>> {
+        let name_and_type = self.resolve_name_and_type(name_and_type_index)?;
+        Ok(MemberRef {
+            class: self.resolve_class_name(class_index)?,
+            name: name_and_type.name,
+            descriptor: name_and_type.descriptor,
+        })
+    }
+
+    /// Method handle whose reference has the constant type required by its
+    /// kind, see JVMS 4.4.8.
+    pub fn resolve_method_handle(&self, index: ConstantIndex) -> JvmParseResult<MethodHandle<'_>> {
+        match self.get(index) {
+            Some(Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            }) => {
+                let reference = self.get(*reference_index);
+                let valid = match reference_kind {
+                    ReferenceKind::GetField
+                    | ReferenceKind::GetStatic
+                    | ReferenceKind::PutField
+                    | ReferenceKind::PutStatic => {
+                        matches!(reference, Some(Constant::Fieldref { .. }))
+                    }
+                    ReferenceKind::InvokeVirtual | ReferenceKind::NewInvokeSpecial => {
+                        matches!(reference, Some(Constant::Methodref { .. }))
+                    }
+                    ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial => matches!(
+                        reference,
+                        Some(Constant::Methodref { .. })
+                            | Some(Constant::InterfaceMethodref { .. })
+                    ),
+                    ReferenceKind::InvokeInterface => {
+                        matches!(reference, Some(Constant::InterfaceMethodref { .. }))
+                    }
+                };
+                if reference.is_some() && !valid {
+                    return Err(JvmParseError::WrongConstantType(
+                        *reference_index,
+                        format!("can not be referenced by {:?}", reference_kind),
+                    ));
+                }
+                Ok(MethodHandle {
+                    reference_kind: *reference_kind,
+                    reference: self.resolve_member_ref(*reference_index)?,
+                })
+            }
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected MethodHandle".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    /// Method descriptor of a MethodType constant.
+    pub fn resolve_method_type(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        match self.get(index) {
+            Some(Constant::MethodType { descriptor_index }) => self.resolve_utf8(*descriptor_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected MethodType".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    /// Constant which can be loaded by `ldc` or passed to a bootstrap method.
+    pub fn resolve_loadable(&self, index: ConstantIndex) -> JvmParseResult<Loadable<'_>> {
+        Ok(match self.get(index) {
+            Some(Constant::Integer(value)) => Loadable::Integer(*value),
+            Some(Constant::Float(value)) => Loadable::Float(*value),
+            Some(Constant::Long(value)) => Loadable::Long(*value),
+            Some(Constant::Double(value)) => Loadable::Double(*value),
+            Some(Constant::Class { name_index }) => {
+                Loadable::Class(self.resolve_utf8(*name_index)?)
+            }
+            Some(Constant::String(_)) => Loadable::String(self.resolve_string(index)?),
+            Some(Constant::MethodHandle { .. }) => {
+                Loadable::MethodHandle(self.resolve_method_handle(index)?)
+            }
+            Some(Constant::MethodType { .. }) => {
+                Loadable::MethodType(self.resolve_method_type(index)?)
+            }
+            Some(Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }) => {
+                let name_and_type = self.resolve_name_and_type(*name_and_type_index)?;
+                Loadable::Dynamic {
+                    bootstrap_method_attr_index: *bootstrap_method_attr_index,
+                    name: name_and_type.name,
+                    descriptor: name_and_type.descriptor,
+                }
+            }
+            Some(_) => {
+                return Err(JvmParseError::WrongConstantType(
+                    index,
+                    "expected loadable constant".into(),
+                ))
+            }
+            None => return Err(JvmParseError::MissingConstant(index)),
+        })
+    }
+
+    /// Name of a module constant like `java.base`.
+    pub fn resolve_module_name(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        match self.get(index) {
+            Some(Constant::Module { name_index }) => self.resolve_utf8(*name_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Module".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+
+    /// Internal name of a package constant like `java/lang`.
+    pub fn resolve_package_name(&self, index: ConstantIndex) -> JvmParseResult<&str> {
+        match self.get(index) {
+            Some(Constant::Package { name_index }) => self.resolve_utf8(*name_index),
+            Some(_) => Err(JvmParseError::WrongConstantType(
+                index,
+                "expected Package".into(),
+            )),
+            None => Err(JvmParseError::MissingConstant(index)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameAndType<'a> {
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
+
+/// Resolved field, method or interface method reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemberRef<'a> {
+    /// Internal name of the class.
+    pub class: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MethodHandle<'a> {
+    pub reference_kind: ReferenceKind,
+    pub reference: MemberRef<'a>,
+}
+
+/// Resolved constant which can be loaded onto the operand stack.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Loadable<'a> {
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    /// Internal name of a class or descriptor of an array type.
+    Class(&'a str),
+    String(&'a str),
+    MethodHandle(MethodHandle<'a>),
+    /// Method descriptor.
+    MethodType(&'a str),
+    /// Dynamically-computed constant. The bootstrap method is resolved by
+    /// `ClassFile::resolve_dynamic_constant`.
+    Dynamic {
+        /// Index into the `BootstrapMethods` attribute.
+        bootstrap_method_attr_index: u16,
+        name: &'a str,
+        /// Field descriptor of the constant.
+        descriptor: &'a str,
+    },
 }
 
 pub enum ConstantTag {
@@ -66,7 +418,10 @@ pub enum ConstantTag {
     Utf8 = 1,
     MethodHandle = 15,
     MethodType = 16,
+    Dynamic = 17,
     InvokeDynamic = 18,
+    Module = 19,
+    Package = 20,
 }
 
 impl TryFrom<u8> for ConstantTag {
@@ -87,7 +442,10 @@ impl TryFrom<u8> for ConstantTag {
             1 => ConstantTag::Utf8,
             15 => ConstantTag::MethodHandle,
             16 => ConstantTag::MethodType,
+            17 => ConstantTag::Dynamic,
             18 => ConstantTag::InvokeDynamic,
+            19 => ConstantTag::Module,
+            20 => ConstantTag::Package,
             _ => {
                 return Err(JvmParseError::InvalidFormat(format!(
                     "unknown constant pool tag: {}",
@@ -133,7 +491,7 @@ pub enum Constant {
         name_index: ConstantIndex,
         descriptor_index: ConstantIndex,
     },
-    Utf8(String),
+    Utf8(JavaString),
     MethodHandle {
         reference_kind: ReferenceKind,
         reference_index: ConstantIndex,
@@ -141,10 +499,22 @@ pub enum Constant {
     MethodType {
         descriptor_index: ConstantIndex,
     },
+    Dynamic {
+        /// Index into the `BootstrapMethods` attribute.
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: ConstantIndex,
+    },
     InvokeDynamic {
-        bootstrap_method_attr_index: ConstantIndex,
+        /// Index into the `BootstrapMethods` attribute.
+        bootstrap_method_attr_index: u16,
         name_and_type_index: ConstantIndex,
     },
+    Module {
+        name_index: ConstantIndex,
+    },
+    Package {
+        name_index: ConstantIndex,
+    },
     InvalidConstant,
 }
 
@@ -0,0 +1,237 @@
+//! Strings of Utf8 constants in Modified UTF-8 (JVMS 4.4.7).
+//!
+//! Java strings are sequences of UTF-16 code units, which may contain
+//! unpaired surrogates. These have no representation as Rust string, so
+//! `JavaString` keeps the exact bytes and converts to Rust strings either
+//! fallibly or lossy.
+
+use std::borrow::Cow;
+use std::char::{decode_utf16, REPLACEMENT_CHARACTER};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use cesu8::to_java_cesu8;
+
+#[derive(Clone)]
+pub struct JavaString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    /// String with the same encoding in UTF-8 and Modified UTF-8.
+    Utf8(String),
+    /// String containing NUL or supplementary characters, or unpaired
+    /// surrogates if `string` is `None`.
+    Encoded {
+        bytes: Vec<u8>,
+        string: Option<String>,
+    },
+}
+
+impl JavaString {
+    /// Takes Modified UTF-8 bytes, `None` if they are malformed.
+    pub fn from_modified_utf8(bytes: Vec<u8>) -> Option<JavaString> {
+        if !is_modified_utf8(&bytes) {
+            return None;
+        }
+        Some(JavaString(match String::from_utf8(bytes) {
+            Ok(string) => Repr::Utf8(string),
+            Err(error) => {
+                let bytes = error.into_bytes();
+                let string = decode_utf16(code_units(&bytes)).collect::<Result<_, _>>();
+                Repr::Encoded {
+                    bytes,
+                    string: string.ok(),
+                }
+            }
+        }))
+    }
+
+    /// Modified UTF-8 bytes as stored in the class file.
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.0 {
+            Repr::Utf8(string) => string.as_bytes(),
+            Repr::Encoded { bytes, .. } => bytes,
+        }
+    }
+
+    /// The string, `None` if it contains unpaired surrogates.
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.0 {
+            Repr::Utf8(string) => Some(string),
+            Repr::Encoded { string, .. } => string.as_deref(),
+        }
+    }
+
+    /// The string with unpaired surrogates replaced by U+FFFD.
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        match self.as_str() {
+            Some(string) => Cow::Borrowed(string),
+            None => Cow::Owned(
+                decode_utf16(code_units(self.as_bytes()))
+                    .map(|c| c.unwrap_or(REPLACEMENT_CHARACTER))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&str> for JavaString {
+    fn from(value: &str) -> Self {
+        JavaString(match to_java_cesu8(value) {
+            Cow::Borrowed(_) => Repr::Utf8(value.to_string()),
+            Cow::Owned(bytes) => Repr::Encoded {
+                bytes,
+                string: Some(value.to_string()),
+            },
+        })
+    }
+}
+
+impl PartialEq for JavaString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for JavaString {}
+
+impl PartialEq<str> for JavaString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for JavaString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl Hash for JavaString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state)
+    }
+}
+
+impl fmt::Debug for JavaString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
+
+impl fmt::Display for JavaString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+/// Decodes Modified UTF-8 bytes without copying them if they are valid UTF-8
+/// as well. `None` for malformed bytes and unpaired surrogates.
+pub(crate) fn decode_modified_utf8(bytes: &[u8]) -> Option<Cow<'_, str>> {
+    if !is_modified_utf8(bytes) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(string) => Some(Cow::Borrowed(string)),
+        Err(_) => decode_utf16(code_units(bytes))
+            .collect::<Result<_, _>>()
+            .ok()
+            .map(Cow::Owned),
+    }
+}
+
+/// Whether the bytes consist of one to three byte sequences without NUL
+/// bytes. Overlong sequences are allowed like in `DataInput.readUTF`.
+fn is_modified_utf8(bytes: &[u8]) -> bool {
+    let mut i = 0;
+    while i < bytes.len() {
+        let length = match bytes[i] {
+            0x01..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => return false,
+        };
+        match bytes.get(i + 1..i + length) {
+            Some(continuation) if continuation.iter().all(|b| b & 0xC0 == 0x80) => i += length,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// UTF-16 code units of Modified UTF-8 bytes, which must be well-formed.
+fn code_units(bytes: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        let byte = *bytes.get(i)? as u16;
+        let (unit, length) = match byte {
+            0x00..=0x7F => (byte, 1),
+            0xC0..=0xDF => (((byte & 0x1F) << 6) | (bytes[i + 1] as u16 & 0x3F), 2),
+            _ => (
+                ((byte & 0x0F) << 12)
+                    | ((bytes[i + 1] as u16 & 0x3F) << 6)
+                    | (bytes[i + 2] as u16 & 0x3F),
+                3,
+            ),
+        };
+        i += length;
+        Some(unit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn java_string(bytes: &[u8]) -> JavaString {
+        JavaString::from_modified_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn plain() {
+        let string = java_string(b"java/lang/Object");
+        assert_eq!(string.as_str(), Some("java/lang/Object"));
+        assert_eq!(string, JavaString::from("java/lang/Object"));
+    }
+
+    #[test]
+    fn nul_and_supplementary() {
+        let bytes = [b'a', 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80];
+        let string = java_string(&bytes);
+        assert_eq!(string.as_str(), Some("a\0\u{1F600}"));
+        assert_eq!(string.as_bytes(), bytes);
+        assert_eq!(JavaString::from("a\0\u{1F600}").as_bytes(), bytes);
+        assert_eq!(decode_modified_utf8(&bytes).unwrap(), "a\0\u{1F600}");
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        let bytes = [b'x', 0xED, 0xA0, 0x80, b'y', 0xED, 0xB8, 0x80];
+        let string = java_string(&bytes);
+        assert_eq!(string.as_str(), None);
+        assert_eq!(string.to_string_lossy(), "x\u{FFFD}y\u{FFFD}");
+        assert_eq!(string.as_bytes(), bytes);
+        assert_eq!(decode_modified_utf8(&bytes), None);
+    }
+
+    #[test]
+    fn overlong() {
+        let string = java_string(&[0xC1, 0x81]);
+        assert_eq!(string.as_str(), Some("A"));
+        assert_eq!(string.as_bytes(), [0xC1, 0x81]);
+        assert_ne!(string, JavaString::from("A"));
+    }
+
+    #[test]
+    fn malformed() {
+        for bytes in [
+            &[0x00][..],
+            &[0xF0, 0x9F, 0x98, 0x80],
+            &[0xC0],
+            &[0xE0, 0x80, 0x41],
+        ] {
+            assert!(JavaString::from_modified_utf8(bytes.to_vec()).is_none());
+            assert_eq!(decode_modified_utf8(bytes), None);
+        }
+    }
+}
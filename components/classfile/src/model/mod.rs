@@ -3,26 +3,51 @@ use std::fmt;
 
 use bitflags::bitflags;
 
-use constants::{ConstantIndex, ConstantPool};
+use constants::{Constant, ConstantIndex, ConstantPool, Loadable, MethodHandle};
 
+use crate::descriptor::{parse_field_descriptor, parse_method_descriptor};
 use crate::error::{JvmParseError, JvmParseResult};
-use crate::model::attributes::{Code, ConstantValue};
+use crate::model::annotations::{Annotation, ElementValue, TypeAnnotation};
+use crate::model::attributes::{
+    BootstrapMethod, CallSite, Code, ConstantValue, DynamicConstant, EnclosingMethod, InnerClass,
+    LineNumber, LocalVariable, LocalVariableType, RecordComponent, StackMapTable,
+};
+use crate::model::module::{Module, ModuleDescriptor};
+use crate::model::view::ClassView;
 
+/// Implements `FlagBits` for bitflags types defined in the same module, where
+/// the `bits` field is accessible.
+macro_rules! flag_bits {
+    ($($flags:ty),*) => {
+        $(
+            impl crate::model::FlagBits for $flags {
+                fn from_bits_retain(bits: u16) -> $flags {
+                    Self { bits }
+                }
+            }
+        )*
+    };
+}
+
+pub mod annotations;
 pub mod attributes;
 pub mod constants;
+pub mod java_string;
+pub mod module;
+pub mod view;
 
 pub struct ClassFile {
     pub(crate) magic: u32,
     pub(crate) minor_version: u16,
     pub(crate) major_version: u16,
     pub(crate) constants: ConstantPool,
-    pub(crate) access_flags: AccessFlags,
+    pub(crate) access_flags: ClassAccessFlags,
     pub(crate) this_class: ConstantIndex,
     pub(crate) super_class: ConstantIndex,
     pub(crate) interfaces: Vec<ConstantIndex>,
     pub(crate) fields: Vec<Field>,
     pub(crate) methods: Vec<Method>,
-    pub(crate) attributes: Vec<Attribute>,
+    pub(crate) attributes: Attributes,
 }
 
 impl ClassFile {
@@ -38,8 +63,8 @@ impl ClassFile {
         self.major_version
     }
 
-    pub fn access_flags(&self) -> AccessFlags {
-        self.access_flags.clone()
+    pub fn access_flags(&self) -> ClassAccessFlags {
+        self.access_flags
     }
 
     pub fn constant_pool(&self) -> &ConstantPool {
@@ -69,10 +94,243 @@ impl ClassFile {
     pub fn attributes(&self) -> &[Attribute] {
         &self.attributes
     }
+
+    /// View with resolved names and parsed descriptors.
+    pub fn view(&self) -> ClassView<'_> {
+        ClassView::new(self)
+    }
+
+    pub fn bootstrap_methods(&self) -> &[BootstrapMethod] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::BootstrapMethods(bootstrap_methods) => Some(&bootstrap_methods[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    /// Resolves the InvokeDynamic constant referenced by an `invokedynamic`
+    /// instruction.
+    pub fn resolve_call_site(&self, index: ConstantIndex) -> JvmParseResult<CallSite<'_>> {
+        let cpool = &self.constants;
+        let (bootstrap_method_attr_index, name_and_type_index) = match cpool.get(index) {
+            Some(Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }) => (*bootstrap_method_attr_index, *name_and_type_index),
+            Some(_) => {
+                return Err(JvmParseError::WrongConstantType(
+                    index,
+                    "expected InvokeDynamic".into(),
+                ))
+            }
+            None => return Err(JvmParseError::MissingConstant(index)),
+        };
+
+        let (bootstrap_method, static_arguments) =
+            self.resolve_bootstrap_method(bootstrap_method_attr_index)?;
+        let name_and_type = cpool.resolve_name_and_type(name_and_type_index)?;
+        let descriptor = parse_method_descriptor(name_and_type.descriptor).ok_or_else(|| {
+            JvmParseError::InvalidFormat(format!(
+                "invalid method descriptor: {}",
+                name_and_type.descriptor
+            ))
+        })?;
+
+        Ok(CallSite {
+            bootstrap_method,
+            static_arguments,
+            name: name_and_type.name,
+            descriptor,
+        })
+    }
+
+    /// Resolves a Dynamic constant, for example one loaded by `ldc`.
+    pub fn resolve_dynamic_constant(
+        &self,
+        index: ConstantIndex,
+    ) -> JvmParseResult<DynamicConstant<'_>> {
+        let cpool = &self.constants;
+        let (bootstrap_method_attr_index, name_and_type_index) = match cpool.get(index) {
+            Some(Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }) => (*bootstrap_method_attr_index, *name_and_type_index),
+            Some(_) => {
+                return Err(JvmParseError::WrongConstantType(
+                    index,
+                    "expected Dynamic".into(),
+                ))
+            }
+            None => return Err(JvmParseError::MissingConstant(index)),
+        };
+
+        let (bootstrap_method, static_arguments) =
+            self.resolve_bootstrap_method(bootstrap_method_attr_index)?;
+        let name_and_type = cpool.resolve_name_and_type(name_and_type_index)?;
+        let descriptor = parse_field_descriptor(name_and_type.descriptor).ok_or_else(|| {
+            JvmParseError::InvalidFormat(format!(
+                "invalid field descriptor: {}",
+                name_and_type.descriptor
+            ))
+        })?;
+
+        Ok(DynamicConstant {
+            bootstrap_method,
+            static_arguments,
+            name: name_and_type.name,
+            descriptor,
+        })
+    }
+
+    /// Method handle and static arguments of an entry in the
+    /// `BootstrapMethods` attribute.
+    fn resolve_bootstrap_method(
+        &self,
+        bootstrap_method_attr_index: u16,
+    ) -> JvmParseResult<(MethodHandle<'_>, Vec<Loadable<'_>>)> {
+        let cpool = &self.constants;
+        let bootstrap_method = self
+            .bootstrap_methods()
+            .get(bootstrap_method_attr_index as usize)
+            .ok_or_else(|| {
+                JvmParseError::InvalidFormat(format!(
+                    "missing bootstrap method {}",
+                    bootstrap_method_attr_index
+                ))
+            })?;
+
+        Ok((
+            cpool.resolve_method_handle(bootstrap_method.bootstrap_method_ref)?,
+            bootstrap_method
+                .bootstrap_arguments
+                .iter()
+                .map(|argument| cpool.resolve_loadable(*argument))
+                .collect::<JvmParseResult<Vec<_>>>()?,
+        ))
+    }
+
+    /// Entries of the `InnerClasses` attribute.
+    pub fn inner_classes(&self) -> &[InnerClass] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::InnerClasses(inner_classes) => Some(&inner_classes[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    pub fn enclosing_method(&self) -> Option<&EnclosingMethod> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::EnclosingMethod(enclosing_method) => Some(enclosing_method),
+                _ => None,
+            })
+    }
+
+    /// Class constant of the immediately enclosing class of a nested class,
+    /// `None` for top level classes.
+    pub fn outer_class(&self) -> JvmParseResult<Option<ConstantIndex>> {
+        if let Some(enclosing_method) = self.enclosing_method() {
+            return Ok(Some(enclosing_method.class_index));
+        }
+
+        let this_class = self.constants.resolve_class_name(self.this_class)?;
+        for inner_class in self.inner_classes() {
+            if self
+                .constants
+                .resolve_class_name(inner_class.inner_class_info_index)?
+                == this_class
+            {
+                return Ok(inner_class.outer_class_info_index);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Class constant of the nest host, `None` when the class is itself a
+    /// nest host.
+    pub fn nest_host(&self) -> Option<ConstantIndex> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::NestHost(host_class_index) => Some(*host_class_index),
+                _ => None,
+            })
+    }
+
+    /// Class constants of the nest members of a nest host.
+    pub fn nest_members(&self) -> &[ConstantIndex] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::NestMembers(classes) => Some(&classes[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    pub fn is_record(&self) -> bool {
+        self.record_components().is_some()
+    }
+
+    /// Components of a record class, `None` for other classes.
+    pub fn record_components(&self) -> Option<&[RecordComponent]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Record(components) => Some(&components[..]),
+                _ => None,
+            })
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| matches!(attribute, Attribute::PermittedSubclasses(_)))
+    }
+
+    /// Class constants of the permitted subclasses of a sealed class.
+    pub fn permitted_subclasses(&self) -> &[ConstantIndex] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::PermittedSubclasses(classes) => Some(&classes[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    /// Module declaration of a `module-info` class, `None` for other classes.
+    pub fn module_descriptor(&self) -> JvmParseResult<Option<ModuleDescriptor<'_>>> {
+        module::module_descriptor(&self.attributes, &self.constants)
+    }
+
+    /// Visible and invisible annotations of the class.
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation> {
+        annotations::annotations(&self.attributes)
+    }
+
+    /// Annotation with a type descriptor like `Ljava/lang/Deprecated;`.
+    pub fn find_annotation(&self, descriptor: &str) -> Option<&Annotation> {
+        annotations::find_annotation(&self.attributes, &self.constants, descriptor)
+    }
+
+    pub fn has_annotation(&self, descriptor: &str) -> bool {
+        self.find_annotation(descriptor).is_some()
+    }
+
+    /// Visible and invisible type annotations in the class declaration.
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation> {
+        annotations::type_annotations(&self.attributes)
+    }
 }
 
 bitflags! {
-    pub struct AccessFlags: u16 {
+    pub struct ClassAccessFlags: u16 {
         /// Declared public; may be accessed from outside its package.
         const PUBLIC = 0x0001;
 
@@ -102,6 +360,139 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Flags of a nested class in the `InnerClasses` attribute.
+    pub struct InnerClassAccessFlags: u16 {
+        /// Marked or implicitly public in source.
+        const PUBLIC = 0x0001;
+
+        /// Marked private in source.
+        const PRIVATE = 0x0002;
+
+        /// Marked protected in source.
+        const PROTECTED = 0x0004;
+
+        /// Marked or implicitly static in source.
+        const STATIC = 0x0008;
+
+        /// Marked or implicitly final in source.
+        const FINAL = 0x0010;
+
+        /// Was an interface in source.
+        const INTERFACE = 0x0200;
+
+        /// Marked or implicitly abstract in source.
+        const ABSTRACT = 0x0400;
+
+        /// Declared synthetic; not present in the source code.
+        const SYNTHETIC = 0x1000;
+
+        /// Declared as an annotation type.
+        const ANNOTATION = 0x2000;
+
+        /// Declared as an enum type.
+        const ENUM = 0x4000;
+    }
+}
+
+bitflags! {
+    pub struct FieldAccessFlags: u16 {
+        /// Declared public; may be accessed from outside its package.
+        const PUBLIC = 0x0001;
+
+        /// Declared private; accessible only within the defining class and other classes belonging to the same nest.
+        const PRIVATE = 0x0002;
+
+        /// Declared protected; may be accessed within subclasses.
+        const PROTECTED = 0x0004;
+
+        /// Declared static.
+        const STATIC = 0x0008;
+
+        /// Declared final; never directly assigned to after object construction.
+        const FINAL = 0x0010;
+
+        /// Declared volatile; cannot be cached.
+        const VOLATILE = 0x0040;
+
+        /// Declared transient; not written or read by a persistent object manager.
+        const TRANSIENT = 0x0080;
+
+        /// Declared synthetic; not present in the source code.
+        const SYNTHETIC = 0x1000;
+
+        /// Declared as an element of an enum.
+        const ENUM = 0x4000;
+    }
+}
+
+bitflags! {
+    pub struct MethodAccessFlags: u16 {
+        /// Declared public; may be accessed from outside its package.
+        const PUBLIC = 0x0001;
+
+        /// Declared private; accessible only within the defining class and other classes belonging to the same nest.
+        const PRIVATE = 0x0002;
+
+        /// Declared protected; may be accessed within subclasses.
+        const PROTECTED = 0x0004;
+
+        /// Declared static.
+        const STATIC = 0x0008;
+
+        /// Declared final; must not be overridden.
+        const FINAL = 0x0010;
+
+        /// Declared synchronized; invocation is wrapped by a monitor use.
+        const SYNCHRONIZED = 0x0020;
+
+        /// A bridge method, generated by the compiler.
+        const BRIDGE = 0x0040;
+
+        /// Declared with variable number of arguments.
+        const VARARGS = 0x0080;
+
+        /// Declared native; implemented in a language other than Java.
+        const NATIVE = 0x0100;
+
+        /// Declared abstract; no implementation is provided.
+        const ABSTRACT = 0x0400;
+
+        /// Declared strictfp; floating-point mode is FP-strict.
+        const STRICT = 0x0800;
+
+        /// Declared synthetic; not present in the source code.
+        const SYNTHETIC = 0x1000;
+    }
+}
+
+bitflags! {
+    /// Flags of a parameter in the `MethodParameters` attribute.
+    pub struct ParameterAccessFlags: u16 {
+        /// Declared final.
+        const FINAL = 0x0010;
+
+        /// Not explicitly or implicitly declared in source code.
+        const SYNTHETIC = 0x1000;
+
+        /// Implicitly declared in source code.
+        const MANDATED = 0x8000;
+    }
+}
+
+/// Access flags with unknown bits kept, so they are written back unchanged.
+pub(crate) trait FlagBits {
+    fn from_bits_retain(bits: u16) -> Self;
+}
+
+flag_bits!(
+    ClassAccessFlags,
+    InnerClassAccessFlags,
+    FieldAccessFlags,
+    MethodAccessFlags,
+    ParameterAccessFlags
+);
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ReferenceKind {
     GetField = 1,
@@ -139,18 +530,140 @@ impl TryFrom<u8> for ReferenceKind {
     }
 }
 
+#[derive(Debug)]
 pub struct Field {
-    pub access_flags: AccessFlags,
+    pub access_flags: FieldAccessFlags,
     pub name_index: ConstantIndex,
     pub descriptor_index: ConstantIndex,
-    pub attributes: Vec<Attribute>,
+    pub attributes: Attributes,
 }
 
+impl Field {
+    /// Visible and invisible annotations of the field.
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation> {
+        annotations::annotations(&self.attributes)
+    }
+
+    /// Annotation with a type descriptor like `Ljava/lang/Deprecated;`.
+    pub fn find_annotation(&self, cpool: &ConstantPool, descriptor: &str) -> Option<&Annotation> {
+        annotations::find_annotation(&self.attributes, cpool, descriptor)
+    }
+
+    pub fn has_annotation(&self, cpool: &ConstantPool, descriptor: &str) -> bool {
+        self.find_annotation(cpool, descriptor).is_some()
+    }
+
+    /// Visible and invisible type annotations of the field type.
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation> {
+        annotations::type_annotations(&self.attributes)
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.attributes)
+    }
+
+    /// Whether the field is marked synthetic by flag or attribute.
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags.contains(FieldAccessFlags::SYNTHETIC) || is_synthetic(&self.attributes)
+    }
+}
+
+#[derive(Debug)]
 pub struct Method {
-    pub access_flags: AccessFlags,
+    pub access_flags: MethodAccessFlags,
     pub name_index: ConstantIndex,
     pub descriptor_index: ConstantIndex,
-    pub attributes: Vec<Attribute>,
+    pub attributes: Attributes,
+}
+
+impl Method {
+    /// Visible and invisible annotations of the method.
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation> {
+        annotations::annotations(&self.attributes)
+    }
+
+    /// Annotation with a type descriptor like `Ljava/lang/Deprecated;`.
+    pub fn find_annotation(&self, cpool: &ConstantPool, descriptor: &str) -> Option<&Annotation> {
+        annotations::find_annotation(&self.attributes, cpool, descriptor)
+    }
+
+    pub fn has_annotation(&self, cpool: &ConstantPool, descriptor: &str) -> bool {
+        self.find_annotation(cpool, descriptor).is_some()
+    }
+
+    /// Visible and invisible type annotations in the method declaration.
+    /// Annotations in the method body are part of the code.
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation> {
+        annotations::type_annotations(&self.attributes)
+    }
+
+    /// Visible and invisible annotations of the parameter with the given
+    /// index. Synthetic parameters might not be counted.
+    pub fn parameter_annotations(&self, parameter: usize) -> impl Iterator<Item = &Annotation> {
+        annotations::parameter_annotations(&self.attributes, parameter)
+    }
+
+    /// Class constants of the checked exceptions in the throws clause.
+    pub fn exceptions(&self) -> &[ConstantIndex] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Exceptions(exceptions) => Some(&exceptions[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    /// Names and flags of the parameters from the `MethodParameters`
+    /// attribute, empty when the attribute is missing.
+    pub fn parameters(&self) -> &[MethodParameter] {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::MethodParameters(parameters) => Some(&parameters[..]),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.attributes)
+    }
+
+    /// Whether the method is marked synthetic by flag or attribute.
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags.contains(MethodAccessFlags::SYNTHETIC) || is_synthetic(&self.attributes)
+    }
+
+    /// Default value of an annotation type element.
+    pub fn annotation_default(&self) -> Option<&ElementValue> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::AnnotationDefault(value) => Some(value),
+                _ => None,
+            })
+    }
+}
+
+fn is_deprecated(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| matches!(attribute, Attribute::Deprecated))
+}
+
+fn is_synthetic(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| matches!(attribute, Attribute::Synthetic))
+}
+
+/// Entry of the `MethodParameters` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodParameter {
+    /// Utf8 constant, `None` for a parameter without name.
+    pub name_index: Option<ConstantIndex>,
+    pub access_flags: ParameterAccessFlags,
 }
 
 #[derive(Debug)]
@@ -158,5 +671,156 @@ pub enum Attribute {
     Code(Code),
     ConstantValue(ConstantValue),
     SourceFile(ConstantIndex), // MUST be index to Utf8
-    Unknown { name: ConstantIndex, value: Vec<u8> },
+    Signature(ConstantIndex),  // MUST be index to Utf8
+    StackMapTable(StackMapTable),
+    LineNumberTable(Vec<LineNumber>),
+    LocalVariableTable(Vec<LocalVariable>),
+    LocalVariableTypeTable(Vec<LocalVariableType>),
+    RuntimeVisibleAnnotations(Vec<Annotation>),
+    RuntimeInvisibleAnnotations(Vec<Annotation>),
+    /// Annotations of each parameter.
+    RuntimeVisibleParameterAnnotations(Vec<Vec<Annotation>>),
+    RuntimeInvisibleParameterAnnotations(Vec<Vec<Annotation>>),
+    AnnotationDefault(ElementValue),
+    BootstrapMethods(Vec<BootstrapMethod>),
+    Module(Module),
+    /// Package constants of all packages of the module.
+    ModulePackages(Vec<ConstantIndex>),
+    /// Class constant of the main class of the module.
+    ModuleMainClass(ConstantIndex),
+    InnerClasses(Vec<InnerClass>),
+    EnclosingMethod(EnclosingMethod),
+    /// Class constant of the nest host.
+    NestHost(ConstantIndex),
+    /// Class constants of the nest members.
+    NestMembers(Vec<ConstantIndex>),
+    Record(Vec<RecordComponent>),
+    /// Class constants of the permitted subclasses.
+    PermittedSubclasses(Vec<ConstantIndex>),
+    /// Class constants of the checked exceptions a method may throw.
+    Exceptions(Vec<ConstantIndex>),
+    MethodParameters(Vec<MethodParameter>),
+    Deprecated,
+    Synthetic,
+    RuntimeVisibleTypeAnnotations(Vec<TypeAnnotation>),
+    RuntimeInvisibleTypeAnnotations(Vec<TypeAnnotation>),
+    Unknown {
+        name: ConstantIndex,
+        value: Vec<u8>,
+    },
+}
+
+impl Attribute {
+    /// Name of the attribute in the class file, `None` for unknown attributes.
+    pub(crate) fn known_name(&self) -> Option<&'static str> {
+        match self {
+            Attribute::Code(_) => Some("Code"),
+            Attribute::ConstantValue(_) => Some("ConstantValue"),
+            Attribute::SourceFile(_) => Some("SourceFile"),
+            Attribute::Signature(_) => Some("Signature"),
+            Attribute::StackMapTable(_) => Some("StackMapTable"),
+            Attribute::LineNumberTable(_) => Some("LineNumberTable"),
+            Attribute::LocalVariableTable(_) => Some("LocalVariableTable"),
+            Attribute::LocalVariableTypeTable(_) => Some("LocalVariableTypeTable"),
+            Attribute::RuntimeVisibleAnnotations(_) => Some("RuntimeVisibleAnnotations"),
+            Attribute::RuntimeInvisibleAnnotations(_) => Some("RuntimeInvisibleAnnotations"),
+            Attribute::RuntimeVisibleParameterAnnotations(_) => {
+                Some("RuntimeVisibleParameterAnnotations")
+            }
+            Attribute::RuntimeInvisibleParameterAnnotations(_) => {
+                Some("RuntimeInvisibleParameterAnnotations")
+            }
+            Attribute::AnnotationDefault(_) => Some("AnnotationDefault"),
+            Attribute::BootstrapMethods(_) => Some("BootstrapMethods"),
+            Attribute::Module(_) => Some("Module"),
+            Attribute::ModulePackages(_) => Some("ModulePackages"),
+            Attribute::ModuleMainClass(_) => Some("ModuleMainClass"),
+            Attribute::InnerClasses(_) => Some("InnerClasses"),
+            Attribute::EnclosingMethod(_) => Some("EnclosingMethod"),
+            Attribute::NestHost(_) => Some("NestHost"),
+            Attribute::NestMembers(_) => Some("NestMembers"),
+            Attribute::Record(_) => Some("Record"),
+            Attribute::PermittedSubclasses(_) => Some("PermittedSubclasses"),
+            Attribute::Exceptions(_) => Some("Exceptions"),
+            Attribute::MethodParameters(_) => Some("MethodParameters"),
+            Attribute::Deprecated => Some("Deprecated"),
+            Attribute::Synthetic => Some("Synthetic"),
+            Attribute::RuntimeVisibleTypeAnnotations(_) => Some("RuntimeVisibleTypeAnnotations"),
+            Attribute::RuntimeInvisibleTypeAnnotations(_) => {
+                Some("RuntimeInvisibleTypeAnnotations")
+            }
+            Attribute::Unknown { .. } => None,
+        }
+    }
+}
+
+/// Attributes of a class, member, `Code` attribute or record component.
+///
+/// Dereferences to a `Vec<Attribute>`. Parsed attributes remember the index of
+/// their name constant, so writing them back keeps that index even if the
+/// constant pool holds the same name more than once.
+#[derive(Default)]
+pub struct Attributes {
+    attributes: Vec<Attribute>,
+    name_indices: Vec<ConstantIndex>,
+}
+
+impl Attributes {
+    pub fn new() -> Attributes {
+        Attributes::default()
+    }
+
+    pub(crate) fn parsed(
+        attributes: Vec<Attribute>,
+        name_indices: Vec<ConstantIndex>,
+    ) -> Attributes {
+        Attributes {
+            attributes,
+            name_indices,
+        }
+    }
+
+    /// Name index the attribute at `index` was parsed with. Only a hint, the
+    /// attributes may have been modified since.
+    pub(crate) fn name_index(&self, index: usize) -> Option<ConstantIndex> {
+        self.name_indices.get(index).copied()
+    }
+}
+
+impl std::ops::Deref for Attributes {
+    type Target = Vec<Attribute>;
+
+    fn deref(&self) -> &Vec<Attribute> {
+        &self.attributes
+    }
+}
+
+impl std::ops::DerefMut for Attributes {
+    fn deref_mut(&mut self) -> &mut Vec<Attribute> {
+        &mut self.attributes
+    }
+}
+
+impl From<Vec<Attribute>> for Attributes {
+    fn from(attributes: Vec<Attribute>) -> Attributes {
+        Attributes {
+            attributes,
+            name_indices: vec![],
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = &'a Attribute;
+    type IntoIter = std::slice::Iter<'a, Attribute>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attributes.iter()
+    }
+}
+
+impl fmt::Debug for Attributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.attributes, f)
+    }
 }
@@ -0,0 +1,247 @@
+use bitflags::bitflags;
+
+use crate::error::JvmParseResult;
+use crate::model::constants::{ConstantIndex, ConstantPool};
+use crate::model::Attribute;
+
+bitflags! {
+    pub struct ModuleFlags: u16 {
+        /// Module is open, all its packages are opened.
+        const OPEN = 0x0020;
+
+        /// Not explicitly or implicitly declared.
+        const SYNTHETIC = 0x1000;
+
+        /// Implicitly declared.
+        const MANDATED = 0x8000;
+    }
+}
+
+bitflags! {
+    pub struct RequiresFlags: u16 {
+        /// Modules depending on this module also depend on the required module.
+        const TRANSITIVE = 0x0020;
+
+        /// Dependency is mandatory at compile time, but optional at run time.
+        const STATIC_PHASE = 0x0040;
+
+        /// Not explicitly or implicitly declared.
+        const SYNTHETIC = 0x1000;
+
+        /// Implicitly declared.
+        const MANDATED = 0x8000;
+    }
+}
+
+bitflags! {
+    /// Flags of an `exports` or `opens` directive.
+    pub struct PackageFlags: u16 {
+        /// Not explicitly or implicitly declared.
+        const SYNTHETIC = 0x1000;
+
+        /// Implicitly declared.
+        const MANDATED = 0x8000;
+    }
+}
+
+flag_bits!(ModuleFlags, RequiresFlags, PackageFlags);
+
+/// Content of the `Module` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    /// Module constant.
+    pub module_name_index: ConstantIndex,
+    pub module_flags: ModuleFlags,
+    /// Utf8 constant.
+    pub module_version_index: Option<ConstantIndex>,
+    pub requires: Vec<Requires>,
+    pub exports: Vec<Exports>,
+    pub opens: Vec<Opens>,
+    /// Class constants of the used services.
+    pub uses: Vec<ConstantIndex>,
+    pub provides: Vec<Provides>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requires {
+    /// Module constant.
+    pub requires_index: ConstantIndex,
+    pub requires_flags: RequiresFlags,
+    /// Utf8 constant with the version at compile time.
+    pub requires_version_index: Option<ConstantIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exports {
+    /// Package constant.
+    pub exports_index: ConstantIndex,
+    pub exports_flags: PackageFlags,
+    /// Module constants, empty for an unqualified export.
+    pub exports_to: Vec<ConstantIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opens {
+    /// Package constant.
+    pub opens_index: ConstantIndex,
+    pub opens_flags: PackageFlags,
+    /// Module constants, empty for an unqualified open.
+    pub opens_to: Vec<ConstantIndex>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provides {
+    /// Class constant of the service interface.
+    pub provides_index: ConstantIndex,
+    /// Class constants of the service implementations.
+    pub provides_with: Vec<ConstantIndex>,
+}
+
+/// Module declaration with resolved names.
+///
+/// Module names are dotted like `java.base`, package and class names are
+/// internal names like `java/lang`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleDescriptor<'a> {
+    pub name: &'a str,
+    pub flags: ModuleFlags,
+    pub version: Option<&'a str>,
+    pub requires: Vec<ModuleRequires<'a>>,
+    pub exports: Vec<ModulePackage<'a>>,
+    pub opens: Vec<ModulePackage<'a>>,
+    pub uses: Vec<&'a str>,
+    pub provides: Vec<ModuleProvides<'a>>,
+    /// All packages of the module from the `ModulePackages` attribute.
+    pub packages: Vec<&'a str>,
+    /// Main class from the `ModuleMainClass` attribute.
+    pub main_class: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRequires<'a> {
+    pub module: &'a str,
+    pub flags: RequiresFlags,
+    pub version: Option<&'a str>,
+}
+
+/// Exported or opened package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModulePackage<'a> {
+    pub package: &'a str,
+    pub flags: PackageFlags,
+    /// Modules the package is restricted to, empty when unqualified.
+    pub to: Vec<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleProvides<'a> {
+    pub service: &'a str,
+    pub with: Vec<&'a str>,
+}
+
+impl Module {
+    pub fn resolve<'a>(&self, cpool: &'a ConstantPool) -> JvmParseResult<ModuleDescriptor<'a>> {
+        let resolve_modules = |indices: &[ConstantIndex]| {
+            indices
+                .iter()
+                .map(|index| cpool.resolve_module_name(*index))
+                .collect::<JvmParseResult<Vec<_>>>()
+        };
+        let resolve_classes = |indices: &[ConstantIndex]| {
+            indices
+                .iter()
+                .map(|index| cpool.resolve_class_name(*index))
+                .collect::<JvmParseResult<Vec<_>>>()
+        };
+
+        Ok(ModuleDescriptor {
+            name: cpool.resolve_module_name(self.module_name_index)?,
+            flags: self.module_flags,
+            version: resolve_optional_utf8(cpool, self.module_version_index)?,
+            requires: self
+                .requires
+                .iter()
+                .map(|requires| {
+                    Ok(ModuleRequires {
+                        module: cpool.resolve_module_name(requires.requires_index)?,
+                        flags: requires.requires_flags,
+                        version: resolve_optional_utf8(cpool, requires.requires_version_index)?,
+                    })
+                })
+                .collect::<JvmParseResult<Vec<_>>>()?,
+            exports: self
+                .exports
+                .iter()
+                .map(|exports| {
+                    Ok(ModulePackage {
+                        package: cpool.resolve_package_name(exports.exports_index)?,
+                        flags: exports.exports_flags,
+                        to: resolve_modules(&exports.exports_to)?,
+                    })
+                })
+                .collect::<JvmParseResult<Vec<_>>>()?,
+            opens: self
+                .opens
+                .iter()
+                .map(|opens| {
+                    Ok(ModulePackage {
+                        package: cpool.resolve_package_name(opens.opens_index)?,
+                        flags: opens.opens_flags,
+                        to: resolve_modules(&opens.opens_to)?,
+                    })
+                })
+                .collect::<JvmParseResult<Vec<_>>>()?,
+            uses: resolve_classes(&self.uses)?,
+            provides: self
+                .provides
+                .iter()
+                .map(|provides| {
+                    Ok(ModuleProvides {
+                        service: cpool.resolve_class_name(provides.provides_index)?,
+                        with: resolve_classes(&provides.provides_with)?,
+                    })
+                })
+                .collect::<JvmParseResult<Vec<_>>>()?,
+            packages: vec![],
+            main_class: None,
+        })
+    }
+}
+
+fn resolve_optional_utf8(
+    cpool: &ConstantPool,
+    index: Option<ConstantIndex>,
+) -> JvmParseResult<Option<&str>> {
+    index.map(|index| cpool.resolve_utf8(index)).transpose()
+}
+
+/// Module declaration of a `module-info` class, `None` for other classes.
+pub(crate) fn module_descriptor<'a>(
+    attributes: &[Attribute],
+    cpool: &'a ConstantPool,
+) -> JvmParseResult<Option<ModuleDescriptor<'a>>> {
+    let module = match attributes.iter().find_map(|attribute| match attribute {
+        Attribute::Module(module) => Some(module),
+        _ => None,
+    }) {
+        Some(module) => module,
+        None => return Ok(None),
+    };
+
+    let mut descriptor = module.resolve(cpool)?;
+    for attribute in attributes {
+        match attribute {
+            Attribute::ModulePackages(packages) => {
+                descriptor.packages = packages
+                    .iter()
+                    .map(|index| cpool.resolve_package_name(*index))
+                    .collect::<JvmParseResult<Vec<_>>>()?;
+            }
+            Attribute::ModuleMainClass(main_class) => {
+                descriptor.main_class = Some(cpool.resolve_class_name(*main_class)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(Some(descriptor))
+}
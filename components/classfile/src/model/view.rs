@@ -0,0 +1,163 @@
+use crate::descriptor::{
+    parse_field_descriptor, parse_method_descriptor, FieldType, MethodDescriptor,
+};
+use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::attributes::Code;
+use crate::model::constants::ConstantPool;
+use crate::model::{
+    Attribute, ClassAccessFlags, ClassFile, Field, FieldAccessFlags, Method, MethodAccessFlags,
+};
+
+/// Class file with names and descriptors resolved from the constant pool.
+#[derive(Clone, Copy)]
+pub struct ClassView<'a> {
+    class_file: &'a ClassFile,
+}
+
+/// Field with resolved name and parsed descriptor.
+#[derive(Debug, Clone)]
+pub struct FieldView<'a> {
+    pub name: &'a str,
+    pub descriptor: FieldType,
+    pub access_flags: FieldAccessFlags,
+    pub field: &'a Field,
+}
+
+/// Method with resolved name and parsed descriptor.
+#[derive(Debug, Clone)]
+pub struct MethodView<'a> {
+    pub name: &'a str,
+    pub descriptor: MethodDescriptor,
+    pub access_flags: MethodAccessFlags,
+    /// Body of the method, `None` for abstract and native methods.
+    pub code: Option<&'a Code>,
+    pub method: &'a Method,
+}
+
+impl<'a> ClassView<'a> {
+    pub fn new(class_file: &'a ClassFile) -> Self {
+        Self { class_file }
+    }
+
+    pub fn class_file(&self) -> &'a ClassFile {
+        self.class_file
+    }
+
+    pub fn constant_pool(&self) -> &'a ConstantPool {
+        &self.class_file.constants
+    }
+
+    pub fn access_flags(&self) -> ClassAccessFlags {
+        self.class_file.access_flags
+    }
+
+    /// Internal name of the class like `java/lang/String`.
+    pub fn class_name(&self) -> JvmParseResult<&'a str> {
+        self.constant_pool()
+            .resolve_class_name(self.class_file.this_class)
+    }
+
+    /// Internal name of the super class, `None` for `java/lang/Object` and
+    /// modules.
+    pub fn super_name(&self) -> JvmParseResult<Option<&'a str>> {
+        if self.class_file.super_class.0 == 0 {
+            return Ok(None);
+        }
+        self.constant_pool()
+            .resolve_class_name(self.class_file.super_class)
+            .map(Some)
+    }
+
+    /// Internal names of the directly implemented interfaces.
+    pub fn interface_names(&self) -> JvmParseResult<Vec<&'a str>> {
+        let cpool = self.constant_pool();
+        self.class_file
+            .interfaces
+            .iter()
+            .map(|interface| cpool.resolve_class_name(*interface))
+            .collect()
+    }
+
+    pub fn fields(&self) -> JvmParseResult<Vec<FieldView<'a>>> {
+        self.class_file
+            .fields
+            .iter()
+            .map(|field| self.field_view(field))
+            .collect()
+    }
+
+    pub fn methods(&self) -> JvmParseResult<Vec<MethodView<'a>>> {
+        self.class_file
+            .methods
+            .iter()
+            .map(|method| self.method_view(method))
+            .collect()
+    }
+
+    /// Field with the given name and descriptor like `Ljava/lang/String;`.
+    pub fn find_field(
+        &self,
+        name: &str,
+        descriptor: &str,
+    ) -> JvmParseResult<Option<FieldView<'a>>> {
+        let cpool = self.constant_pool();
+        for field in &self.class_file.fields {
+            if cpool.resolve_utf8(field.name_index)? == name
+                && cpool.resolve_utf8(field.descriptor_index)? == descriptor
+            {
+                return self.field_view(field).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Method with the given name and descriptor like `(I)V`.
+    pub fn find_method(
+        &self,
+        name: &str,
+        descriptor: &str,
+    ) -> JvmParseResult<Option<MethodView<'a>>> {
+        let cpool = self.constant_pool();
+        for method in &self.class_file.methods {
+            if cpool.resolve_utf8(method.name_index)? == name
+                && cpool.resolve_utf8(method.descriptor_index)? == descriptor
+            {
+                return self.method_view(method).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn field_view(&self, field: &'a Field) -> JvmParseResult<FieldView<'a>> {
+        let cpool = self.constant_pool();
+        let descriptor = cpool.resolve_utf8(field.descriptor_index)?;
+        Ok(FieldView {
+            name: cpool.resolve_utf8(field.name_index)?,
+            descriptor: parse_field_descriptor(descriptor).ok_or_else(|| {
+                JvmParseError::InvalidFormat(format!("invalid field descriptor: {}", descriptor))
+            })?,
+            access_flags: field.access_flags,
+            field,
+        })
+    }
+
+    fn method_view(&self, method: &'a Method) -> JvmParseResult<MethodView<'a>> {
+        let cpool = self.constant_pool();
+        let descriptor = cpool.resolve_utf8(method.descriptor_index)?;
+        Ok(MethodView {
+            name: cpool.resolve_utf8(method.name_index)?,
+            descriptor: parse_method_descriptor(descriptor).ok_or_else(|| {
+                JvmParseError::InvalidFormat(format!("invalid method descriptor: {}", descriptor))
+            })?,
+            access_flags: method.access_flags,
+            code: method
+                .attributes
+                .iter()
+                .find_map(|attribute| match attribute {
+                    Attribute::Code(code) => Some(code),
+                    _ => None,
+                }),
+            method,
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::annotations::{
+    Annotation, ConstKind, ElementValue, ElementValuePair, LocalVarTargetEntry, TypeAnnotation,
+    TypeAnnotationTarget, TypePath, TypePathEntry, TypePathKind,
+};
+use crate::model::constants::ConstantPool;
+use crate::parse::{ClassFileEntry, ClassFileRead, ReadClassFileExt};
+use byteorder::ReadBytesExt;
+
+impl ClassFileEntry for Annotation {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Annotation {
+            type_index: reader.parse(cpool)?,
+            elements: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for ElementValuePair {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(ElementValuePair {
+            name_index: reader.parse(cpool)?,
+            value: reader.parse(cpool)?,
+        })
+    }
+}
+
+/// Parses the annotation or array of an element value within the depth limit.
+fn parse_nested<T: ClassFileRead, U: ClassFileEntry>(
+    reader: &mut T,
+    cpool: &ConstantPool,
+) -> JvmParseResult<U> {
+    reader.context().enter()?;
+    let result = reader.parse(cpool);
+    reader.context().leave();
+    result
+}
+
+impl ClassFileEntry for ElementValue {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let tag = reader.read_u8()?;
+        Ok(match tag {
+            b'e' => ElementValue::Enum {
+                type_name_index: reader.parse(cpool)?,
+                const_name_index: reader.parse(cpool)?,
+            },
+            b'c' => ElementValue::Class(reader.parse(cpool)?),
+            b'@' => ElementValue::Annotation(parse_nested(reader, cpool)?),
+            b'[' => ElementValue::Array(parse_nested(reader, cpool)?),
+            _ => match ConstKind::from_tag(tag) {
+                Some(kind) => ElementValue::Const(kind, reader.parse(cpool)?),
+                None => {
+                    return Err(JvmParseError::InvalidFormat(format!(
+                        "unknown element value tag: {}",
+                        tag
+                    )))
+                }
+            },
+        })
+    }
+}
+
+impl ClassFileEntry for LocalVarTargetEntry {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(LocalVarTargetEntry {
+            start_pc: reader.parse(cpool)?,
+            length: reader.parse(cpool)?,
+            index: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for TypeAnnotationTarget {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let target_type = reader.read_u8()?;
+        Ok(match target_type {
+            0x00 => TypeAnnotationTarget::ClassTypeParameter(reader.read_u8()?),
+            0x01 => TypeAnnotationTarget::MethodTypeParameter(reader.read_u8()?),
+            0x10 => TypeAnnotationTarget::Supertype(reader.parse(cpool)?),
+            0x11 => TypeAnnotationTarget::ClassTypeParameterBound {
+                type_parameter_index: reader.read_u8()?,
+                bound_index: reader.read_u8()?,
+            },
+            0x12 => TypeAnnotationTarget::MethodTypeParameterBound {
+                type_parameter_index: reader.read_u8()?,
+                bound_index: reader.read_u8()?,
+            },
+            0x13 => TypeAnnotationTarget::Field,
+            0x14 => TypeAnnotationTarget::MethodReturn,
+            0x15 => TypeAnnotationTarget::MethodReceiver,
+            0x16 => TypeAnnotationTarget::FormalParameter(reader.read_u8()?),
+            0x17 => TypeAnnotationTarget::Throws(reader.parse(cpool)?),
+            0x40 => TypeAnnotationTarget::LocalVariable(reader.parse(cpool)?),
+            0x41 => TypeAnnotationTarget::ResourceVariable(reader.parse(cpool)?),
+            0x42 => TypeAnnotationTarget::ExceptionParameter(reader.parse(cpool)?),
+            0x43 => TypeAnnotationTarget::Instanceof(reader.parse(cpool)?),
+            0x44 => TypeAnnotationTarget::New(reader.parse(cpool)?),
+            0x45 => TypeAnnotationTarget::ConstructorReference(reader.parse(cpool)?),
+            0x46 => TypeAnnotationTarget::MethodReference(reader.parse(cpool)?),
+            0x47 => TypeAnnotationTarget::Cast {
+                offset: reader.parse(cpool)?,
+                type_argument_index: reader.read_u8()?,
+            },
+            0x48 => TypeAnnotationTarget::ConstructorInvocationTypeArgument {
+                offset: reader.parse(cpool)?,
+                type_argument_index: reader.read_u8()?,
+            },
+            0x49 => TypeAnnotationTarget::MethodInvocationTypeArgument {
+                offset: reader.parse(cpool)?,
+                type_argument_index: reader.read_u8()?,
+            },
+            0x4A => TypeAnnotationTarget::ConstructorReferenceTypeArgument {
+                offset: reader.parse(cpool)?,
+                type_argument_index: reader.read_u8()?,
+            },
+            0x4B => TypeAnnotationTarget::MethodReferenceTypeArgument {
+                offset: reader.parse(cpool)?,
+                type_argument_index: reader.read_u8()?,
+            },
+            _ => {
+                return Err(JvmParseError::InvalidFormat(format!(
+                    "unknown type annotation target type: {:#x}",
+                    target_type
+                )))
+            }
+        })
+    }
+}
+
+impl ClassFileEntry for TypePathEntry {
+    fn parse<T: ClassFileRead>(reader: &mut T, _cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let kind = match reader.read_u8()? {
+            0 => TypePathKind::ArrayElement,
+            1 => TypePathKind::Nested,
+            2 => TypePathKind::WildcardBound,
+            3 => TypePathKind::TypeArgument,
+            kind => {
+                return Err(JvmParseError::InvalidFormat(format!(
+                    "unknown type path kind: {}",
+                    kind
+                )))
+            }
+        };
+        Ok(TypePathEntry {
+            kind,
+            type_argument_index: reader.read_u8()?,
+        })
+    }
+}
+
+impl ClassFileEntry for TypePath {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let path_length = reader.read_u8()?;
+        Ok(TypePath {
+            path: (0..path_length)
+                .map(|_| reader.parse(cpool))
+                .collect::<JvmParseResult<Vec<TypePathEntry>>>()?,
+        })
+    }
+}
+
+impl ClassFileEntry for TypeAnnotation {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(TypeAnnotation {
+            target: reader.parse(cpool)?,
+            type_path: reader.parse(cpool)?,
+            annotation: reader.parse(cpool)?,
+        })
+    }
+}
+
+/// Annotations of each parameter, whose count is a single byte.
+pub fn parse_parameter_annotations<T: ClassFileRead>(
+    reader: &mut T,
+    cpool: &ConstantPool,
+) -> JvmParseResult<Vec<Vec<Annotation>>> {
+    let num_parameters = reader.read_u8()?;
+    (0..num_parameters)
+        .map(|_| reader.parse(cpool))
+        .collect::<JvmParseResult<Vec<Vec<Annotation>>>>()
+}
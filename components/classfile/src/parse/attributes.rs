@@ -1,20 +1,63 @@
-use crate::error::JvmParseResult;
-use crate::model::attributes::{Code, ConstantValue, ExceptionTableEntry};
+use crate::error::{JvmParseError, JvmParseResult};
+use crate::model::attributes::{
+    BootstrapMethod, Code, ConstantValue, EnclosingMethod, ExceptionTableEntry, InnerClass,
+    LineNumber, LocalVariable, LocalVariableType, RecordComponent, StackMapFrame,
+    StackMapFrameKind, StackMapTable, VerificationTypeInfo,
+};
 use crate::model::constants::ConstantPool;
-use crate::parse::{parse_bytes_u32, ClassFileEntry, ReadClassFileExt};
+use crate::parse::{parse_bytes_limited, ClassFileEntry, ClassFileRead, ReadClassFileExt};
+use byteorder::{BigEndian, ReadBytesExt};
 use rustjvm_opcode::disasm;
-use std::io::Read;
 
 impl ClassFileEntry for ConstantValue {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
         Ok(ConstantValue {
             constantvalue_index: reader.parse(cpool)?,
         })
     }
 }
 
+impl ClassFileEntry for BootstrapMethod {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(BootstrapMethod {
+            bootstrap_method_ref: reader.parse(cpool)?,
+            bootstrap_arguments: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for InnerClass {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(InnerClass {
+            inner_class_info_index: reader.parse(cpool)?,
+            outer_class_info_index: reader.parse(cpool)?,
+            inner_name_index: reader.parse(cpool)?,
+            inner_class_access_flags: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for EnclosingMethod {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(EnclosingMethod {
+            class_index: reader.parse(cpool)?,
+            method_index: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for RecordComponent {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(RecordComponent {
+            name_index: reader.parse(cpool)?,
+            descriptor_index: reader.parse(cpool)?,
+            attributes: reader.parse(cpool)?,
+        })
+    }
+}
+
 impl ClassFileEntry for ExceptionTableEntry {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
         Ok(ExceptionTableEntry {
             start_pc: reader.parse(cpool)?,
             end_pc: reader.parse(cpool)?,
@@ -25,13 +68,143 @@ impl ClassFileEntry for ExceptionTableEntry {
 }
 
 impl ClassFileEntry for Code {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let max_stack = reader.parse(cpool)?;
+        let max_locals = reader.parse(cpool)?;
+        let max_code_length = reader.context().limits().max_code_length;
+        let code = parse_bytes_limited(reader, "code length", max_code_length)?;
+        let code = disasm(&code).map_err(|error| {
+            let segment = match error.opcode {
+                Some(_) => format!("code@pc {}", error.pc),
+                None => "code".into(),
+            };
+            let pc = error.pc;
+            JvmParseError::from(error)
+                .before(code.len() - pc)
+                .within(segment)
+        })?;
         Ok(Code {
-            max_stack: reader.parse(cpool)?,
-            max_locals: reader.parse(cpool)?,
-            code: disasm(&parse_bytes_u32(reader)?)?,
+            max_stack,
+            max_locals,
+            code,
             exception_table: reader.parse(cpool)?,
             attributes: reader.parse(cpool)?,
         })
     }
 }
+
+impl ClassFileEntry for LineNumber {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(LineNumber {
+            start_pc: reader.parse(cpool)?,
+            line_number: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for LocalVariable {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(LocalVariable {
+            start_pc: reader.parse(cpool)?,
+            length: reader.parse(cpool)?,
+            name_index: reader.parse(cpool)?,
+            descriptor_index: reader.parse(cpool)?,
+            index: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for LocalVariableType {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(LocalVariableType {
+            start_pc: reader.parse(cpool)?,
+            length: reader.parse(cpool)?,
+            name_index: reader.parse(cpool)?,
+            signature_index: reader.parse(cpool)?,
+            index: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for VerificationTypeInfo {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let tag = reader.read_u8()?;
+        Ok(match tag {
+            0 => VerificationTypeInfo::Top,
+            1 => VerificationTypeInfo::Integer,
+            2 => VerificationTypeInfo::Float,
+            3 => VerificationTypeInfo::Double,
+            4 => VerificationTypeInfo::Long,
+            5 => VerificationTypeInfo::Null,
+            6 => VerificationTypeInfo::UninitializedThis,
+            7 => VerificationTypeInfo::Object(reader.parse(cpool)?),
+            8 => VerificationTypeInfo::Uninitialized(reader.parse(cpool)?),
+            _ => {
+                return Err(JvmParseError::InvalidFormat(format!(
+                    "unknown verification type tag: {}",
+                    tag
+                )))
+            }
+        })
+    }
+}
+
+impl ClassFileEntry for StackMapTable {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        let number_of_entries = reader.read_u16::<BigEndian>()?;
+        let mut entries: Vec<StackMapFrame> = vec![];
+        for _ in 0..number_of_entries {
+            let frame_type = reader.read_u8()?;
+            let (offset_delta, kind): (u16, _) = match frame_type {
+                0..=63 => (frame_type.into(), StackMapFrameKind::Same),
+                64..=127 => (
+                    (frame_type - 64).into(),
+                    StackMapFrameKind::SameLocals1StackItem(reader.parse(cpool)?),
+                ),
+                247 => (
+                    reader.parse(cpool)?,
+                    StackMapFrameKind::SameLocals1StackItemExtended(reader.parse(cpool)?),
+                ),
+                248..=250 => (
+                    reader.parse(cpool)?,
+                    StackMapFrameKind::Chop(251 - frame_type),
+                ),
+                251 => (reader.parse(cpool)?, StackMapFrameKind::SameExtended),
+                252..=254 => {
+                    let offset_delta = reader.parse(cpool)?;
+                    let locals = (251..frame_type)
+                        .map(|_| reader.parse(cpool))
+                        .collect::<JvmParseResult<Vec<_>>>()?;
+                    (offset_delta, StackMapFrameKind::Append(locals))
+                }
+                255 => (
+                    reader.parse(cpool)?,
+                    StackMapFrameKind::Full {
+                        locals: reader.parse(cpool)?,
+                        stack: reader.parse(cpool)?,
+                    },
+                ),
+                _ => {
+                    return Err(JvmParseError::InvalidFormat(format!(
+                        "reserved stack map frame type: {}",
+                        frame_type
+                    )))
+                }
+            };
+
+            // every frame but the first is at least one byte after the previous one
+            let offset = match entries.last() {
+                Some(previous) => previous
+                    .offset
+                    .checked_add(offset_delta)
+                    .and_then(|offset| offset.checked_add(1))
+                    .ok_or_else(|| {
+                        JvmParseError::InvalidFormat("stack map frame offset overflow".into())
+                    })?,
+                None => offset_delta,
+            };
+            entries.push(StackMapFrame { offset, kind });
+        }
+        Ok(StackMapTable { entries })
+    }
+}
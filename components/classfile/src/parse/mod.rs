@@ -1,28 +1,252 @@
+use std::cell::{Cell, RefCell};
 use std::convert::TryInto;
-use std::io::Read;
+use std::io::{self, Read};
 
 use byteorder::{BigEndian, ReadBytesExt};
-use cesu8::from_java_cesu8;
 
 use crate::error::{JvmParseError, JvmParseResult};
 use crate::model::constants::{Constant, ConstantIndex, ConstantPool};
-use crate::model::{AccessFlags, Attribute, ClassFile, Field, Method};
+use crate::model::java_string::JavaString;
+use crate::model::{
+    Attribute, Attributes, ClassAccessFlags, ClassFile, Field, FieldAccessFlags, FlagBits,
+    InnerClassAccessFlags, Method, MethodAccessFlags, MethodParameter, ParameterAccessFlags,
+};
+use crate::parse::annotations::parse_parameter_annotations;
 
+/// Implements `ClassFilePrimitive` for u16 bitflags types.
+macro_rules! parse_flags {
+    ($($flags:ty),*) => {
+        $(
+            impl ClassFilePrimitive for $flags {
+                fn parse_primitive<T: Read>(reader: &mut T) -> JvmParseResult<$flags> {
+                    let bits = reader.read_u16::<BigEndian>()?;
+                    Ok(<$flags>::from_bits_retain(bits))
+                }
+            }
+        )*
+    };
+}
+
+mod annotations;
 mod attributes;
+mod module;
 
-pub(crate) trait ReadClassFileExt: Read + Sized {
+pub(crate) trait ReadClassFileExt: ClassFileRead + Sized {
     fn parse<T: ClassFileEntry>(&mut self, cpool: &ConstantPool) -> JvmParseResult<T> {
         T::parse(self, cpool)
     }
 }
 
-impl<R: Read + Sized> ReadClassFileExt for R {}
+impl<R: ClassFileRead + Sized> ReadClassFileExt for R {}
+
+/// Reader of class file contents, which knows its position in the class file
+/// and the state of the parse.
+pub trait ClassFileRead: Read {
+    /// Offset of the next byte in the class file.
+    fn position(&self) -> u64;
+
+    fn context(&self) -> &ParseContext;
+}
+
+/// Limits on the sizes claimed by a class file, which are checked before
+/// memory is allocated for them. Exceeding a limit is an error even in a
+/// lenient parse.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseLimits {
+    /// Maximal length of the info of an attribute, 16 MiB by default.
+    pub max_attribute_length: u32,
+    /// Maximal number of constant pool slots, including the second slots of
+    /// long and double constants.
+    pub max_constants: u16,
+    /// Maximal length of the code of a method, 65535 bytes by default as
+    /// required by the JVM.
+    pub max_code_length: u32,
+    /// Maximal nesting depth of attributes and annotation element values.
+    pub max_depth: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_attribute_length: 16 << 20,
+            max_constants: u16::MAX,
+            max_code_length: 65535,
+            max_depth: 64,
+        }
+    }
+}
+
+/// Options of `parse_class_file_with`.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Continues after malformed attributes, see `parse_class_file_lenient`.
+    pub lenient: bool,
+    pub limits: ParseLimits,
+}
+
+/// Mode and collected diagnostics of a parse.
+pub struct ParseContext {
+    lenient: bool,
+    limits: ParseLimits,
+    depth: Cell<usize>,
+    diagnostics: RefCell<Vec<JvmParseError>>,
+}
+
+impl ParseContext {
+    pub(crate) fn new(options: &ParseOptions) -> Self {
+        Self {
+            lenient: options.lenient,
+            limits: options.limits,
+            depth: Cell::new(0),
+            diagnostics: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn limits(&self) -> &ParseLimits {
+        &self.limits
+    }
+
+    /// Enters a nested structure, which must not exceed the maximal depth.
+    /// Each successful call is paired with a call to `leave`.
+    pub(crate) fn enter(&self) -> JvmParseResult<()> {
+        let depth = self.depth.get() + 1;
+        check_limit("nesting depth", depth as u64, self.limits.max_depth as u64)?;
+        self.depth.set(depth);
+        Ok(())
+    }
+
+    pub(crate) fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    /// Number of diagnostics reported so far.
+    fn mark(&self) -> usize {
+        self.diagnostics.borrow().len()
+    }
+
+    /// Adds the path segment of the enclosing structure to the diagnostics
+    /// reported since `mark`.
+    fn within_since(&self, mark: usize, segment: &str) {
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        let reported = diagnostics.split_off(mark);
+        diagnostics.extend(reported.into_iter().map(|error| error.within(segment)));
+    }
+}
+
+pub(crate) fn check_limit(what: &'static str, value: u64, limit: u64) -> JvmParseResult<()> {
+    if value > limit {
+        return Err(JvmParseError::LimitExceeded { what, value, limit });
+    }
+    Ok(())
+}
+
+/// Reader of the class file passed to `parse_class_file`.
+struct ClassFileReader<'a, T> {
+    inner: T,
+    position: u64,
+    context: &'a ParseContext,
+}
+
+impl<T: Read> Read for ClassFileReader<'_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<T: Read> ClassFileRead for ClassFileReader<'_, T> {
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn context(&self) -> &ParseContext {
+        self.context
+    }
+}
+
+/// Reader of the info bytes of an attribute.
+struct AttributeReader<'a> {
+    bytes: &'a [u8],
+    /// Offset of the end of the attribute in the class file.
+    end: u64,
+    context: &'a ParseContext,
+}
+
+impl Read for AttributeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.bytes.read(buf)
+    }
+}
+
+impl ClassFileRead for AttributeReader<'_> {
+    fn position(&self) -> u64 {
+        self.end - self.bytes.len() as u64
+    }
+
+    fn context(&self) -> &ParseContext {
+        self.context
+    }
+}
 
 /// https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html
-pub fn parse_class_file<T: Read>(mut reader: T) -> JvmParseResult<ClassFile> {
+///
+/// Errors are returned as `JvmParseError::Located` with the byte offset and
+/// the path of the structure they occurred in.
+pub fn parse_class_file<T: Read>(reader: T) -> JvmParseResult<ClassFile> {
+    let (class_file, _) = parse_class_file_with(reader, &ParseOptions::default())?;
+    Ok(class_file)
+}
+
+/// Parses a class file like `parse_class_file`, but continues after
+/// malformed attributes. These are kept as `Attribute::Unknown`, so a method
+/// with a malformed `Code` attribute is left without code. The errors are
+/// returned as diagnostics along with the class file.
+///
+/// Errors in the constant pool or in the structure around the attributes are
+/// still fatal.
+pub fn parse_class_file_lenient<T: Read>(
+    reader: T,
+) -> JvmParseResult<(ClassFile, Vec<JvmParseError>)> {
+    let options = ParseOptions {
+        lenient: true,
+        ..ParseOptions::default()
+    };
+    parse_class_file_with(reader, &options)
+}
+
+/// Parses a class file with the given mode and limits. Diagnostics are only
+/// returned by a lenient parse.
+pub fn parse_class_file_with<T: Read>(
+    reader: T,
+    options: &ParseOptions,
+) -> JvmParseResult<(ClassFile, Vec<JvmParseError>)> {
+    let context = ParseContext::new(options);
+    let mut reader = ClassFileReader {
+        inner: reader,
+        position: 0,
+        context: &context,
+    };
+    let mut class_name = None;
+    let result = parse_class_file_contents(&mut reader, &mut class_name);
+    let position = reader.position;
+    let class_file = result.map_err(|error| error.at(position).in_class(class_name.clone()))?;
+    let diagnostics = context
+        .diagnostics
+        .into_inner()
+        .into_iter()
+        .map(|diagnostic| diagnostic.in_class(class_name.clone()))
+        .collect();
+    Ok((class_file, diagnostics))
+}
+
+fn parse_class_file_contents<T: ClassFileRead>(
+    reader: &mut T,
+    class_name: &mut Option<String>,
+) -> JvmParseResult<ClassFile> {
     let magic: u32 = reader.read_u32::<BigEndian>()?;
     if magic != 0xCAFEBABE {
-        return Err(JvmParseError::InvalidFormat("invalid magic".into()));
+        return Err(JvmParseError::InvalidFormat("invalid magic".into()).before(4));
     }
 
     let minor_version: u16 = reader.read_u16::<BigEndian>()?;
@@ -31,93 +255,74 @@ pub fn parse_class_file<T: Read>(mut reader: T) -> JvmParseResult<ClassFile> {
         return Err(JvmParseError::InvalidFormat(format!(
             "version must be at least 45.0, but got {}.{}",
             major_version, minor_version
-        )));
+        ))
+        .before(4));
     }
 
-    let cpool = ConstantPool::new(parse_constants(&mut reader)?);
+    let cpool = ConstantPool::new(parse_constants(reader)?);
+    let access_flags = reader.parse(&cpool)?;
+    let this_class = reader.parse(&cpool)?;
+    *class_name = cpool
+        .resolve_class_name(this_class)
+        .ok()
+        .map(|name| name.to_string());
     Ok(ClassFile {
         magic,
         minor_version,
         major_version,
-        access_flags: reader.parse(&cpool)?,
-        this_class: reader.parse(&cpool)?,
+        access_flags,
+        this_class,
         super_class: reader.parse(&cpool)?,
         interfaces: reader.parse(&cpool)?,
-        fields: reader.parse(&cpool)?,
-        methods: reader.parse(&cpool)?,
+        fields: parse_members(reader, &cpool, "fields")?,
+        methods: parse_members(reader, &cpool, "methods")?,
         attributes: reader.parse(&cpool)?,
         constants: cpool,
     })
 }
 
-fn parse_constants<T: Read>(reader: &mut T) -> JvmParseResult<Vec<Constant>> {
+/// Fields or methods, errors are located by the index of the member.
+fn parse_members<T: ClassFileRead, U: ClassFileEntry>(
+    reader: &mut T,
+    cpool: &ConstantPool,
+    name: &str,
+) -> JvmParseResult<Vec<U>> {
+    let count = reader.read_u16::<BigEndian>()?;
+    (0..count)
+        .map(|i| {
+            let segment = format!("{}[{}]", name, i);
+            let mark = reader.context().mark();
+            let member = U::parse(reader, cpool).map_err(|error| error.within(&segment))?;
+            reader.context().within_since(mark, &segment);
+            Ok(member)
+        })
+        .collect()
+}
+
+fn parse_constants<T: ClassFileRead>(reader: &mut T) -> JvmParseResult<Vec<Constant>> {
     let constant_pool_count: u16 = reader.read_u16::<BigEndian>()?;
-    let mut constants = vec![];
-    constants.reserve(constant_pool_count as usize - 1);
+    if constant_pool_count == 0 {
+        return Err(
+            JvmParseError::InvalidFormat("constant pool count must be at least 1".into()).before(2),
+        );
+    }
+    let max_constants = reader.context().limits.max_constants;
+    check_limit(
+        "constant pool count",
+        constant_pool_count as u64 - 1,
+        max_constants as u64,
+    )
+    .map_err(|error| error.before(2))?;
+    let mut constants = Vec::with_capacity(constant_pool_count as usize - 1);
 
     let mut i = 1;
     while i < constant_pool_count {
-        let mut long_constant = false;
-        let tag = reader.read_u8()?;
-        let constant: Constant = match tag {
-            7 => Constant::Class {
-                name_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            9 => Constant::Fieldref {
-                class_index: ConstantIndex::parse_primitive(reader)?,
-                name_and_type_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            10 => Constant::Methodref {
-                class_index: ConstantIndex::parse_primitive(reader)?,
-                name_and_type_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            11 => Constant::InterfaceMethodref {
-                class_index: ConstantIndex::parse_primitive(reader)?,
-                name_and_type_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            8 => Constant::String(ConstantIndex::parse_primitive(reader)?),
-            3 => Constant::Integer(reader.read_i32::<BigEndian>()?),
-            4 => Constant::Float(reader.read_f32::<BigEndian>()?),
-            5 => {
-                long_constant = true;
-                Constant::Long(reader.read_i64::<BigEndian>()?)
-            }
-            6 => {
-                long_constant = true;
-                Constant::Double(reader.read_f64::<BigEndian>()?)
-            }
-            12 => Constant::NameAndType {
-                name_index: ConstantIndex::parse_primitive(reader)?,
-                descriptor_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            15 => Constant::MethodHandle {
-                reference_kind: reader.read_u8()?.try_into()?,
-                reference_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            16 => Constant::MethodType {
-                descriptor_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            18 => Constant::InvokeDynamic {
-                bootstrap_method_attr_index: ConstantIndex::parse_primitive(reader)?,
-                name_and_type_index: ConstantIndex::parse_primitive(reader)?,
-            },
-            1 => Constant::Utf8(
-                from_java_cesu8(&parse_bytes_u16(reader)?)
-                    .map_err(|_err| {
-                        JvmParseError::InvalidFormat(format!("invalid string for constant {}", i))
-                    })?
-                    .into(),
-            ),
-            _ => {
-                return Err(JvmParseError::InvalidFormat(format!(
-                    "unknown constant pool tag at {}: {}",
-                    i, tag
-                )))
-            }
-        };
+        let constant = parse_constant(reader, i)
+            .map_err(|error| error.within(format!("constant_pool[{}]", i)))?;
+        let long_constant = matches!(constant, Constant::Long(_) | Constant::Double(_));
         constants.push(constant);
         if long_constant {
-            if i + 1 > constant_pool_count {
+            if i + 1 >= constant_pool_count {
                 return Err(JvmParseError::InvalidFormat(format!(
                     "long/double constant is missing second constant pool index {}",
                     i + 1
@@ -133,8 +338,71 @@ fn parse_constants<T: Read>(reader: &mut T) -> JvmParseResult<Vec<Constant>> {
     Ok(constants)
 }
 
+pub(crate) fn parse_constant<T: Read>(reader: &mut T, i: u16) -> JvmParseResult<Constant> {
+    let tag = reader.read_u8()?;
+    Ok(match tag {
+        7 => Constant::Class {
+            name_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        9 => Constant::Fieldref {
+            class_index: ConstantIndex::parse_primitive(reader)?,
+            name_and_type_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        10 => Constant::Methodref {
+            class_index: ConstantIndex::parse_primitive(reader)?,
+            name_and_type_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        11 => Constant::InterfaceMethodref {
+            class_index: ConstantIndex::parse_primitive(reader)?,
+            name_and_type_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        8 => Constant::String(ConstantIndex::parse_primitive(reader)?),
+        3 => Constant::Integer(reader.read_i32::<BigEndian>()?),
+        4 => Constant::Float(reader.read_f32::<BigEndian>()?),
+        5 => Constant::Long(reader.read_i64::<BigEndian>()?),
+        6 => Constant::Double(reader.read_f64::<BigEndian>()?),
+        12 => Constant::NameAndType {
+            name_index: ConstantIndex::parse_primitive(reader)?,
+            descriptor_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        15 => Constant::MethodHandle {
+            reference_kind: reader.read_u8()?.try_into()?,
+            reference_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        16 => Constant::MethodType {
+            descriptor_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        17 => Constant::Dynamic {
+            bootstrap_method_attr_index: reader.read_u16::<BigEndian>()?,
+            name_and_type_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        18 => Constant::InvokeDynamic {
+            bootstrap_method_attr_index: reader.read_u16::<BigEndian>()?,
+            name_and_type_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        19 => Constant::Module {
+            name_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        20 => Constant::Package {
+            name_index: ConstantIndex::parse_primitive(reader)?,
+        },
+        1 => Constant::Utf8(
+            JavaString::from_modified_utf8(parse_bytes_u16(reader)?).ok_or_else(|| {
+                JvmParseError::InvalidFormat(format!("invalid string for constant {}", i))
+            })?,
+        ),
+        _ => {
+            return Err(JvmParseError::InvalidFormat(format!(
+                "unknown constant pool tag at {}: {}",
+                i, tag
+            ))
+            .before(1))
+        }
+    })
+}
+
 pub trait ClassFileEntry: Sized {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self>;
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self>;
 }
 
 pub trait ClassFilePrimitive: Sized {
@@ -143,7 +411,7 @@ pub trait ClassFilePrimitive: Sized {
 
 impl<T: ClassFilePrimitive> ClassFileEntry for T {
     #[inline]
-    fn parse<R: Read>(reader: &mut R, _: &ConstantPool) -> JvmParseResult<Self> {
+    fn parse<R: ClassFileRead>(reader: &mut R, _: &ConstantPool) -> JvmParseResult<Self> {
         T::parse_primitive(reader)
     }
 }
@@ -156,20 +424,38 @@ impl ClassFilePrimitive for u16 {
 
 pub fn parse_bytes_u16<R: Read>(reader: &mut R) -> JvmParseResult<Vec<u8>> {
     let length = reader.read_u16::<BigEndian>()?;
-    let mut buf: Vec<u8> = vec![0; length as usize];
-    reader.read_exact(&mut buf)?;
-    Ok(buf)
+    parse_bytes(reader, length as u64)
 }
 
+/// Reads bytes with a u32 length. The buffer only grows with the bytes
+/// actually read, use `parse_bytes_limited` to reject large lengths upfront.
 pub fn parse_bytes_u32<R: Read>(reader: &mut R) -> JvmParseResult<Vec<u8>> {
     let length = reader.read_u32::<BigEndian>()?;
-    let mut buf: Vec<u8> = vec![0; length as usize];
-    reader.read_exact(&mut buf)?;
+    parse_bytes(reader, length as u64)
+}
+
+/// Reads bytes with a u32 length, which must not exceed `limit`.
+pub(crate) fn parse_bytes_limited<R: Read>(
+    reader: &mut R,
+    what: &'static str,
+    limit: u32,
+) -> JvmParseResult<Vec<u8>> {
+    let length = reader.read_u32::<BigEndian>()?;
+    check_limit(what, length as u64, limit as u64).map_err(|error| error.before(4))?;
+    parse_bytes(reader, length as u64)
+}
+
+fn parse_bytes<R: Read>(reader: &mut R, length: u64) -> JvmParseResult<Vec<u8>> {
+    let mut buf = vec![];
+    reader.by_ref().take(length).read_to_end(&mut buf)?;
+    if (buf.len() as u64) < length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
     Ok(buf)
 }
 
 impl<U: ClassFileEntry> ClassFileEntry for Vec<U> {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Vec<U>> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Vec<U>> {
         let attributes_count = reader.read_u16::<BigEndian>()? as usize;
         (0..attributes_count)
             .map(|_| U::parse(reader, cpool))
@@ -183,16 +469,47 @@ impl ClassFilePrimitive for ConstantIndex {
     }
 }
 
-impl ClassFilePrimitive for AccessFlags {
-    fn parse_primitive<T: Read>(reader: &mut T) -> JvmParseResult<AccessFlags> {
-        Ok(AccessFlags::from_bits_truncate(
-            reader.read_u16::<BigEndian>()?,
-        ))
+/// Index 0 stands for an absent constant.
+impl ClassFilePrimitive for Option<ConstantIndex> {
+    fn parse_primitive<T: Read>(reader: &mut T) -> JvmParseResult<Option<ConstantIndex>> {
+        Ok(match reader.read_u16::<BigEndian>()? {
+            0 => None,
+            index => Some(ConstantIndex(index)),
+        })
     }
 }
 
+parse_flags!(
+    ClassAccessFlags,
+    InnerClassAccessFlags,
+    FieldAccessFlags,
+    MethodAccessFlags,
+    ParameterAccessFlags
+);
+
+impl ClassFileEntry for MethodParameter {
+    fn parse<T: ClassFileRead>(
+        reader: &mut T,
+        cpool: &ConstantPool,
+    ) -> JvmParseResult<MethodParameter> {
+        Ok(MethodParameter {
+            name_index: reader.parse(cpool)?,
+            access_flags: reader.parse(cpool)?,
+        })
+    }
+}
+
+/// Entries of the `MethodParameters` attribute, which are counted by a u8.
+fn parse_method_parameters<T: ClassFileRead>(
+    reader: &mut T,
+    cpool: &ConstantPool,
+) -> JvmParseResult<Vec<MethodParameter>> {
+    let parameters_count = reader.read_u8()?;
+    (0..parameters_count).map(|_| reader.parse(cpool)).collect()
+}
+
 impl ClassFileEntry for Field {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Field> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Field> {
         Ok(Field {
             access_flags: reader.parse(cpool)?,
             name_index: reader.parse(cpool)?,
@@ -203,7 +520,7 @@ impl ClassFileEntry for Field {
 }
 
 impl ClassFileEntry for Method {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Method> {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Method> {
         Ok(Method {
             access_flags: reader.parse(cpool)?,
             name_index: reader.parse(cpool)?,
@@ -213,21 +530,149 @@ impl ClassFileEntry for Method {
     }
 }
 
-impl ClassFileEntry for Attribute {
-    fn parse<T: Read>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Attribute> {
-        let attribute_name_index = ConstantIndex::parse_primitive(reader)?;
-        let name = cpool.resolve_utf8(attribute_name_index)?;
-        let info = parse_bytes_u32(reader)?;
-        let mut slice: &[u8] = &info;
+impl ClassFileEntry for Attributes {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Attributes> {
+        let attributes_count = reader.read_u16::<BigEndian>()? as usize;
+        let (name_indices, attributes) = (0..attributes_count)
+            .map(|_| parse_attribute(reader, cpool))
+            .collect::<JvmParseResult<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        Ok(Attributes::parsed(attributes, name_indices))
+    }
+}
+
+/// Attribute with the index of its name constant.
+fn parse_attribute<T: ClassFileRead>(
+    reader: &mut T,
+    cpool: &ConstantPool,
+) -> JvmParseResult<(ConstantIndex, Attribute)> {
+    let attribute_name_index = ConstantIndex::parse_primitive(reader)?;
+    let max_attribute_length = reader.context().limits.max_attribute_length;
+    let info = parse_bytes_limited(reader, "attribute length", max_attribute_length)
+        .map_err(|error| error.within(attribute_segment(attribute_name_index, cpool)))?;
+    let attribute = decode_attribute(
+        attribute_name_index,
+        info,
+        reader.position(),
+        reader.context(),
+        cpool,
+    )?;
+    Ok((attribute_name_index, attribute))
+}
 
-        Ok(match name {
-            "Code" => Attribute::Code(slice.parse(cpool)?),
-            "ConstantValue" => Attribute::ConstantValue(slice.parse(cpool)?),
-            "SourceFile" => Attribute::SourceFile(slice.parse(cpool)?),
-            _ => Attribute::Unknown {
+fn attribute_segment(name_index: ConstantIndex, cpool: &ConstantPool) -> String {
+    match cpool.resolve_utf8(name_index) {
+        Ok(name) => format!("attributes[{}]", name),
+        Err(_) => "attributes".into(),
+    }
+}
+
+/// Decodes the info bytes of an attribute, which end at offset `end` in the
+/// class file. Unknown attributes, and malformed ones in a lenient parse, are
+/// kept as `Attribute::Unknown`.
+pub(crate) fn decode_attribute(
+    attribute_name_index: ConstantIndex,
+    info: Vec<u8>,
+    end: u64,
+    context: &ParseContext,
+    cpool: &ConstantPool,
+) -> JvmParseResult<Attribute> {
+    let name = cpool.resolve_utf8(attribute_name_index);
+    let segment = attribute_segment(attribute_name_index, cpool);
+    let mut info_reader = AttributeReader {
+        bytes: &info,
+        end,
+        context,
+    };
+    let mark = context.mark();
+    let result = match name {
+        Ok(name) => context
+            .enter()
+            .and_then(|()| {
+                let result = parse_attribute_info(name, &mut info_reader, cpool);
+                context.leave();
+                match result {
+                    Ok(Some(_)) if !info_reader.bytes.is_empty() => {
+                        Err(JvmParseError::InvalidFormat(format!(
+                            "{} trailing bytes",
+                            info_reader.bytes.len()
+                        )))
+                    }
+                    result => result,
+                }
+            })
+            .map_err(|error| error.at(info_reader.position())),
+        Err(error) => Err(error.at(info_reader.position() - 6)),
+    };
+    context.within_since(mark, &segment);
+
+    match result {
+        Ok(Some(attribute)) => Ok(attribute),
+        Ok(None) => Ok(Attribute::Unknown {
+            name: attribute_name_index,
+            value: info,
+        }),
+        Err(error) if context.lenient => {
+            context
+                .diagnostics
+                .borrow_mut()
+                .push(error.within(&segment));
+            Ok(Attribute::Unknown {
                 name: attribute_name_index,
                 value: info,
-            },
-        })
+            })
+        }
+        Err(error) => Err(error.within(segment)),
     }
 }
+
+/// Known attribute from its info bytes, `None` for unknown attributes.
+fn parse_attribute_info(
+    name: &str,
+    reader: &mut AttributeReader<'_>,
+    cpool: &ConstantPool,
+) -> JvmParseResult<Option<Attribute>> {
+    Ok(Some(match name {
+        "Code" => Attribute::Code(reader.parse(cpool)?),
+        "ConstantValue" => Attribute::ConstantValue(reader.parse(cpool)?),
+        "SourceFile" => Attribute::SourceFile(reader.parse(cpool)?),
+        "Signature" => Attribute::Signature(reader.parse(cpool)?),
+        "StackMapTable" => Attribute::StackMapTable(reader.parse(cpool)?),
+        "LineNumberTable" => Attribute::LineNumberTable(reader.parse(cpool)?),
+        "LocalVariableTable" => Attribute::LocalVariableTable(reader.parse(cpool)?),
+        "LocalVariableTypeTable" => Attribute::LocalVariableTypeTable(reader.parse(cpool)?),
+        "RuntimeVisibleAnnotations" => Attribute::RuntimeVisibleAnnotations(reader.parse(cpool)?),
+        "RuntimeInvisibleAnnotations" => {
+            Attribute::RuntimeInvisibleAnnotations(reader.parse(cpool)?)
+        }
+        "RuntimeVisibleParameterAnnotations" => Attribute::RuntimeVisibleParameterAnnotations(
+            parse_parameter_annotations(reader, cpool)?,
+        ),
+        "RuntimeInvisibleParameterAnnotations" => Attribute::RuntimeInvisibleParameterAnnotations(
+            parse_parameter_annotations(reader, cpool)?,
+        ),
+        "AnnotationDefault" => Attribute::AnnotationDefault(reader.parse(cpool)?),
+        "BootstrapMethods" => Attribute::BootstrapMethods(reader.parse(cpool)?),
+        "Module" => Attribute::Module(reader.parse(cpool)?),
+        "ModulePackages" => Attribute::ModulePackages(reader.parse(cpool)?),
+        "ModuleMainClass" => Attribute::ModuleMainClass(reader.parse(cpool)?),
+        "InnerClasses" => Attribute::InnerClasses(reader.parse(cpool)?),
+        "EnclosingMethod" => Attribute::EnclosingMethod(reader.parse(cpool)?),
+        "NestHost" => Attribute::NestHost(reader.parse(cpool)?),
+        "NestMembers" => Attribute::NestMembers(reader.parse(cpool)?),
+        "Record" => Attribute::Record(reader.parse(cpool)?),
+        "PermittedSubclasses" => Attribute::PermittedSubclasses(reader.parse(cpool)?),
+        "Exceptions" => Attribute::Exceptions(reader.parse(cpool)?),
+        "MethodParameters" => Attribute::MethodParameters(parse_method_parameters(reader, cpool)?),
+        "Deprecated" => Attribute::Deprecated,
+        "Synthetic" => Attribute::Synthetic,
+        "RuntimeVisibleTypeAnnotations" => {
+            Attribute::RuntimeVisibleTypeAnnotations(reader.parse(cpool)?)
+        }
+        "RuntimeInvisibleTypeAnnotations" => {
+            Attribute::RuntimeInvisibleTypeAnnotations(reader.parse(cpool)?)
+        }
+        _ => return Ok(None),
+    }))
+}
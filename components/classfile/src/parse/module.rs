@@ -0,0 +1,67 @@
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::error::JvmParseResult;
+use crate::model::constants::ConstantPool;
+use crate::model::module::{
+    Exports, Module, ModuleFlags, Opens, PackageFlags, Provides, Requires, RequiresFlags,
+};
+use crate::model::FlagBits;
+use crate::parse::{ClassFileEntry, ClassFilePrimitive, ClassFileRead, ReadClassFileExt};
+
+parse_flags!(ModuleFlags, RequiresFlags, PackageFlags);
+
+impl ClassFileEntry for Module {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Module {
+            module_name_index: reader.parse(cpool)?,
+            module_flags: reader.parse(cpool)?,
+            module_version_index: reader.parse(cpool)?,
+            requires: reader.parse(cpool)?,
+            exports: reader.parse(cpool)?,
+            opens: reader.parse(cpool)?,
+            uses: reader.parse(cpool)?,
+            provides: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for Requires {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Requires {
+            requires_index: reader.parse(cpool)?,
+            requires_flags: reader.parse(cpool)?,
+            requires_version_index: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for Exports {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Exports {
+            exports_index: reader.parse(cpool)?,
+            exports_flags: reader.parse(cpool)?,
+            exports_to: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for Opens {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Opens {
+            opens_index: reader.parse(cpool)?,
+            opens_flags: reader.parse(cpool)?,
+            opens_to: reader.parse(cpool)?,
+        })
+    }
+}
+
+impl ClassFileEntry for Provides {
+    fn parse<T: ClassFileRead>(reader: &mut T, cpool: &ConstantPool) -> JvmParseResult<Self> {
+        Ok(Provides {
+            provides_index: reader.parse(cpool)?,
+            provides_with: reader.parse(cpool)?,
+        })
+    }
+}
@@ -0,0 +1,653 @@
+//! Generic signatures as specified in
+//! https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.9.1
+//!
+//! The `Display` implementations print signatures like they are written in
+//! Java source code, e.g. `java.util.List<? extends java.lang.Number>`.
+//!
+//! Signatures come from untrusted class files, so arrays may have at most 255
+//! dimensions and type arguments may be nested at most as deep as the default
+//! `ParseLimits::max_depth`.
+
+use std::fmt;
+
+use crate::parse::ParseLimits;
+
+/// Maximal number of array dimensions (JVMS 4.4.1).
+const MAX_ARRAY_DIMENSIONS: usize = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JavaTypeSignature {
+    Base(BaseType),
+    Reference(ReferenceTypeSignature),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceTypeSignature {
+    Class(ClassTypeSignature),
+    TypeVariable(String),
+    Array(Box<JavaTypeSignature>),
+}
+
+/// Class type like `java/util/Map<TK;TV;>.Entry`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassTypeSignature {
+    /// Package in internal form like `java/util`, empty for the default package.
+    pub package: String,
+    /// Outermost class first, followed by the inner classes.
+    pub classes: Vec<SimpleClassTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleClassTypeSignature {
+    pub name: String,
+    pub type_arguments: Vec<TypeArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeArgument {
+    /// `?`
+    Any,
+    Exact(ReferenceTypeSignature),
+    /// `? extends`
+    Extends(ReferenceTypeSignature),
+    /// `? super`
+    Super(ReferenceTypeSignature),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParameter {
+    pub name: String,
+    pub class_bound: Option<ReferenceTypeSignature>,
+    pub interface_bounds: Vec<ReferenceTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub super_class: ClassTypeSignature,
+    pub interfaces: Vec<ClassTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub params: Vec<JavaTypeSignature>,
+    /// `None` for `void`.
+    pub result: Option<JavaTypeSignature>,
+    /// Class types or type variables.
+    pub throws: Vec<ReferenceTypeSignature>,
+}
+
+impl ClassTypeSignature {
+    /// Internal name of the erased class like `java/util/Map$Entry`.
+    pub fn erasure(&self) -> String {
+        let mut name = self.package.clone();
+        for (i, class) in self.classes.iter().enumerate() {
+            if i > 0 {
+                name.push('$');
+            } else if !name.is_empty() {
+                name.push('/');
+            }
+            name.push_str(&class.name);
+        }
+        name
+    }
+}
+
+impl fmt::Display for BaseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BaseType::Byte => "byte",
+            BaseType::Char => "char",
+            BaseType::Double => "double",
+            BaseType::Float => "float",
+            BaseType::Int => "int",
+            BaseType::Long => "long",
+            BaseType::Short => "short",
+            BaseType::Boolean => "boolean",
+        })
+    }
+}
+
+impl fmt::Display for JavaTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JavaTypeSignature::Base(base_type) => base_type.fmt(f),
+            JavaTypeSignature::Reference(reference) => reference.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for ReferenceTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceTypeSignature::Class(class) => class.fmt(f),
+            ReferenceTypeSignature::TypeVariable(name) => f.write_str(name),
+            ReferenceTypeSignature::Array(component) => write!(f, "{}[]", component),
+        }
+    }
+}
+
+fn write_separated<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    sep: &str,
+    elems: &[T],
+) -> fmt::Result {
+    for (i, elem) in elems.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        elem.fmt(f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ClassTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.package.is_empty() {
+            write!(f, "{}.", self.package.replace('/', "."))?;
+        }
+        write_separated(f, ".", &self.classes)
+    }
+}
+
+impl fmt::Display for SimpleClassTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.type_arguments.is_empty() {
+            f.write_str("<")?;
+            write_separated(f, ", ", &self.type_arguments)?;
+            f.write_str(">")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeArgument::Any => f.write_str("?"),
+            TypeArgument::Exact(ty) => ty.fmt(f),
+            TypeArgument::Extends(ty) => write!(f, "? extends {}", ty),
+            TypeArgument::Super(ty) => write!(f, "? super {}", ty),
+        }
+    }
+}
+
+fn is_object(ty: &ReferenceTypeSignature) -> bool {
+    match ty {
+        ReferenceTypeSignature::Class(class) => {
+            class.package == "java/lang"
+                && class.classes.len() == 1
+                && class.classes[0].name == "Object"
+                && class.classes[0].type_arguments.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// A sole `java.lang.Object` bound is omitted, as it is implicit in Java.
+impl fmt::Display for TypeParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        let bounds: Vec<&ReferenceTypeSignature> = self
+            .class_bound
+            .iter()
+            .chain(self.interface_bounds.iter())
+            .collect();
+        if bounds.len() == 1 && is_object(bounds[0]) {
+            return Ok(());
+        }
+        if !bounds.is_empty() {
+            f.write_str(" extends ")?;
+            write_separated(f, " & ", &bounds)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_type_parameters(f: &mut fmt::Formatter<'_>, params: &[TypeParameter]) -> fmt::Result {
+    if !params.is_empty() {
+        f.write_str("<")?;
+        write_separated(f, ", ", params)?;
+        f.write_str("> ")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ClassSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        write!(f, "extends {}", self.super_class)?;
+        if !self.interfaces.is_empty() {
+            f.write_str(" implements ")?;
+            write_separated(f, ", ", &self.interfaces)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        match &self.result {
+            Some(result) => result.fmt(f)?,
+            None => f.write_str("void")?,
+        }
+        f.write_str(" (")?;
+        write_separated(f, ", ", &self.params)?;
+        f.write_str(")")?;
+        if !self.throws.is_empty() {
+            f.write_str(" throws ")?;
+            write_separated(f, ", ", &self.throws)?;
+        }
+        Ok(())
+    }
+}
+
+fn identifier(x: &str) -> Option<(&str, &str)> {
+    let end = x
+        .find(&['.', ';', '[', '/', '<', '>', ':'][..])
+        .unwrap_or(x.len());
+    if end == 0 {
+        None
+    } else {
+        Some((&x[end..], &x[..end]))
+    }
+}
+
+fn base_type(c: char) -> Option<BaseType> {
+    Some(match c {
+        'B' => BaseType::Byte,
+        'C' => BaseType::Char,
+        'D' => BaseType::Double,
+        'F' => BaseType::Float,
+        'I' => BaseType::Int,
+        'J' => BaseType::Long,
+        'S' => BaseType::Short,
+        'Z' => BaseType::Boolean,
+        _ => return None,
+    })
+}
+
+/// Levels of type arguments a signature may contain.
+fn max_depth() -> usize {
+    ParseLimits::default().max_depth
+}
+
+/// Java types are parsed with the remaining nesting `depth` of type
+/// arguments, parsing fails when it is exhausted.
+fn java_type_signature(x: &str, depth: usize) -> Option<(&str, JavaTypeSignature)> {
+    match base_type(x.chars().next()?) {
+        Some(base_type) => Some((&x[1..], JavaTypeSignature::Base(base_type))),
+        None => {
+            let (x, reference) = reference_type_signature(x, depth)?;
+            Some((x, JavaTypeSignature::Reference(reference)))
+        }
+    }
+}
+
+fn reference_type_signature(x: &str, depth: usize) -> Option<(&str, ReferenceTypeSignature)> {
+    match x.chars().next()? {
+        'L' => {
+            let (x, class) = class_type_signature(x, depth)?;
+            Some((x, ReferenceTypeSignature::Class(class)))
+        }
+        'T' => {
+            let (x, name) = identifier(&x[1..])?;
+            Some((
+                x.strip_prefix(';')?,
+                ReferenceTypeSignature::TypeVariable(name.to_string()),
+            ))
+        }
+        '[' => {
+            let dimensions = x.find(|c| c != '[').unwrap_or(x.len());
+            if dimensions > MAX_ARRAY_DIMENSIONS {
+                return None;
+            }
+            let (x, component) = java_type_signature(&x[dimensions..], depth)?;
+            let mut array = ReferenceTypeSignature::Array(Box::new(component));
+            for _ in 1..dimensions {
+                array =
+                    ReferenceTypeSignature::Array(Box::new(JavaTypeSignature::Reference(array)));
+            }
+            Some((x, array))
+        }
+        _ => None,
+    }
+}
+
+fn class_type_signature(x: &str, depth: usize) -> Option<(&str, ClassTypeSignature)> {
+    let mut x = x.strip_prefix('L')?;
+    let mut package = String::new();
+    let outer = loop {
+        let (rest, name) = identifier(x)?;
+        match rest.strip_prefix('/') {
+            Some(rest) => {
+                if !package.is_empty() {
+                    package.push('/');
+                }
+                package.push_str(name);
+                x = rest;
+            }
+            None => {
+                let (rest, outer) = simple_class_type_signature(rest, name, depth)?;
+                x = rest;
+                break outer;
+            }
+        }
+    };
+
+    let mut classes = vec![outer];
+    while let Some(rest) = x.strip_prefix('.') {
+        let (rest, name) = identifier(rest)?;
+        let (rest, inner) = simple_class_type_signature(rest, name, depth)?;
+        classes.push(inner);
+        x = rest;
+    }
+
+    Some((
+        x.strip_prefix(';')?,
+        ClassTypeSignature { package, classes },
+    ))
+}
+
+/// Type arguments following the already parsed class name.
+fn simple_class_type_signature<'a>(
+    x: &'a str,
+    name: &str,
+    depth: usize,
+) -> Option<(&'a str, SimpleClassTypeSignature)> {
+    let mut type_arguments = vec![];
+    let mut x = x;
+    if let Some(mut rest) = x.strip_prefix('<') {
+        let depth = depth.checked_sub(1)?;
+        while !rest.starts_with('>') {
+            let (after, type_argument) = type_argument(rest, depth)?;
+            type_arguments.push(type_argument);
+            rest = after;
+        }
+        if type_arguments.is_empty() {
+            return None;
+        }
+        x = &rest[1..];
+    }
+
+    Some((
+        x,
+        SimpleClassTypeSignature {
+            name: name.to_string(),
+            type_arguments,
+        },
+    ))
+}
+
+fn type_argument(x: &str, depth: usize) -> Option<(&str, TypeArgument)> {
+    Some(match x.chars().next()? {
+        '*' => (&x[1..], TypeArgument::Any),
+        '+' => {
+            let (x, ty) = reference_type_signature(&x[1..], depth)?;
+            (x, TypeArgument::Extends(ty))
+        }
+        '-' => {
+            let (x, ty) = reference_type_signature(&x[1..], depth)?;
+            (x, TypeArgument::Super(ty))
+        }
+        _ => {
+            let (x, ty) = reference_type_signature(x, depth)?;
+            (x, TypeArgument::Exact(ty))
+        }
+    })
+}
+
+fn type_parameters(x: &str) -> Option<(&str, Vec<TypeParameter>)> {
+    let mut params = vec![];
+    let mut x = match x.strip_prefix('<') {
+        Some(x) => x,
+        None => return Some((x, params)),
+    };
+
+    while !x.starts_with('>') {
+        let (rest, name) = identifier(x)?;
+        x = rest.strip_prefix(':')?;
+
+        let mut class_bound = None;
+        if !x.starts_with(':') && !x.starts_with('>') {
+            let (rest, bound) = reference_type_signature(x, max_depth())?;
+            class_bound = Some(bound);
+            x = rest;
+        }
+
+        let mut interface_bounds = vec![];
+        while let Some(rest) = x.strip_prefix(':') {
+            let (rest, bound) = reference_type_signature(rest, max_depth())?;
+            interface_bounds.push(bound);
+            x = rest;
+        }
+
+        params.push(TypeParameter {
+            name: name.to_string(),
+            class_bound,
+            interface_bounds,
+        });
+    }
+
+    if params.is_empty() {
+        None
+    } else {
+        Some((&x[1..], params))
+    }
+}
+
+pub fn parse_class_signature(x: &str) -> Option<ClassSignature> {
+    let (x, type_parameters) = type_parameters(x)?;
+    let (mut x, super_class) = class_type_signature(x, max_depth())?;
+    let mut interfaces = vec![];
+    while !x.is_empty() {
+        let (rest, interface) = class_type_signature(x, max_depth())?;
+        interfaces.push(interface);
+        x = rest;
+    }
+
+    Some(ClassSignature {
+        type_parameters,
+        super_class,
+        interfaces,
+    })
+}
+
+pub fn parse_method_signature(x: &str) -> Option<MethodSignature> {
+    let (x, type_parameters) = type_parameters(x)?;
+    let mut x = x.strip_prefix('(')?;
+    let mut params = vec![];
+    while !x.starts_with(')') {
+        let (rest, param) = java_type_signature(x, max_depth())?;
+        params.push(param);
+        x = rest;
+    }
+    x = &x[1..];
+
+    let result = match x.strip_prefix('V') {
+        Some(rest) => {
+            x = rest;
+            None
+        }
+        None => {
+            let (rest, result) = java_type_signature(x, max_depth())?;
+            x = rest;
+            Some(result)
+        }
+    };
+
+    let mut throws = vec![];
+    while let Some(rest) = x.strip_prefix('^') {
+        let (rest, exception) = reference_type_signature(rest, max_depth())?;
+        if let ReferenceTypeSignature::Array(_) = exception {
+            return None;
+        }
+        throws.push(exception);
+        x = rest;
+    }
+
+    if x.is_empty() {
+        Some(MethodSignature {
+            type_parameters,
+            params,
+            result,
+            throws,
+        })
+    } else {
+        None
+    }
+}
+
+pub fn parse_field_signature(x: &str) -> Option<ReferenceTypeSignature> {
+    match reference_type_signature(x, max_depth()) {
+        Some(("", field_type)) => Some(field_type),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(package: &str, name: &str, type_arguments: Vec<TypeArgument>) -> ClassTypeSignature {
+        ClassTypeSignature {
+            package: package.into(),
+            classes: vec![SimpleClassTypeSignature {
+                name: name.into(),
+                type_arguments,
+            }],
+        }
+    }
+
+    #[test]
+    fn wildcard() {
+        let ty = parse_field_signature("Ljava/util/List<+Ljava/lang/Number;>;").unwrap();
+        assert_eq!(
+            ty,
+            ReferenceTypeSignature::Class(class(
+                "java/util",
+                "List",
+                vec![TypeArgument::Extends(ReferenceTypeSignature::Class(class(
+                    "java/lang",
+                    "Number",
+                    vec![]
+                )))]
+            ))
+        );
+        assert_eq!(
+            &format!("{}", ty),
+            "java.util.List<? extends java.lang.Number>"
+        );
+    }
+
+    #[test]
+    fn wildcards() {
+        let ty = parse_field_signature("Ljava/util/Map<*-TK;>;").unwrap();
+        assert_eq!(&format!("{}", ty), "java.util.Map<?, ? super K>");
+    }
+
+    #[test]
+    fn inner_class() {
+        let ty = parse_field_signature("LOuter<TT;>.Inner<[I>.Deep;").unwrap();
+        match &ty {
+            ReferenceTypeSignature::Class(class) => {
+                assert_eq!(class.package, "");
+                assert_eq!(class.classes.len(), 3);
+                assert_eq!(class.erasure(), "Outer$Inner$Deep");
+            }
+            _ => panic!("expected class type"),
+        }
+        assert_eq!(&format!("{}", ty), "Outer<T>.Inner<int[]>.Deep");
+    }
+
+    #[test]
+    fn arrays() {
+        let ty = parse_field_signature("[[TT;").unwrap();
+        assert_eq!(&format!("{}", ty), "T[][]");
+        assert_eq!(parse_field_signature("I"), None);
+    }
+
+    #[test]
+    fn class_signature() {
+        let signature = parse_class_signature(
+            "<T::Ljava/lang/Comparable<-TT;>;U:Ljava/lang/Object;>Ljava/util/ArrayList<TT;>;Ljava/io/Serializable;",
+        )
+        .unwrap();
+        assert_eq!(signature.type_parameters.len(), 2);
+        assert_eq!(signature.type_parameters[0].class_bound, None);
+        assert_eq!(signature.interfaces.len(), 1);
+        assert_eq!(
+            &format!("{}", signature),
+            "<T extends java.lang.Comparable<? super T>, U> extends java.util.ArrayList<T> implements java.io.Serializable"
+        );
+    }
+
+    #[test]
+    fn method_signature() {
+        let signature = parse_method_signature(
+            "<E:Ljava/lang/Exception;>(Ljava/util/List<*>;J)TE;^TE;^Ljava/io/IOException;",
+        )
+        .unwrap();
+        assert_eq!(signature.params.len(), 2);
+        assert_eq!(signature.throws.len(), 2);
+        assert_eq!(
+            &format!("{}", signature),
+            "<E extends java.lang.Exception> E (java.util.List<?>, long) throws E, java.io.IOException"
+        );
+
+        let signature = parse_method_signature("()V").unwrap();
+        assert_eq!(signature.result, None);
+        assert_eq!(&format!("{}", signature), "void ()");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse_field_signature("Ljava/util/List<>;"), None);
+        assert_eq!(parse_field_signature("Ljava/util/List"), None);
+        assert_eq!(parse_field_signature("Ljava/util/List;;"), None);
+        assert_eq!(parse_field_signature("TT"), None);
+        assert_eq!(parse_class_signature("<>Ljava/lang/Object;"), None);
+        assert_eq!(parse_class_signature("<T>Ljava/lang/Object;"), None);
+        assert_eq!(parse_method_signature("(I)"), None);
+        assert_eq!(parse_method_signature("()V^[I"), None);
+        assert_eq!(parse_method_signature("()VI"), None);
+    }
+
+    #[test]
+    fn array_dimensions() {
+        let ty = parse_field_signature(&format!("{}I", "[".repeat(255))).unwrap();
+        assert_eq!(format!("{}", ty), format!("int{}", "[]".repeat(255)));
+        assert_eq!(
+            parse_field_signature(&format!("{}I", "[".repeat(256))),
+            None
+        );
+        assert_eq!(
+            parse_field_signature(&format!("{}I", "[".repeat(60_000))),
+            None
+        );
+    }
+
+    #[test]
+    fn type_argument_depth() {
+        let nested = |depth: usize| format!("{}LA;{}", "LA<".repeat(depth), ">;".repeat(depth));
+        let depth = ParseLimits::default().max_depth;
+        assert!(parse_field_signature(&nested(depth)).is_some());
+        assert_eq!(parse_field_signature(&nested(depth + 1)), None);
+        assert_eq!(parse_field_signature(&"LA<".repeat(20_000)), None);
+    }
+}
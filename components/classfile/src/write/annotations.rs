@@ -0,0 +1,166 @@
+use std::convert::TryFrom;
+use std::io::Write;
+
+use byteorder::WriteBytesExt;
+
+use crate::error::{JvmWriteError, JvmWriteResult};
+use crate::model::annotations::{
+    Annotation, ElementValue, ElementValuePair, LocalVarTargetEntry, TypeAnnotation,
+    TypeAnnotationTarget, TypePath, TypePathEntry,
+};
+use crate::model::constants::ConstantPool;
+use crate::write::{WriteClassFileEntry, WriteClassFileExt};
+
+impl WriteClassFileEntry for Annotation {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.type_index, cpool)?;
+        writer.write_entry(&self.elements, cpool)
+    }
+}
+
+impl WriteClassFileEntry for ElementValuePair {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.value, cpool)
+    }
+}
+
+impl WriteClassFileEntry for ElementValue {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        match self {
+            ElementValue::Const(kind, const_value_index) => {
+                writer.write_u8(kind.tag())?;
+                writer.write_entry(const_value_index, cpool)
+            }
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+            } => {
+                writer.write_u8(b'e')?;
+                writer.write_entry(type_name_index, cpool)?;
+                writer.write_entry(const_name_index, cpool)
+            }
+            ElementValue::Class(class_info_index) => {
+                writer.write_u8(b'c')?;
+                writer.write_entry(class_info_index, cpool)
+            }
+            ElementValue::Annotation(annotation) => {
+                writer.write_u8(b'@')?;
+                writer.write_entry(annotation, cpool)
+            }
+            ElementValue::Array(values) => {
+                writer.write_u8(b'[')?;
+                writer.write_entry(values, cpool)
+            }
+        }
+    }
+}
+
+impl WriteClassFileEntry for LocalVarTargetEntry {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.start_pc, cpool)?;
+        writer.write_entry(&self.length, cpool)?;
+        writer.write_entry(&self.index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for TypeAnnotationTarget {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_u8(self.target_type())?;
+        match self {
+            TypeAnnotationTarget::ClassTypeParameter(index)
+            | TypeAnnotationTarget::MethodTypeParameter(index)
+            | TypeAnnotationTarget::FormalParameter(index) => writer.write_u8(*index)?,
+            TypeAnnotationTarget::ClassTypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            }
+            | TypeAnnotationTarget::MethodTypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => {
+                writer.write_u8(*type_parameter_index)?;
+                writer.write_u8(*bound_index)?;
+            }
+            TypeAnnotationTarget::Field
+            | TypeAnnotationTarget::MethodReturn
+            | TypeAnnotationTarget::MethodReceiver => {}
+            TypeAnnotationTarget::Supertype(index)
+            | TypeAnnotationTarget::Throws(index)
+            | TypeAnnotationTarget::ExceptionParameter(index)
+            | TypeAnnotationTarget::Instanceof(index)
+            | TypeAnnotationTarget::New(index)
+            | TypeAnnotationTarget::ConstructorReference(index)
+            | TypeAnnotationTarget::MethodReference(index) => writer.write_entry(index, cpool)?,
+            TypeAnnotationTarget::LocalVariable(table)
+            | TypeAnnotationTarget::ResourceVariable(table) => writer.write_entry(table, cpool)?,
+            TypeAnnotationTarget::Cast {
+                offset,
+                type_argument_index,
+            }
+            | TypeAnnotationTarget::ConstructorInvocationTypeArgument {
+                offset,
+                type_argument_index,
+            }
+            | TypeAnnotationTarget::MethodInvocationTypeArgument {
+                offset,
+                type_argument_index,
+            }
+            | TypeAnnotationTarget::ConstructorReferenceTypeArgument {
+                offset,
+                type_argument_index,
+            }
+            | TypeAnnotationTarget::MethodReferenceTypeArgument {
+                offset,
+                type_argument_index,
+            } => {
+                writer.write_entry(offset, cpool)?;
+                writer.write_u8(*type_argument_index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WriteClassFileEntry for TypePathEntry {
+    fn write<T: Write>(&self, writer: &mut T, _cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_u8(self.kind as u8)?;
+        Ok(writer.write_u8(self.type_argument_index)?)
+    }
+}
+
+impl WriteClassFileEntry for TypePath {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        let path_length = u8::try_from(self.path.len()).map_err(|_| {
+            JvmWriteError::InvalidFormat(format!("type path too long: {}", self.path.len()))
+        })?;
+        writer.write_u8(path_length)?;
+        for entry in &self.path {
+            writer.write_entry(entry, cpool)?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteClassFileEntry for TypeAnnotation {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.target, cpool)?;
+        writer.write_entry(&self.type_path, cpool)?;
+        writer.write_entry(&self.annotation, cpool)
+    }
+}
+
+pub fn write_parameter_annotations<T: Write>(
+    writer: &mut T,
+    parameters: &[Vec<Annotation>],
+    cpool: &ConstantPool,
+) -> JvmWriteResult<()> {
+    let num_parameters = u8::try_from(parameters.len()).map_err(|_| {
+        JvmWriteError::InvalidFormat(format!("too many parameters: {}", parameters.len()))
+    })?;
+    writer.write_u8(num_parameters)?;
+    for annotations in parameters {
+        writer.write_entry(annotations, cpool)?;
+    }
+    Ok(())
+}
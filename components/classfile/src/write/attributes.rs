@@ -0,0 +1,187 @@
+use std::io::Write;
+
+use byteorder::WriteBytesExt;
+use rustjvm_opcode::assemble;
+
+use crate::error::{JvmWriteError, JvmWriteResult};
+use crate::model::attributes::{
+    BootstrapMethod, Code, ConstantValue, EnclosingMethod, ExceptionTableEntry, InnerClass,
+    LineNumber, LocalVariable, LocalVariableType, RecordComponent, StackMapFrameKind,
+    StackMapTable, VerificationTypeInfo,
+};
+use crate::model::constants::ConstantPool;
+use crate::write::{write_bytes_u32, write_length_u16, WriteClassFileEntry, WriteClassFileExt};
+
+impl WriteClassFileEntry for ConstantValue {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.constantvalue_index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for BootstrapMethod {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.bootstrap_method_ref, cpool)?;
+        writer.write_entry(&self.bootstrap_arguments, cpool)
+    }
+}
+
+impl WriteClassFileEntry for InnerClass {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.inner_class_info_index, cpool)?;
+        writer.write_entry(&self.outer_class_info_index, cpool)?;
+        writer.write_entry(&self.inner_name_index, cpool)?;
+        writer.write_entry(&self.inner_class_access_flags, cpool)
+    }
+}
+
+impl WriteClassFileEntry for EnclosingMethod {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.class_index, cpool)?;
+        writer.write_entry(&self.method_index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for RecordComponent {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.descriptor_index, cpool)?;
+        writer.write_entry(&self.attributes, cpool)
+    }
+}
+
+impl WriteClassFileEntry for ExceptionTableEntry {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.start_pc, cpool)?;
+        writer.write_entry(&self.end_pc, cpool)?;
+        writer.write_entry(&self.handler_pc, cpool)?;
+        writer.write_entry(&self.catch_type, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Code {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.max_stack, cpool)?;
+        writer.write_entry(&self.max_locals, cpool)?;
+        write_bytes_u32(writer, &assemble(self.code.instructions())?)?;
+        writer.write_entry(&self.exception_table, cpool)?;
+        writer.write_entry(&self.attributes, cpool)
+    }
+}
+
+impl WriteClassFileEntry for LineNumber {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.start_pc, cpool)?;
+        writer.write_entry(&self.line_number, cpool)
+    }
+}
+
+impl WriteClassFileEntry for LocalVariable {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.start_pc, cpool)?;
+        writer.write_entry(&self.length, cpool)?;
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.descriptor_index, cpool)?;
+        writer.write_entry(&self.index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for LocalVariableType {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.start_pc, cpool)?;
+        writer.write_entry(&self.length, cpool)?;
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.signature_index, cpool)?;
+        writer.write_entry(&self.index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for VerificationTypeInfo {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        match self {
+            VerificationTypeInfo::Top => writer.write_u8(0)?,
+            VerificationTypeInfo::Integer => writer.write_u8(1)?,
+            VerificationTypeInfo::Float => writer.write_u8(2)?,
+            VerificationTypeInfo::Double => writer.write_u8(3)?,
+            VerificationTypeInfo::Long => writer.write_u8(4)?,
+            VerificationTypeInfo::Null => writer.write_u8(5)?,
+            VerificationTypeInfo::UninitializedThis => writer.write_u8(6)?,
+            VerificationTypeInfo::Object(class_index) => {
+                writer.write_u8(7)?;
+                writer.write_entry(class_index, cpool)?;
+            }
+            VerificationTypeInfo::Uninitialized(offset) => {
+                writer.write_u8(8)?;
+                writer.write_entry(offset, cpool)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WriteClassFileEntry for StackMapTable {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        write_length_u16(writer, self.entries.len())?;
+        let mut previous: Option<u16> = None;
+        for frame in &self.entries {
+            let offset_delta = match previous {
+                Some(previous) => frame
+                    .offset
+                    .checked_sub(previous)
+                    .and_then(|delta| delta.checked_sub(1)),
+                None => Some(frame.offset),
+            }
+            .ok_or_else(|| {
+                JvmWriteError::InvalidFormat(format!(
+                    "stack map frame at {} is not after the previous frame",
+                    frame.offset
+                ))
+            })?;
+            previous = Some(frame.offset);
+
+            // short forms are replaced by extended forms when the delta is too large
+            match &frame.kind {
+                StackMapFrameKind::Same if offset_delta < 64 => {
+                    writer.write_u8(offset_delta as u8)?;
+                }
+                StackMapFrameKind::Same | StackMapFrameKind::SameExtended => {
+                    writer.write_u8(251)?;
+                    writer.write_entry(&offset_delta, cpool)?;
+                }
+                StackMapFrameKind::SameLocals1StackItem(stack) if offset_delta < 64 => {
+                    writer.write_u8(64 + offset_delta as u8)?;
+                    writer.write_entry(stack, cpool)?;
+                }
+                StackMapFrameKind::SameLocals1StackItem(stack)
+                | StackMapFrameKind::SameLocals1StackItemExtended(stack) => {
+                    writer.write_u8(247)?;
+                    writer.write_entry(&offset_delta, cpool)?;
+                    writer.write_entry(stack, cpool)?;
+                }
+                StackMapFrameKind::Chop(count @ 1..=3) => {
+                    writer.write_u8(251 - count)?;
+                    writer.write_entry(&offset_delta, cpool)?;
+                }
+                StackMapFrameKind::Append(locals) if (1..=3).contains(&locals.len()) => {
+                    writer.write_u8(251 + locals.len() as u8)?;
+                    writer.write_entry(&offset_delta, cpool)?;
+                    for local in locals {
+                        writer.write_entry(local, cpool)?;
+                    }
+                }
+                StackMapFrameKind::Full { locals, stack } => {
+                    writer.write_u8(255)?;
+                    writer.write_entry(&offset_delta, cpool)?;
+                    writer.write_entry(locals, cpool)?;
+                    writer.write_entry(stack, cpool)?;
+                }
+                StackMapFrameKind::Chop(_) | StackMapFrameKind::Append(_) => {
+                    return Err(JvmWriteError::InvalidFormat(format!(
+                        "stack map frame at {} must chop or append 1 to 3 locals",
+                        frame.offset
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
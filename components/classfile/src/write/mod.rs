@@ -0,0 +1,378 @@
+use std::convert::TryFrom;
+use std::io::Write;
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::error::{JvmWriteError, JvmWriteResult};
+use crate::model::constants::{Constant, ConstantIndex, ConstantPool};
+use crate::model::{
+    Attribute, Attributes, ClassAccessFlags, ClassFile, Field, FieldAccessFlags,
+    InnerClassAccessFlags, Method, MethodAccessFlags, MethodParameter, ParameterAccessFlags,
+};
+use crate::write::annotations::write_parameter_annotations;
+
+/// Implements `WriteClassFilePrimitive` for u16 bitflags types.
+macro_rules! write_flags {
+    ($($flags:ty),*) => {
+        $(
+            impl WriteClassFilePrimitive for $flags {
+                fn write_primitive<T: Write>(&self, writer: &mut T) -> JvmWriteResult<()> {
+                    Ok(writer.write_u16::<BigEndian>(self.bits())?)
+                }
+            }
+        )*
+    };
+}
+
+mod annotations;
+mod attributes;
+mod module;
+
+pub(crate) trait WriteClassFileExt: Write + Sized {
+    fn write_entry<T: WriteClassFileEntry>(
+        &mut self,
+        value: &T,
+        cpool: &ConstantPool,
+    ) -> JvmWriteResult<()> {
+        value.write(self, cpool)
+    }
+}
+
+impl<W: Write + Sized> WriteClassFileExt for W {}
+
+/// Serializes a class file in the format of
+/// https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html
+pub fn write_class_file<T: Write>(class_file: &ClassFile, mut writer: T) -> JvmWriteResult<()> {
+    let cpool = &class_file.constants;
+    writer.write_u32::<BigEndian>(class_file.magic)?;
+    writer.write_u16::<BigEndian>(class_file.minor_version)?;
+    writer.write_u16::<BigEndian>(class_file.major_version)?;
+    write_constants(&mut writer, cpool)?;
+    writer.write_entry(&class_file.access_flags, cpool)?;
+    writer.write_entry(&class_file.this_class, cpool)?;
+    writer.write_entry(&class_file.super_class, cpool)?;
+    writer.write_entry(&class_file.interfaces, cpool)?;
+    writer.write_entry(&class_file.fields, cpool)?;
+    writer.write_entry(&class_file.methods, cpool)?;
+    writer.write_entry(&class_file.attributes, cpool)?;
+    Ok(())
+}
+
+fn write_constants<T: Write>(writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+    let constants = cpool.constants();
+    write_length_u16(writer, constants.len() + 1)?;
+
+    for constant in constants {
+        match constant {
+            Constant::Class { name_index } => {
+                writer.write_u8(7)?;
+                name_index.write_primitive(writer)?;
+            }
+            Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_u8(9)?;
+                class_index.write_primitive(writer)?;
+                name_and_type_index.write_primitive(writer)?;
+            }
+            Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_u8(10)?;
+                class_index.write_primitive(writer)?;
+                name_and_type_index.write_primitive(writer)?;
+            }
+            Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_u8(11)?;
+                class_index.write_primitive(writer)?;
+                name_and_type_index.write_primitive(writer)?;
+            }
+            Constant::String(string_index) => {
+                writer.write_u8(8)?;
+                string_index.write_primitive(writer)?;
+            }
+            Constant::Integer(value) => {
+                writer.write_u8(3)?;
+                writer.write_i32::<BigEndian>(*value)?;
+            }
+            Constant::Float(value) => {
+                writer.write_u8(4)?;
+                writer.write_f32::<BigEndian>(*value)?;
+            }
+            Constant::Long(value) => {
+                writer.write_u8(5)?;
+                writer.write_i64::<BigEndian>(*value)?;
+            }
+            Constant::Double(value) => {
+                writer.write_u8(6)?;
+                writer.write_f64::<BigEndian>(*value)?;
+            }
+            Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            } => {
+                writer.write_u8(12)?;
+                name_index.write_primitive(writer)?;
+                descriptor_index.write_primitive(writer)?;
+            }
+            Constant::Utf8(value) => {
+                writer.write_u8(1)?;
+                write_bytes_u16(writer, value.as_bytes())?;
+            }
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                writer.write_u8(15)?;
+                writer.write_u8(*reference_kind as u8)?;
+                reference_index.write_primitive(writer)?;
+            }
+            Constant::MethodType { descriptor_index } => {
+                writer.write_u8(16)?;
+                descriptor_index.write_primitive(writer)?;
+            }
+            Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                writer.write_u8(17)?;
+                writer.write_u16::<BigEndian>(*bootstrap_method_attr_index)?;
+                name_and_type_index.write_primitive(writer)?;
+            }
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                writer.write_u8(18)?;
+                writer.write_u16::<BigEndian>(*bootstrap_method_attr_index)?;
+                name_and_type_index.write_primitive(writer)?;
+            }
+            Constant::Module { name_index } => {
+                writer.write_u8(19)?;
+                name_index.write_primitive(writer)?;
+            }
+            Constant::Package { name_index } => {
+                writer.write_u8(20)?;
+                name_index.write_primitive(writer)?;
+            }
+            // second slot of long and double constants
+            Constant::InvalidConstant => {}
+        }
+    }
+    Ok(())
+}
+
+pub trait WriteClassFileEntry {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()>;
+}
+
+pub trait WriteClassFilePrimitive {
+    fn write_primitive<T: Write>(&self, writer: &mut T) -> JvmWriteResult<()>;
+}
+
+impl<U: WriteClassFilePrimitive> WriteClassFileEntry for U {
+    #[inline]
+    fn write<T: Write>(&self, writer: &mut T, _: &ConstantPool) -> JvmWriteResult<()> {
+        self.write_primitive(writer)
+    }
+}
+
+impl WriteClassFilePrimitive for u16 {
+    fn write_primitive<T: Write>(&self, writer: &mut T) -> JvmWriteResult<()> {
+        Ok(writer.write_u16::<BigEndian>(*self)?)
+    }
+}
+
+pub(crate) fn write_length_u16<T: Write>(writer: &mut T, length: usize) -> JvmWriteResult<()> {
+    let length = u16::try_from(length)
+        .map_err(|_| JvmWriteError::InvalidFormat(format!("too many entries: {}", length)))?;
+    Ok(writer.write_u16::<BigEndian>(length)?)
+}
+
+pub fn write_bytes_u16<T: Write>(writer: &mut T, bytes: &[u8]) -> JvmWriteResult<()> {
+    write_length_u16(writer, bytes.len())?;
+    Ok(writer.write_all(bytes)?)
+}
+
+pub fn write_bytes_u32<T: Write>(writer: &mut T, bytes: &[u8]) -> JvmWriteResult<()> {
+    let length = u32::try_from(bytes.len())
+        .map_err(|_| JvmWriteError::InvalidFormat(format!("too long: {}", bytes.len())))?;
+    writer.write_u32::<BigEndian>(length)?;
+    Ok(writer.write_all(bytes)?)
+}
+
+impl<U: WriteClassFileEntry> WriteClassFileEntry for Vec<U> {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        write_length_u16(writer, self.len())?;
+        for entry in self {
+            entry.write(writer, cpool)?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteClassFilePrimitive for ConstantIndex {
+    fn write_primitive<T: Write>(&self, writer: &mut T) -> JvmWriteResult<()> {
+        Ok(writer.write_u16::<BigEndian>(self.0)?)
+    }
+}
+
+/// Index 0 stands for an absent constant.
+impl WriteClassFilePrimitive for Option<ConstantIndex> {
+    fn write_primitive<T: Write>(&self, writer: &mut T) -> JvmWriteResult<()> {
+        Ok(writer.write_u16::<BigEndian>(self.map_or(0, |index| index.0))?)
+    }
+}
+
+write_flags!(
+    ClassAccessFlags,
+    InnerClassAccessFlags,
+    FieldAccessFlags,
+    MethodAccessFlags,
+    ParameterAccessFlags
+);
+
+impl WriteClassFileEntry for MethodParameter {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.access_flags, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Field {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.access_flags, cpool)?;
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.descriptor_index, cpool)?;
+        writer.write_entry(&self.attributes, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Method {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.access_flags, cpool)?;
+        writer.write_entry(&self.name_index, cpool)?;
+        writer.write_entry(&self.descriptor_index, cpool)?;
+        writer.write_entry(&self.attributes, cpool)
+    }
+}
+
+/// Entries of the `MethodParameters` attribute, which are counted by a u8.
+fn write_method_parameters<T: Write>(
+    writer: &mut T,
+    parameters: &[MethodParameter],
+    cpool: &ConstantPool,
+) -> JvmWriteResult<()> {
+    let parameters_count = u8::try_from(parameters.len()).map_err(|_| {
+        JvmWriteError::InvalidFormat(format!("too many parameters: {}", parameters.len()))
+    })?;
+    writer.write_u8(parameters_count)?;
+    for parameter in parameters {
+        writer.write_entry(parameter, cpool)?;
+    }
+    Ok(())
+}
+
+/// Index of the Utf8 constant with the name of an attribute. The index the
+/// attribute was parsed with is kept if it still names the attribute.
+fn attribute_name(
+    cpool: &ConstantPool,
+    name: &str,
+    parsed: Option<ConstantIndex>,
+) -> JvmWriteResult<ConstantIndex> {
+    match parsed {
+        Some(index) if cpool.resolve_utf8(index).ok() == Some(name) => Ok(index),
+        _ => cpool.find_utf8(name).ok_or_else(|| {
+            JvmWriteError::InvalidFormat(format!("missing constant for attribute name {}", name))
+        }),
+    }
+}
+
+impl WriteClassFileEntry for Attributes {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        write_length_u16(writer, self.len())?;
+        for (index, attribute) in self.iter().enumerate() {
+            write_attribute(writer, attribute, self.name_index(index), cpool)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_attribute<T: Write>(
+    writer: &mut T,
+    attribute: &Attribute,
+    parsed_name: Option<ConstantIndex>,
+    cpool: &ConstantPool,
+) -> JvmWriteResult<()> {
+    let name_index = match attribute {
+        Attribute::Unknown { name, .. } => *name,
+        _ => attribute_name(
+            cpool,
+            attribute.known_name().unwrap_or_default(),
+            parsed_name,
+        )?,
+    };
+
+    let mut info: Vec<u8> = vec![];
+    match attribute {
+        Attribute::Code(code) => info.write_entry(code, cpool)?,
+        Attribute::ConstantValue(constant_value) => info.write_entry(constant_value, cpool)?,
+        Attribute::SourceFile(sourcefile_index) => info.write_entry(sourcefile_index, cpool)?,
+        Attribute::Signature(signature_index) => info.write_entry(signature_index, cpool)?,
+        Attribute::StackMapTable(stack_map_table) => info.write_entry(stack_map_table, cpool)?,
+        Attribute::LineNumberTable(line_numbers) => info.write_entry(line_numbers, cpool)?,
+        Attribute::LocalVariableTable(local_variables) => {
+            info.write_entry(local_variables, cpool)?
+        }
+        Attribute::LocalVariableTypeTable(local_variable_types) => {
+            info.write_entry(local_variable_types, cpool)?
+        }
+        Attribute::RuntimeVisibleAnnotations(annotations) => {
+            info.write_entry(annotations, cpool)?
+        }
+        Attribute::RuntimeInvisibleAnnotations(annotations) => {
+            info.write_entry(annotations, cpool)?
+        }
+        Attribute::RuntimeVisibleParameterAnnotations(parameters) => {
+            write_parameter_annotations(&mut info, parameters, cpool)?
+        }
+        Attribute::RuntimeInvisibleParameterAnnotations(parameters) => {
+            write_parameter_annotations(&mut info, parameters, cpool)?
+        }
+        Attribute::AnnotationDefault(default_value) => info.write_entry(default_value, cpool)?,
+        Attribute::BootstrapMethods(bootstrap_methods) => {
+            info.write_entry(bootstrap_methods, cpool)?
+        }
+        Attribute::Module(module) => info.write_entry(module, cpool)?,
+        Attribute::ModulePackages(packages) => info.write_entry(packages, cpool)?,
+        Attribute::ModuleMainClass(main_class) => info.write_entry(main_class, cpool)?,
+        Attribute::InnerClasses(inner_classes) => info.write_entry(inner_classes, cpool)?,
+        Attribute::EnclosingMethod(enclosing_method) => {
+            info.write_entry(enclosing_method, cpool)?
+        }
+        Attribute::NestHost(host_class_index) => info.write_entry(host_class_index, cpool)?,
+        Attribute::NestMembers(classes) => info.write_entry(classes, cpool)?,
+        Attribute::Record(components) => info.write_entry(components, cpool)?,
+        Attribute::PermittedSubclasses(classes) => info.write_entry(classes, cpool)?,
+        Attribute::Exceptions(exceptions) => info.write_entry(exceptions, cpool)?,
+        Attribute::MethodParameters(parameters) => {
+            write_method_parameters(&mut info, parameters, cpool)?
+        }
+        Attribute::Deprecated | Attribute::Synthetic => {}
+        Attribute::RuntimeVisibleTypeAnnotations(annotations) => {
+            info.write_entry(annotations, cpool)?
+        }
+        Attribute::RuntimeInvisibleTypeAnnotations(annotations) => {
+            info.write_entry(annotations, cpool)?
+        }
+        Attribute::Unknown { value, .. } => info.extend_from_slice(value),
+    }
+
+    name_index.write_primitive(writer)?;
+    write_bytes_u32(writer, &info)
+}
@@ -0,0 +1,56 @@
+use std::io::Write;
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::error::JvmWriteResult;
+use crate::model::constants::ConstantPool;
+use crate::model::module::{
+    Exports, Module, ModuleFlags, Opens, PackageFlags, Provides, Requires, RequiresFlags,
+};
+use crate::write::{WriteClassFileEntry, WriteClassFileExt, WriteClassFilePrimitive};
+
+write_flags!(ModuleFlags, RequiresFlags, PackageFlags);
+
+impl WriteClassFileEntry for Module {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.module_name_index, cpool)?;
+        writer.write_entry(&self.module_flags, cpool)?;
+        writer.write_entry(&self.module_version_index, cpool)?;
+        writer.write_entry(&self.requires, cpool)?;
+        writer.write_entry(&self.exports, cpool)?;
+        writer.write_entry(&self.opens, cpool)?;
+        writer.write_entry(&self.uses, cpool)?;
+        writer.write_entry(&self.provides, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Requires {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.requires_index, cpool)?;
+        writer.write_entry(&self.requires_flags, cpool)?;
+        writer.write_entry(&self.requires_version_index, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Exports {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.exports_index, cpool)?;
+        writer.write_entry(&self.exports_flags, cpool)?;
+        writer.write_entry(&self.exports_to, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Opens {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.opens_index, cpool)?;
+        writer.write_entry(&self.opens_flags, cpool)?;
+        writer.write_entry(&self.opens_to, cpool)
+    }
+}
+
+impl WriteClassFileEntry for Provides {
+    fn write<T: Write>(&self, writer: &mut T, cpool: &ConstantPool) -> JvmWriteResult<()> {
+        writer.write_entry(&self.provides_index, cpool)?;
+        writer.write_entry(&self.provides_with, cpool)
+    }
+}
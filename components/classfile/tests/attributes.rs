@@ -0,0 +1,863 @@
+use classfile::descriptor::parse_field_descriptor;
+use classfile::model::annotations::{
+    ConstKind, ElementValue, LocalVarTargetEntry, TypeAnnotationTarget, TypePathEntry, TypePathKind,
+};
+use classfile::model::attributes::{
+    Code, StackMapFrame, StackMapFrameKind, StackMapTable, VerificationTypeInfo,
+};
+use classfile::model::constants::{ConstantIndex, ConstantPool, Loadable};
+use classfile::model::module::{
+    ModuleFlags, ModulePackage, ModuleProvides, ModuleRequires, PackageFlags, RequiresFlags,
+};
+use classfile::model::{
+    Attribute, ClassFile, FieldAccessFlags, Method, MethodAccessFlags, ParameterAccessFlags,
+    ReferenceKind,
+};
+use classfile::parse::parse_class_file;
+use classfile::signature::{parse_class_signature, parse_field_signature, parse_method_signature};
+use classfile::write::WriteClassFileEntry;
+use rustjvm_opcode::Opcode;
+use std::fs::File;
+use std::path::PathBuf;
+
+fn load_class(resource: &str) -> ClassFile {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    path.push(resource);
+    parse_class_file(File::open(path).unwrap()).unwrap()
+}
+
+fn method_code<'a>(class_file: &'a ClassFile, name: &str, descriptor: &str) -> &'a Code {
+    let cpool = class_file.constant_pool();
+    let method = class_file
+        .methods()
+        .iter()
+        .find(|method| {
+            cpool.resolve_utf8(method.name_index).unwrap() == name
+                && cpool.resolve_utf8(method.descriptor_index).unwrap() == descriptor
+        })
+        .unwrap();
+    method
+        .attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            Attribute::Code(code) => Some(code),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn frame_kinds(code: &Code) -> Vec<(u16, &StackMapFrameKind)> {
+    code.stack_map_table()
+        .unwrap()
+        .entries
+        .iter()
+        .map(|frame| (frame.offset, &frame.kind))
+        .collect()
+}
+
+#[test]
+fn stack_map_uninitialized_this() {
+    let class_file = load_class("StackMaps.class");
+    let code = method_code(&class_file, "<init>", "(Z)V");
+    let frames = frame_kinds(code);
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(
+        frames[0],
+        (
+            10,
+            &StackMapFrameKind::SameLocals1StackItem(VerificationTypeInfo::UninitializedThis)
+        )
+    );
+    match frames[1] {
+        (11, StackMapFrameKind::Full { locals, stack }) => {
+            assert_eq!(
+                locals,
+                &[
+                    VerificationTypeInfo::UninitializedThis,
+                    VerificationTypeInfo::Integer
+                ]
+            );
+            assert_eq!(stack.len(), 2);
+            assert_eq!(
+                stack[1]
+                    .class_name(code, class_file.constant_pool())
+                    .unwrap(),
+                Some("java/lang/Object")
+            );
+        }
+        frame => panic!("unexpected frame: {:?}", frame),
+    }
+}
+
+#[test]
+fn stack_map_uninitialized() {
+    let class_file = load_class("StackMaps.class");
+    let code = method_code(
+        &class_file,
+        "create",
+        "(Z)Lde/richardliebscher/rustjvm/StackMaps;",
+    );
+    let table = code.stack_map_table().unwrap();
+
+    let frame = table.frame_at(13).unwrap();
+    match &frame.kind {
+        StackMapFrameKind::Full { locals, stack } => {
+            assert_eq!(locals, &[VerificationTypeInfo::Integer]);
+            assert_eq!(
+                stack,
+                &[
+                    VerificationTypeInfo::Uninitialized(0),
+                    VerificationTypeInfo::Uninitialized(0)
+                ]
+            );
+            assert_eq!(
+                stack[0]
+                    .class_name(code, class_file.constant_pool())
+                    .unwrap(),
+                Some("de/richardliebscher/rustjvm/StackMaps")
+            );
+        }
+        kind => panic!("unexpected frame: {:?}", kind),
+    }
+    assert!(table.frame_at(15).is_some());
+    assert!(table.frame_at(14).is_none());
+}
+
+#[test]
+fn stack_map_append_chop() {
+    let class_file = load_class("StackMaps.class");
+    let code = method_code(&class_file, "locals", "(ID)J");
+    let frames = frame_kinds(code);
+
+    assert_eq!(
+        frames[0].1,
+        &StackMapFrameKind::Append(vec![
+            VerificationTypeInfo::Long,
+            VerificationTypeInfo::Integer
+        ])
+    );
+    assert_eq!(frames[1].1, &StackMapFrameKind::Chop(1));
+    assert_eq!(frames[2].1, &StackMapFrameKind::Same);
+    assert!(frames[0].0 < frames[1].0 && frames[1].0 < frames[2].0);
+}
+
+#[test]
+fn stack_map_extended() {
+    let class_file = load_class("StackMaps.class");
+
+    let frames = frame_kinds(method_code(&class_file, "sameExtended", "(II)I"));
+    assert_eq!(frames, vec![(69, &StackMapFrameKind::SameExtended)]);
+
+    let frames = frame_kinds(method_code(&class_file, "handlerExtended", "(II)I"));
+    assert_eq!(frames[0].0, 67);
+    assert!(matches!(
+        frames[0].1,
+        StackMapFrameKind::SameLocals1StackItemExtended(VerificationTypeInfo::Object(_))
+    ));
+}
+
+fn write_stack_map_table(entries: Vec<StackMapFrame>) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    StackMapTable { entries }
+        .write(&mut bytes, &ConstantPool::new(vec![]))
+        .ok()
+        .map(|_| bytes)
+}
+
+#[test]
+fn stack_map_write_deltas() {
+    let bytes = write_stack_map_table(vec![
+        StackMapFrame {
+            offset: 3,
+            kind: StackMapFrameKind::Same,
+        },
+        StackMapFrame {
+            offset: 100,
+            kind: StackMapFrameKind::Same,
+        },
+        StackMapFrame {
+            offset: 101,
+            kind: StackMapFrameKind::Append(vec![VerificationTypeInfo::Null]),
+        },
+    ]);
+    assert_eq!(bytes, Some(vec![0, 3, 3, 251, 0, 96, 252, 0, 0, 5]));
+
+    let unordered = write_stack_map_table(vec![
+        StackMapFrame {
+            offset: 3,
+            kind: StackMapFrameKind::Same,
+        },
+        StackMapFrame {
+            offset: 3,
+            kind: StackMapFrameKind::Same,
+        },
+    ]);
+    assert_eq!(unordered, None);
+}
+
+#[test]
+fn line_numbers() {
+    let class_file = load_class("DebugInfo.class");
+    let code = method_code(&class_file, "sum", "(I)I");
+
+    assert_eq!(code.line_for_pc(0), Some(8));
+    assert_eq!(code.line_for_pc(3), Some(9));
+    assert_eq!(code.line_for_pc(12), Some(10));
+    assert_eq!(code.line_for_pc(16), Some(9));
+    assert_eq!(code.line_for_pc(20), Some(12));
+}
+
+#[test]
+fn local_variables() {
+    let class_file = load_class("DebugInfo.class");
+    let cpool = class_file.constant_pool();
+    let code = method_code(&class_file, "sum", "(I)I");
+    let locals_at = |pc| {
+        let mut locals: Vec<(u16, &str)> = code
+            .locals_at(pc)
+            .map(|local| (local.index, cpool.resolve_utf8(local.name_index).unwrap()))
+            .collect();
+        locals.sort();
+        locals
+    };
+
+    assert_eq!(locals_at(0), vec![(0, "count")]);
+    assert_eq!(locals_at(4), vec![(0, "count"), (1, "sum"), (2, "i")]);
+    assert_eq!(locals_at(19), vec![(0, "count"), (1, "sum")]);
+}
+
+#[test]
+fn local_variable_types() {
+    let class_file = load_class("DebugInfo.class");
+    let cpool = class_file.constant_pool();
+    let code = method_code(&class_file, "names", "(Ljava/lang/String;)Ljava/util/List;");
+
+    let local = code.locals_at(8).find(|local| local.index == 1).unwrap();
+    assert_eq!(cpool.resolve_utf8(local.name_index).unwrap(), "names");
+    assert_eq!(
+        cpool.resolve_utf8(local.descriptor_index).unwrap(),
+        "Ljava/util/List;"
+    );
+
+    let local_types: Vec<&str> = code
+        .local_types_at(8)
+        .map(|local_type| cpool.resolve_utf8(local_type.signature_index).unwrap())
+        .collect();
+    assert_eq!(local_types, vec!["Ljava/util/List<Ljava/lang/String;>;"]);
+    assert_eq!(code.local_types_at(7).count(), 0);
+}
+
+fn signature<'a>(class_file: &'a ClassFile, attributes: &[Attribute]) -> &'a str {
+    let index = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            Attribute::Signature(index) => Some(*index),
+            _ => None,
+        })
+        .unwrap();
+    class_file.constant_pool().resolve_utf8(index).unwrap()
+}
+
+#[test]
+fn signatures() {
+    let class_file = load_class("Generics.class");
+
+    let class_signature =
+        parse_class_signature(signature(&class_file, class_file.attributes())).unwrap();
+    assert_eq!(
+        class_signature.to_string(),
+        "<T extends java.lang.Comparable<? super T>> extends java.util.ArrayList<T> \
+         implements java.io.Serializable"
+    );
+
+    let field_signature =
+        parse_field_signature(signature(&class_file, &class_file.fields()[0].attributes)).unwrap();
+    assert_eq!(
+        field_signature.to_string(),
+        "java.util.Map<java.lang.String, java.util.List<? extends java.lang.Number>>"
+    );
+
+    let method = class_file
+        .methods()
+        .iter()
+        .find(|method| {
+            class_file
+                .constant_pool()
+                .resolve_utf8(method.name_index)
+                .unwrap()
+                == "first"
+        })
+        .unwrap();
+    let method_signature =
+        parse_method_signature(signature(&class_file, &method.attributes)).unwrap();
+    assert_eq!(
+        method_signature.to_string(),
+        "<E extends java.lang.Exception> T (java.util.List<? extends T>, \
+         java.util.Map$Entry<java.lang.String, int[]>) throws E"
+    );
+}
+
+const INFO: &str = "Lde/richardliebscher/rustjvm/Annotated$Info;";
+const MARKER: &str = "Lde/richardliebscher/rustjvm/Annotated$Marker;";
+
+#[test]
+fn class_annotations() {
+    let class_file = load_class("Annotated.class");
+    let cpool = class_file.constant_pool();
+
+    assert!(class_file.has_annotation("Ljava/lang/Deprecated;"));
+    assert!(!class_file.has_annotation(MARKER));
+    assert_eq!(class_file.annotations().count(), 2);
+
+    let info = class_file.find_annotation(INFO).unwrap();
+    assert_eq!(info.elements.len(), 8);
+    let utf8 = |value: &ElementValue| match value {
+        ElementValue::Const(ConstKind::String, index) => cpool.resolve_utf8(*index).unwrap(),
+        ElementValue::Class(index) => cpool.resolve_utf8(*index).unwrap(),
+        value => panic!("unexpected value: {:?}", value),
+    };
+
+    assert_eq!(utf8(info.element(cpool, "name").unwrap()), "class");
+    assert_eq!(
+        utf8(info.element(cpool, "type").unwrap()),
+        "Ljava/lang/String;"
+    );
+    assert!(matches!(
+        info.element(cpool, "count"),
+        Some(ElementValue::Const(ConstKind::Int, _))
+    ));
+    assert!(matches!(
+        info.element(cpool, "ratio"),
+        Some(ElementValue::Const(ConstKind::Double, _))
+    ));
+    match info.element(cpool, "element").unwrap() {
+        ElementValue::Enum {
+            type_name_index,
+            const_name_index,
+        } => {
+            assert_eq!(
+                cpool.resolve_utf8(*type_name_index).unwrap(),
+                "Ljava/lang/annotation/ElementType;"
+            );
+            assert_eq!(cpool.resolve_utf8(*const_name_index).unwrap(), "TYPE");
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+    match info.element(cpool, "tags").unwrap() {
+        ElementValue::Array(values) => {
+            let tags: Vec<&str> = values.iter().map(utf8).collect();
+            assert_eq!(tags, vec!["a", "b"]);
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+    match info.element(cpool, "nested").unwrap() {
+        ElementValue::Annotation(nested) => {
+            assert_eq!(cpool.resolve_utf8(nested.type_index).unwrap(), MARKER);
+            assert!(nested.elements.is_empty());
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+    assert_eq!(info.element(cpool, "flags"), None);
+}
+
+#[test]
+fn member_annotations() {
+    let class_file = load_class("Annotated.class");
+    let cpool = class_file.constant_pool();
+
+    let field = &class_file.fields()[0];
+    assert!(field.has_annotation(cpool, MARKER));
+    assert!(!field.has_annotation(cpool, INFO));
+
+    let method = class_file
+        .methods()
+        .iter()
+        .find(|method| cpool.resolve_utf8(method.name_index).unwrap() == "method")
+        .unwrap();
+    assert!(method.has_annotation(cpool, INFO));
+    let parameter_types = |parameter| {
+        method
+            .parameter_annotations(parameter)
+            .map(|annotation| cpool.resolve_utf8(annotation.type_index).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(parameter_types(0), vec![MARKER]);
+    assert_eq!(parameter_types(1), Vec::<&str>::new());
+    assert_eq!(parameter_types(2), vec![INFO, MARKER]);
+    assert_eq!(parameter_types(3), Vec::<&str>::new());
+}
+
+#[test]
+fn annotation_default() {
+    let class_file = load_class("Annotated$Info.class");
+    let cpool = class_file.constant_pool();
+    let default = |name: &str| {
+        class_file
+            .methods()
+            .iter()
+            .find(|method| cpool.resolve_utf8(method.name_index).unwrap() == name)
+            .unwrap()
+            .annotation_default()
+            .unwrap()
+    };
+
+    match default("name") {
+        ElementValue::Const(ConstKind::String, index) => {
+            assert_eq!(cpool.resolve_utf8(*index).unwrap(), "unnamed")
+        }
+        value => panic!("unexpected value: {:?}", value),
+    }
+    match default("type") {
+        ElementValue::Class(index) => assert_eq!(cpool.resolve_utf8(*index).unwrap(), "V"),
+        value => panic!("unexpected value: {:?}", value),
+    }
+    assert_eq!(default("tags"), &ElementValue::Array(vec![]));
+    assert!(matches!(default("nested"), ElementValue::Annotation(_)));
+}
+
+const NULLABLE: &str = "Lde/richardliebscher/rustjvm/Nullable;";
+
+#[test]
+fn type_annotations() {
+    let class_file = load_class("TypeAnnotated.class");
+    let cpool = class_file.constant_pool();
+
+    let targets: Vec<&TypeAnnotationTarget> = class_file
+        .type_annotations()
+        .map(|annotation| &annotation.target)
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            &TypeAnnotationTarget::Supertype(0),
+            &TypeAnnotationTarget::ClassTypeParameter(0)
+        ]
+    );
+
+    let index = &class_file.fields()[0];
+    let paths: Vec<&[TypePathEntry]> = index
+        .type_annotations()
+        .map(|annotation| {
+            assert_eq!(annotation.target, TypeAnnotationTarget::Field);
+            assert_eq!(
+                cpool
+                    .resolve_utf8(annotation.annotation.type_index)
+                    .unwrap(),
+                NULLABLE
+            );
+            &annotation.type_path.path[..]
+        })
+        .collect();
+    let type_argument = |type_argument_index| TypePathEntry {
+        kind: TypePathKind::TypeArgument,
+        type_argument_index,
+    };
+    let wildcard = TypePathEntry {
+        kind: TypePathKind::WildcardBound,
+        type_argument_index: 0,
+    };
+    assert_eq!(
+        paths,
+        vec![
+            &[type_argument(1)][..],
+            &[type_argument(1), type_argument(0), wildcard][..]
+        ]
+    );
+
+    let method = class_file
+        .methods()
+        .iter()
+        .find(|method| cpool.resolve_utf8(method.name_index).unwrap() == "method")
+        .unwrap();
+    let targets: Vec<&TypeAnnotationTarget> = method
+        .type_annotations()
+        .map(|annotation| &annotation.target)
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            &TypeAnnotationTarget::Throws(0),
+            &TypeAnnotationTarget::MethodReturn,
+            &TypeAnnotationTarget::FormalParameter(0)
+        ]
+    );
+}
+
+#[test]
+fn code_type_annotations() {
+    let class_file = load_class("TypeAnnotated.class");
+    let code = method_code(&class_file, "body", "(Ljava/lang/Object;)I");
+
+    let targets: Vec<&TypeAnnotationTarget> = code
+        .type_annotations()
+        .map(|annotation| &annotation.target)
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            &TypeAnnotationTarget::Cast {
+                offset: 1,
+                type_argument_index: 0
+            },
+            &TypeAnnotationTarget::Instanceof(6),
+            &TypeAnnotationTarget::New(14),
+            &TypeAnnotationTarget::LocalVariable(vec![LocalVarTargetEntry {
+                start_pc: 5,
+                length: 27,
+                index: 2
+            }])
+        ]
+    );
+}
+
+fn invokedynamic_index(code: &Code) -> ConstantIndex {
+    code.code
+        .iter()
+        .find_map(|instruction| match instruction.opcode {
+            Opcode::Invokedynamic(index) => Some(ConstantIndex(index)),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn bootstrap_methods() {
+    let class_file = load_class("Indy.class");
+    assert_eq!(class_file.bootstrap_methods().len(), 2);
+
+    let code = method_code(
+        &class_file,
+        "greeting",
+        "(Ljava/lang/String;)Ljava/util/function/Supplier;",
+    );
+    let call_site = class_file
+        .resolve_call_site(invokedynamic_index(code))
+        .unwrap();
+    assert_eq!(
+        call_site.bootstrap_method.reference_kind,
+        ReferenceKind::InvokeStatic
+    );
+    assert_eq!(
+        call_site.bootstrap_method.reference.class,
+        "java/lang/invoke/LambdaMetafactory"
+    );
+    assert_eq!(call_site.bootstrap_method.reference.name, "metafactory");
+    assert_eq!(call_site.name, "get");
+    assert_eq!(
+        call_site.descriptor.params,
+        vec![parse_field_descriptor("Ljava/lang/String;").unwrap()]
+    );
+    assert_eq!(
+        call_site.descriptor.rty,
+        parse_field_descriptor("Ljava/util/function/Supplier;")
+    );
+    assert_eq!(call_site.static_arguments.len(), 3);
+    assert_eq!(
+        call_site.static_arguments[0],
+        Loadable::MethodType("()Ljava/lang/Object;")
+    );
+    match &call_site.static_arguments[1] {
+        Loadable::MethodHandle(handle) => {
+            assert_eq!(handle.reference.class, "de/richardliebscher/rustjvm/Indy");
+            assert_eq!(handle.reference.name, "lambda$greeting$0");
+        }
+        other => panic!("expected method handle, got {:?}", other),
+    }
+
+    let code = method_code(
+        &class_file,
+        "lambda$greeting$0",
+        "(Ljava/lang/String;)Ljava/lang/String;",
+    );
+    let call_site = class_file
+        .resolve_call_site(invokedynamic_index(code))
+        .unwrap();
+    assert_eq!(
+        call_site.bootstrap_method.reference.name,
+        "makeConcatWithConstants"
+    );
+    assert_eq!(
+        call_site.static_arguments,
+        vec![Loadable::String("Hello \u{1}!")]
+    );
+}
+
+#[test]
+fn resolve_call_site_rejects_other_constants() {
+    let class_file = load_class("Indy.class");
+    assert!(class_file
+        .resolve_call_site(class_file.this_class())
+        .is_err());
+}
+
+#[test]
+fn module_constants() {
+    let class_file = load_class("module-info.class");
+    let cpool = class_file.constant_pool();
+    assert_eq!(
+        cpool.resolve_module_name(ConstantIndex(5)).unwrap(),
+        "de.richardliebscher.rustjvm"
+    );
+    assert_eq!(
+        cpool.resolve_package_name(ConstantIndex(10)).unwrap(),
+        "de/richardliebscher/rustjvm"
+    );
+    assert!(cpool.resolve_module_name(ConstantIndex(10)).is_err());
+}
+
+#[test]
+fn module_descriptor() {
+    let class_file = load_class("module-info.class");
+    let module = class_file.module_descriptor().unwrap().unwrap();
+    assert_eq!(module.name, "de.richardliebscher.rustjvm");
+    assert_eq!(module.flags, ModuleFlags::empty());
+    assert_eq!(module.version, Some("1.2"));
+    assert_eq!(
+        module.requires,
+        vec![
+            ModuleRequires {
+                module: "java.base",
+                flags: RequiresFlags::MANDATED,
+                version: None,
+            },
+            ModuleRequires {
+                module: "java.logging",
+                flags: RequiresFlags::TRANSITIVE,
+                version: None,
+            },
+        ]
+    );
+    assert_eq!(
+        module.exports,
+        vec![ModulePackage {
+            package: "de/richardliebscher/rustjvm",
+            flags: PackageFlags::empty(),
+            to: vec![],
+        }]
+    );
+    assert_eq!(
+        module.opens,
+        vec![ModulePackage {
+            package: "de/richardliebscher/rustjvm",
+            flags: PackageFlags::empty(),
+            to: vec!["java.base"],
+        }]
+    );
+    assert_eq!(module.uses, vec!["java/util/function/Supplier"]);
+    assert_eq!(
+        module.provides,
+        vec![ModuleProvides {
+            service: "java/util/function/Supplier",
+            with: vec!["de/richardliebscher/rustjvm/GreetingProvider"],
+        }]
+    );
+    assert_eq!(module.packages, vec!["de/richardliebscher/rustjvm"]);
+    assert_eq!(
+        module.main_class,
+        Some("de/richardliebscher/rustjvm/JavaHelloWorld")
+    );
+
+    assert_eq!(load_class("Indy.class").module_descriptor().unwrap(), None);
+}
+
+fn class_names<'a>(cpool: &'a ConstantPool, classes: &[ConstantIndex]) -> Vec<&'a str> {
+    classes
+        .iter()
+        .map(|class| cpool.resolve_class_name(*class).unwrap())
+        .collect()
+}
+
+#[test]
+fn nest_members() {
+    let class_file = load_class("Shapes.class");
+    let cpool = class_file.constant_pool();
+    assert_eq!(class_file.nest_host(), None);
+    assert_eq!(
+        class_names(cpool, class_file.nest_members()),
+        vec![
+            "de/richardliebscher/rustjvm/Shapes$Polygon",
+            "de/richardliebscher/rustjvm/Shapes$Circle",
+            "de/richardliebscher/rustjvm/Shapes$Shape",
+            "de/richardliebscher/rustjvm/Shapes$1",
+        ]
+    );
+    assert_eq!(class_file.outer_class().unwrap(), None);
+
+    let anonymous = &class_file.inner_classes()[0];
+    assert_eq!(
+        cpool
+            .resolve_class_name(anonymous.inner_class_info_index)
+            .unwrap(),
+        "de/richardliebscher/rustjvm/Shapes$1"
+    );
+    assert_eq!(anonymous.outer_class_info_index, None);
+    assert_eq!(anonymous.inner_name_index, None);
+
+    let circle = load_class("Shapes$Circle.class");
+    let cpool = circle.constant_pool();
+    let host = circle.nest_host().unwrap();
+    assert_eq!(
+        cpool.resolve_class_name(host).unwrap(),
+        "de/richardliebscher/rustjvm/Shapes"
+    );
+    let outer = circle.outer_class().unwrap().unwrap();
+    assert_eq!(
+        cpool.resolve_class_name(outer).unwrap(),
+        "de/richardliebscher/rustjvm/Shapes"
+    );
+}
+
+#[test]
+fn enclosing_method() {
+    let class_file = load_class("Shapes$1.class");
+    let cpool = class_file.constant_pool();
+    let enclosing_method = class_file.enclosing_method().unwrap();
+    assert_eq!(
+        cpool
+            .resolve_class_name(enclosing_method.class_index)
+            .unwrap(),
+        "de/richardliebscher/rustjvm/Shapes"
+    );
+    let method = cpool
+        .resolve_name_and_type(enclosing_method.method_index.unwrap())
+        .unwrap();
+    assert_eq!(method.name, "printer");
+    assert_eq!(
+        method.descriptor,
+        "(Lde/richardliebscher/rustjvm/Shapes$Shape;)Ljava/lang/Runnable;"
+    );
+    assert_eq!(
+        class_file.outer_class().unwrap(),
+        Some(enclosing_method.class_index)
+    );
+}
+
+#[test]
+fn records() {
+    let class_file = load_class("Shapes$Polygon.class");
+    let cpool = class_file.constant_pool();
+    assert!(class_file.is_record());
+    match class_file.record_components().unwrap() {
+        [component] => {
+            assert_eq!(cpool.resolve_utf8(component.name_index).unwrap(), "points");
+            assert_eq!(
+                cpool.resolve_utf8(component.descriptor_index).unwrap(),
+                "Ljava/util/List;"
+            );
+            assert_eq!(
+                signature(&class_file, &component.attributes),
+                "Ljava/util/List<Ljava/lang/String;>;"
+            );
+        }
+        components => panic!("unexpected components: {:?}", components),
+    }
+
+    assert!(!load_class("Shapes.class").is_record());
+}
+
+#[test]
+fn permitted_subclasses() {
+    let class_file = load_class("Shapes$Shape.class");
+    let cpool = class_file.constant_pool();
+    assert!(class_file.is_sealed());
+    assert_eq!(
+        class_names(cpool, class_file.permitted_subclasses()),
+        vec![
+            "de/richardliebscher/rustjvm/Shapes$Circle",
+            "de/richardliebscher/rustjvm/Shapes$Polygon",
+        ]
+    );
+
+    let circle = load_class("Shapes$Circle.class");
+    assert!(!circle.is_sealed());
+    assert!(circle.permitted_subclasses().is_empty());
+}
+
+fn find_method<'a>(class_file: &'a ClassFile, name: &str, descriptor: &str) -> &'a Method {
+    let cpool = class_file.constant_pool();
+    class_file
+        .methods()
+        .iter()
+        .find(|method| {
+            cpool.resolve_utf8(method.name_index).unwrap() == name
+                && cpool.resolve_utf8(method.descriptor_index).unwrap() == descriptor
+        })
+        .unwrap()
+}
+
+#[test]
+fn member_flags() {
+    let class_file = load_class("MethodInfo.class");
+    let fields = class_file.fields();
+    assert_eq!(
+        fields[0].access_flags,
+        FieldAccessFlags::PROTECTED | FieldAccessFlags::VOLATILE
+    );
+    assert_eq!(
+        fields[1].access_flags,
+        FieldAccessFlags::PRIVATE | FieldAccessFlags::TRANSIENT
+    );
+
+    let read = find_method(&class_file, "read", "(Ljava/lang/String;[I)V");
+    assert_eq!(
+        read.access_flags,
+        MethodAccessFlags::PUBLIC | MethodAccessFlags::SYNCHRONIZED | MethodAccessFlags::VARARGS
+    );
+    assert!(!read.is_synthetic());
+
+    let bridge = find_method(&class_file, "call", "()Ljava/lang/Object;");
+    assert!(bridge.access_flags.contains(MethodAccessFlags::BRIDGE));
+    assert!(bridge.is_synthetic());
+}
+
+#[test]
+fn exceptions() {
+    let class_file = load_class("MethodInfo.class");
+    let cpool = class_file.constant_pool();
+    let read = find_method(&class_file, "read", "(Ljava/lang/String;[I)V");
+    assert_eq!(
+        class_names(cpool, read.exceptions()),
+        vec!["java/io/IOException", "java/lang/InterruptedException"]
+    );
+
+    let call = find_method(&class_file, "call", "()Ljava/lang/String;");
+    assert!(call.exceptions().is_empty());
+}
+
+#[test]
+fn method_parameters() {
+    let class_file = load_class("MethodInfo.class");
+    let cpool = class_file.constant_pool();
+    let read = find_method(&class_file, "read", "(Ljava/lang/String;[I)V");
+    let parameters: Vec<_> = read
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            (
+                cpool.resolve_utf8(parameter.name_index.unwrap()).unwrap(),
+                parameter.access_flags,
+            )
+        })
+        .collect();
+    assert_eq!(
+        parameters,
+        vec![
+            ("path", ParameterAccessFlags::FINAL),
+            ("offsets", ParameterAccessFlags::empty()),
+        ]
+    );
+
+    let call = find_method(&class_file, "call", "()Ljava/lang/String;");
+    assert!(call.parameters().is_empty());
+}
+
+#[test]
+fn deprecated() {
+    let class_file = load_class("MethodInfo.class");
+    assert!(class_file.fields()[2].is_deprecated());
+    assert!(!class_file.fields()[0].is_deprecated());
+    assert!(find_method(&class_file, "read", "(Ljava/lang/String;[I)V").is_deprecated());
+    assert!(!find_method(&class_file, "call", "()Ljava/lang/String;").is_deprecated());
+}
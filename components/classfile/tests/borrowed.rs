@@ -0,0 +1,229 @@
+use classfile::borrowed::{parse_class_file_ref, parse_class_file_ref_with};
+use classfile::build::ClassBuilder;
+use classfile::error::JvmParseError;
+use classfile::model::{ClassAccessFlags, MethodAccessFlags};
+use classfile::parse::ParseLimits;
+use classfile::write::write_class_file;
+use rustjvm_opcode::disasm;
+use rustjvm_opcode::{Assembler, Opcode};
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::PathBuf;
+
+fn class_files() -> Vec<(PathBuf, Vec<u8>)> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    fs::read_dir(path)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let mut bytes = vec![];
+            File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+            (path, bytes)
+        })
+        .collect()
+}
+
+#[test]
+fn matches_owned_parse() {
+    for (path, bytes) in class_files() {
+        let class_ref = parse_class_file_ref(&bytes).unwrap();
+        let class_file = class_ref.to_class_file().unwrap();
+        let cpool_ref = class_ref.constant_pool();
+        let cpool = class_file.constant_pool();
+        let name = path.display();
+
+        assert_eq!(class_ref.bytes().len(), bytes.len(), "{}", name);
+        assert_eq!(class_ref.major_version(), class_file.major_version());
+        assert_eq!(class_ref.minor_version(), class_file.minor_version());
+        assert_eq!(class_ref.access_flags(), class_file.access_flags());
+        assert_eq!(
+            class_ref.class_name().unwrap(),
+            class_file.view().class_name().unwrap()
+        );
+        assert_eq!(class_ref.super_class(), class_file.super_class());
+        assert_eq!(
+            class_ref.interfaces().collect::<Vec<_>>(),
+            class_file.interfaces()
+        );
+        assert_eq!(
+            class_ref.attributes().len(),
+            class_file.attributes().len(),
+            "{}",
+            name
+        );
+
+        assert_eq!(class_ref.fields().len(), class_file.fields().len());
+        for (field_ref, field) in class_ref.fields().zip(class_file.fields()) {
+            assert_eq!(field_ref.access_flags, field.access_flags);
+            assert_eq!(
+                cpool_ref.resolve_utf8(field_ref.name_index).unwrap(),
+                cpool.resolve_utf8(field.name_index).unwrap()
+            );
+            assert_eq!(field_ref.attributes.len(), field.attributes.len());
+        }
+
+        let methods = class_file.view().methods().unwrap();
+        assert_eq!(class_ref.methods().len(), methods.len());
+        for (method_ref, method) in class_ref.methods().zip(&methods) {
+            assert_eq!(method_ref.access_flags, method.access_flags);
+            assert_eq!(
+                cpool_ref.resolve_utf8(method_ref.name_index).unwrap(),
+                method.name
+            );
+            assert_eq!(
+                cpool_ref.resolve_utf8(method_ref.descriptor_index).unwrap(),
+                cpool.resolve_utf8(method.method.descriptor_index).unwrap()
+            );
+            assert_eq!(method_ref.attributes.len(), method.method.attributes.len());
+
+            let code_ref = method_ref.code(cpool_ref).unwrap();
+            assert_eq!(code_ref.is_some(), method.code.is_some());
+            if let (Some(code_ref), Some(code)) = (code_ref, method.code) {
+                assert_eq!(code_ref.max_stack, code.max_stack);
+                assert_eq!(code_ref.max_locals, code.max_locals);
+                assert_eq!(disasm(code_ref.code).unwrap(), code.code);
+                let handlers: Vec<_> = code_ref
+                    .exception_table()
+                    .map(|entry| (entry.start_pc, entry.handler_pc, entry.catch_type))
+                    .collect();
+                let expected: Vec<_> = code
+                    .exception_table
+                    .iter()
+                    .map(|entry| (entry.start_pc, entry.handler_pc, entry.catch_type))
+                    .collect();
+                assert_eq!(handlers, expected);
+                assert_eq!(code_ref.attributes.len(), code.attributes.len());
+            }
+        }
+
+        let mut written = vec![];
+        write_class_file(&class_file, &mut written).unwrap();
+        assert_eq!(written, bytes, "{}", name);
+    }
+}
+
+#[test]
+fn borrows_contents() {
+    for (_, bytes) in class_files() {
+        let class_ref = parse_class_file_ref(&bytes).unwrap();
+        let range = bytes.as_ptr_range();
+        match class_ref.class_name().unwrap() {
+            Cow::Borrowed(name) => assert!(range.contains(&name.as_ptr())),
+            Cow::Owned(name) => panic!("copied {}", name),
+        }
+        for method in class_ref.methods() {
+            for attribute in method.attributes {
+                assert!(range.contains(&attribute.info.as_ptr()));
+                assert_eq!(
+                    &bytes[attribute.offset()..attribute.offset() + attribute.info.len()],
+                    attribute.info
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn truncated() {
+    for (_, bytes) in class_files() {
+        for length in 0..bytes.len() {
+            let error = parse_class_file_ref(&bytes[..length]).err().unwrap();
+            assert!(matches!(error.kind(), JvmParseError::Io(_)));
+            assert!(error.location().unwrap().offset() <= length as u64);
+        }
+    }
+}
+
+#[test]
+fn invalid_layout() {
+    let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34, 0x00, 0x00];
+    let error = parse_class_file_ref(&bytes).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::InvalidFormat(_)));
+    assert_eq!(error.location().unwrap().offset(), 8);
+
+    bytes[9] = 2;
+    bytes.push(0x42);
+    let error = parse_class_file_ref(&bytes).err().unwrap();
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), 10);
+    assert_eq!(location.path(), "constant_pool[1]");
+}
+
+#[test]
+fn limits() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let mut method = class.method(MethodAccessFlags::STATIC, "run", "()V");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Iconst0);
+    asm.push(Opcode::Pop);
+    asm.push(Opcode::Return);
+    method.code(1, 0, asm.finish().unwrap());
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+
+    let limits = ParseLimits {
+        max_constants: 2,
+        ..ParseLimits::default()
+    };
+    let error = parse_class_file_ref_with(&bytes, &limits).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::LimitExceeded { .. }));
+    assert_eq!(error.location().unwrap().offset(), 8);
+
+    let limits = ParseLimits {
+        max_code_length: 2,
+        ..ParseLimits::default()
+    };
+    let class_ref = parse_class_file_ref_with(&bytes, &limits).unwrap();
+    let method_ref = class_ref.methods().next().unwrap();
+    let error = method_ref.code(class_ref.constant_pool()).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::LimitExceeded { .. }));
+    let error = class_ref.to_class_file().err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::LimitExceeded { .. }));
+    assert_eq!(
+        error.location().unwrap().path(),
+        "methods[0].attributes[Code]"
+    );
+
+    let limits = ParseLimits {
+        max_attribute_length: 4,
+        ..ParseLimits::default()
+    };
+    let error = parse_class_file_ref_with(&bytes, &limits).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::LimitExceeded { .. }));
+    assert_eq!(error.location().unwrap().path(), "methods[0].attributes");
+}
+
+#[test]
+fn code_trailing_bytes() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let mut method = class.method(MethodAccessFlags::STATIC, "run", "()V");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Return);
+    method.code(0, 0, asm.finish().unwrap());
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+
+    // the Code attribute of the only method is followed by the class
+    // attributes count; append two bytes to it
+    let end = bytes.len() - 2;
+    let length = end - 13 - 4;
+    assert_eq!(bytes[length..length + 4], [0, 0, 0, 13]);
+    bytes[length + 3] = 15;
+    bytes.splice(end..end, [0, 0].iter().copied());
+
+    let class_ref = parse_class_file_ref(&bytes).unwrap();
+    let method_ref = class_ref.methods().next().unwrap();
+    let error = method_ref.code(class_ref.constant_pool()).err().unwrap();
+    assert!(
+        matches!(error.kind(), JvmParseError::InvalidFormat(message) if message == "2 trailing bytes")
+    );
+    assert_eq!(error.location().unwrap().offset(), end as u64);
+
+    let error = class_ref.to_class_file().err().unwrap();
+    assert!(
+        matches!(error.kind(), JvmParseError::InvalidFormat(message) if message == "2 trailing bytes")
+    );
+    assert_eq!(error.location().unwrap().offset(), end as u64);
+}
@@ -0,0 +1,176 @@
+use classfile::build::ClassBuilder;
+use classfile::descriptor::parse_field_descriptor;
+use classfile::model::attributes::BootstrapMethod;
+use classfile::model::constants::{ConstantIndex, Loadable};
+use classfile::model::{Attribute, ClassAccessFlags, MethodAccessFlags, ReferenceKind};
+use classfile::parse::parse_class_file;
+use classfile::write::write_class_file;
+use rustjvm_opcode::{Assembler, Opcode};
+
+fn hello_world() -> Vec<u8> {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC | ClassAccessFlags::SUPER, "Hello");
+    class.source_file("Hello.java");
+
+    let mut main = class.method(
+        MethodAccessFlags::PUBLIC | MethodAccessFlags::STATIC,
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    let constants = main.constants();
+    let out = constants.field_ref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let message = constants.string("Hello World");
+    let println = constants.method_ref("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Getstatic(out.0));
+    asm.push(Opcode::ldc(message.0));
+    asm.push(Opcode::Invokevirtual(println.0));
+    asm.push(Opcode::Return);
+    main.code(2, 1, asm.finish().unwrap());
+
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn build_hello_world() {
+    let bytes = hello_world();
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+
+    let this_class = cpool.resolve_class(class_file.this_class()).unwrap();
+    assert_eq!(cpool.resolve_utf8(this_class).unwrap(), "Hello");
+    let super_class = cpool.resolve_class(class_file.super_class()).unwrap();
+    assert_eq!(cpool.resolve_utf8(super_class).unwrap(), "java/lang/Object");
+
+    let method = &class_file.methods()[0];
+    assert_eq!(cpool.resolve_utf8(method.name_index).unwrap(), "main");
+    match &method.attributes[..] {
+        [Attribute::Code(code)] => {
+            assert_eq!(code.max_stack, 2);
+            assert_eq!(code.code.len(), 4);
+        }
+        attributes => panic!("unexpected attributes: {:?}", attributes),
+    }
+    match class_file.attributes() {
+        [Attribute::SourceFile(name)] => {
+            assert_eq!(cpool.resolve_utf8(*name).unwrap(), "Hello.java")
+        }
+        attributes => panic!("unexpected attributes: {:?}", attributes),
+    }
+
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn build_dynamic_constant() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC | ClassAccessFlags::SUPER, "Condy");
+    let constants = class.constants();
+    let bootstrap = constants.method_ref(
+        "java/lang/invoke/ConstantBootstraps",
+        "getStaticFinal",
+        "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;Ljava/lang/Class;)Ljava/lang/Object;",
+    );
+    let bootstrap_method_ref = constants.method_handle(ReferenceKind::InvokeStatic, bootstrap);
+    let owner = constants.class("java/lang/Integer");
+    let dynamic = constants.dynamic(0, "MAX_VALUE", "I");
+    class.attribute(Attribute::BootstrapMethods(vec![BootstrapMethod {
+        bootstrap_method_ref,
+        bootstrap_arguments: vec![owner],
+    }]));
+    class.version(55, 0);
+
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+
+    assert_eq!(
+        cpool.resolve_loadable(dynamic).unwrap(),
+        Loadable::Dynamic {
+            bootstrap_method_attr_index: 0,
+            name: "MAX_VALUE",
+            descriptor: "I",
+        }
+    );
+    let constant = class_file.resolve_dynamic_constant(dynamic).unwrap();
+    assert_eq!(constant.bootstrap_method.reference.name, "getStaticFinal");
+    assert_eq!(
+        constant.static_arguments,
+        vec![Loadable::Class("java/lang/Integer")]
+    );
+    assert_eq!(constant.name, "MAX_VALUE");
+    assert_eq!(constant.descriptor, parse_field_descriptor("I").unwrap());
+    assert!(class_file.resolve_dynamic_constant(bootstrap).is_err());
+}
+
+#[test]
+fn build_method_metadata() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Metadata");
+    let mut method = class.method(MethodAccessFlags::PUBLIC, "run", "()V");
+    method
+        .exception("java/io/IOException")
+        .exception("java/lang/InterruptedException")
+        .attribute(Attribute::Synthetic);
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Return);
+    method.code(0, 1, asm.finish().unwrap());
+
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+
+    let method = &class_file.methods()[0];
+    let exceptions: Vec<_> = method
+        .exceptions()
+        .iter()
+        .map(|exception| cpool.resolve_class_name(*exception).unwrap())
+        .collect();
+    assert_eq!(
+        exceptions,
+        vec!["java/io/IOException", "java/lang/InterruptedException"]
+    );
+    assert!(method.is_synthetic());
+    assert!(!method.is_deprecated());
+}
+
+#[test]
+fn build_exception_handler() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Handler");
+    let mut method = class.method(MethodAccessFlags::STATIC, "run", "()V");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Return);
+    asm.push(Opcode::Athrow);
+    method
+        .code(1, 0, asm.finish().unwrap())
+        .exception_handler(0, 1, 1, Some("java/lang/Exception"))
+        .exception_handler(0, 1, 1, None);
+
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+
+    match &class_file.methods()[0].attributes[..] {
+        [Attribute::Code(code)] => {
+            let table = &code.exception_table;
+            assert_eq!(table.len(), 2);
+            assert_eq!(
+                (table[0].start_pc, table[0].end_pc, table[0].handler_pc),
+                (0, 1, 1)
+            );
+            assert_eq!(
+                cpool
+                    .resolve_class_name(ConstantIndex(table[0].catch_type))
+                    .unwrap(),
+                "java/lang/Exception"
+            );
+            assert_eq!(table[1].catch_type, 0);
+        }
+        attributes => panic!("unexpected attributes: {:?}", attributes),
+    }
+}
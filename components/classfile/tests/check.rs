@@ -0,0 +1,24 @@
+use classfile::check::check_format;
+use classfile::parse::parse_class_file;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+#[test]
+fn compiled_classes_are_well_formed() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    for entry in fs::read_dir(path).unwrap() {
+        let path = entry.unwrap().path();
+        let class_file = parse_class_file(File::open(&path).unwrap()).unwrap();
+        let violations: Vec<_> = check_format(&class_file)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert!(
+            violations.is_empty(),
+            "{}: {:?}",
+            path.display(),
+            violations
+        );
+    }
+}
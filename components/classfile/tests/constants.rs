@@ -0,0 +1,160 @@
+use classfile::build::ConstantPoolBuilder;
+use classfile::error::JvmParseError;
+use classfile::model::constants::{Constant, ConstantIndex, MemberRef};
+use classfile::model::{Attribute, ReferenceKind};
+use classfile::parse::parse_class_file;
+use std::fs::File;
+use std::path::PathBuf;
+
+#[test]
+fn resolve_references() {
+    let mut constants = ConstantPoolBuilder::new();
+    let field = constants.field_ref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let method = constants.method_ref("java/io/PrintStream", "println", "(I)V");
+    let interface_method = constants.interface_method_ref("java/lang/Runnable", "run", "()V");
+    let method_type = constants.method_type("(J)Z");
+    let cpool = constants.build().unwrap();
+
+    assert_eq!(
+        cpool.resolve_field_ref(field).unwrap(),
+        MemberRef {
+            class: "java/lang/System",
+            name: "out",
+            descriptor: "Ljava/io/PrintStream;",
+        }
+    );
+    assert_eq!(cpool.resolve_method_ref(method).unwrap().name, "println");
+    assert_eq!(
+        cpool
+            .resolve_interface_method_ref(interface_method)
+            .unwrap()
+            .class,
+        "java/lang/Runnable"
+    );
+    assert_eq!(cpool.resolve_method_type(method_type).unwrap(), "(J)Z");
+
+    assert!(matches!(
+        cpool.resolve_method_ref(field),
+        Err(JvmParseError::WrongConstantType(index, _)) if index == field
+    ));
+    assert!(cpool.resolve_field_ref(interface_method).is_err());
+    assert!(cpool.resolve_interface_method_ref(method).is_err());
+    assert_eq!(
+        cpool.resolve_member_ref(interface_method).unwrap().name,
+        "run"
+    );
+}
+
+#[test]
+fn resolve_method_handles() {
+    let mut constants = ConstantPoolBuilder::new();
+    let field = constants.field_ref("java/lang/System", "out", "Ljava/io/PrintStream;");
+    let method = constants.method_ref("java/io/PrintStream", "println", "(I)V");
+    let interface_method = constants.interface_method_ref("java/lang/Runnable", "run", "()V");
+    let get_static = constants.method_handle(ReferenceKind::GetStatic, field);
+    let invoke_static = constants.method_handle(ReferenceKind::InvokeStatic, interface_method);
+    let get_method = constants.method_handle(ReferenceKind::GetField, method);
+    let virtual_field = constants.method_handle(ReferenceKind::InvokeVirtual, field);
+    let interface_virtual = constants.method_handle(ReferenceKind::InvokeInterface, method);
+    let cpool = constants.build().unwrap();
+
+    assert_eq!(
+        cpool
+            .resolve_method_handle(get_static)
+            .unwrap()
+            .reference
+            .name,
+        "out"
+    );
+    assert_eq!(
+        cpool
+            .resolve_method_handle(invoke_static)
+            .unwrap()
+            .reference_kind,
+        ReferenceKind::InvokeStatic
+    );
+    for (handle, reference) in [
+        (get_method, method),
+        (virtual_field, field),
+        (interface_virtual, method),
+    ] {
+        assert!(matches!(
+            cpool.resolve_method_handle(handle),
+            Err(JvmParseError::WrongConstantType(index, _)) if index == reference
+        ));
+    }
+}
+
+#[test]
+fn resolve_values() {
+    let mut constants = ConstantPoolBuilder::new();
+    let string = constants.string("Hello");
+    let integer = constants.integer(-7);
+    let long = constants.long(1 << 40);
+    let float = constants.float(1.5);
+    let double = constants.double(-0.25);
+    let cpool = constants.build().unwrap();
+
+    assert_eq!(cpool.resolve_string(string).unwrap(), "Hello");
+    assert_eq!(cpool.resolve_integer(integer).unwrap(), -7);
+    assert_eq!(cpool.resolve_long(long).unwrap(), 1 << 40);
+    assert_eq!(cpool.resolve_float(float).unwrap(), 1.5);
+    assert_eq!(cpool.resolve_double(double).unwrap(), -0.25);
+
+    assert!(cpool.resolve_integer(float).is_err());
+    assert!(cpool.resolve_long(double).is_err());
+    assert!(cpool.resolve_string(ConstantIndex(string.0 - 1)).is_err());
+}
+
+#[test]
+fn reject_unusable_slots() {
+    let mut constants = ConstantPoolBuilder::new();
+    let long = constants.long(42);
+    let double = constants.double(4.2);
+    let cpool = constants.build().unwrap();
+
+    let after_long = ConstantIndex(long.0 + 1);
+    assert!(cpool.get(long).is_some());
+    assert!(cpool.get(after_long).is_none());
+    assert!(matches!(
+        cpool.resolve_long(after_long),
+        Err(JvmParseError::MissingConstant(index)) if index == after_long
+    ));
+    assert!(cpool.get(ConstantIndex(double.0 + 1)).is_none());
+    assert!(cpool.get(ConstantIndex(0)).is_none());
+}
+
+#[test]
+fn modified_utf8_strings() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes/Strings.class");
+    let class_file = parse_class_file(File::open(path).unwrap()).unwrap();
+    let cpool = class_file.constant_pool();
+    let value = |name: &str| {
+        let field = class_file
+            .fields()
+            .iter()
+            .find(|field| cpool.resolve_utf8(field.name_index).unwrap() == name)
+            .unwrap();
+        let string_index = field
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::ConstantValue(value) => Some(value.constantvalue_index),
+                _ => None,
+            })
+            .unwrap();
+        match cpool.get(string_index) {
+            Some(Constant::String(utf8_index)) => cpool.resolve_java_string(*utf8_index).unwrap(),
+            constant => panic!("unexpected constant {:?}", constant),
+        }
+    };
+
+    assert_eq!(value("PLAIN").as_str(), Some("plain"));
+    assert_eq!(value("NUL").as_str(), Some("a\0b"));
+    assert_eq!(value("NUL").as_bytes(), b"a\xC0\x80b");
+    assert_eq!(value("SUPPLEMENTARY").as_str(), Some("\u{1F600}"));
+    assert_eq!(value("LONE_SURROGATE").as_str(), None);
+    assert_eq!(value("LONE_SURROGATE").to_string_lossy(), "x\u{FFFD}y");
+    assert_eq!(value("LONE_SURROGATE").as_bytes(), b"x\xED\xA0\x80y");
+}
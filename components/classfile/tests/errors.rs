@@ -0,0 +1,194 @@
+use classfile::build::ClassBuilder;
+use classfile::error::JvmParseError;
+use classfile::model::{Attribute, ClassAccessFlags, MethodAccessFlags};
+use classfile::parse::{parse_class_file, parse_class_file_lenient};
+use classfile::write::write_class_file;
+use rustjvm_opcode::{Assembler, DisasmErrorKind, Opcode};
+
+const CODE: [u8; 3] = [0x03, 0x57, 0xb1];
+
+/// Class with a method containing `CODE` and the offset of the code.
+fn class_bytes() -> (Vec<u8>, usize) {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let mut method = class.method(MethodAccessFlags::STATIC, "run", "()V");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Iconst0);
+    asm.push(Opcode::Pop);
+    asm.push(Opcode::Return);
+    method.code(1, 0, asm.finish().unwrap());
+
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let offset = bytes
+        .windows(CODE.len())
+        .position(|window| window == CODE)
+        .unwrap();
+    (bytes, offset)
+}
+
+#[test]
+fn invalid_opcode() {
+    let (mut bytes, offset) = class_bytes();
+    bytes[offset + 2] = 0xcb;
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), (offset + 2) as u64);
+    assert_eq!(location.path(), "methods[0].attributes[Code].code@pc 2");
+    assert_eq!(location.class_name(), Some("Test"));
+    match error.kind() {
+        JvmParseError::InvalidCode(error) => {
+            assert_eq!(error.kind, DisasmErrorKind::UnknownOpcode);
+            assert_eq!(error.pc, 2);
+            assert_eq!(error.opcode, Some(0xcb));
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Test: invalid code: unknown opcode at pc 2 (opcode 0xcb) at byte {} \
+             in methods[0].attributes[Code].code@pc 2",
+            offset + 2
+        )
+    );
+}
+
+#[test]
+fn invalid_magic() {
+    let (mut bytes, _) = class_bytes();
+    bytes[0] = 0;
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), 0);
+    assert_eq!(location.path(), "");
+    assert_eq!(location.class_name(), None);
+    assert_eq!(error.to_string(), "invalid magic at byte 0");
+}
+
+#[test]
+fn unknown_constant_tag() {
+    let (mut bytes, _) = class_bytes();
+    // first constant follows magic, version and constant pool count
+    bytes[10] = 2;
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), 10);
+    assert_eq!(location.path(), "constant_pool[1]");
+    assert!(matches!(error.kind(), JvmParseError::InvalidFormat(_)));
+}
+
+#[test]
+fn truncated() {
+    let (bytes, offset) = class_bytes();
+
+    let error = parse_class_file(&bytes[..offset + 1]).err().unwrap();
+    let location = error.location().unwrap();
+    assert_eq!(location.path(), "methods[0].attributes[Code]");
+    assert_eq!(location.class_name(), Some("Test"));
+    assert!(matches!(error.kind(), JvmParseError::Io(_)));
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[test]
+fn lenient_skips_method_body() {
+    let (mut bytes, offset) = class_bytes();
+    bytes[offset + 2] = 0xcb;
+
+    let (class_file, diagnostics) = parse_class_file_lenient(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+    match &class_file.methods()[0].attributes[..] {
+        [Attribute::Unknown { name, .. }] => assert_eq!(cpool.resolve_utf8(*name).unwrap(), "Code"),
+        attributes => panic!("unexpected attributes: {:?}", attributes),
+    }
+    assert_eq!(diagnostics.len(), 1);
+    let location = diagnostics[0].location().unwrap();
+    assert_eq!(location.offset(), (offset + 2) as u64);
+    assert_eq!(location.path(), "methods[0].attributes[Code].code@pc 2");
+    assert_eq!(location.class_name(), Some("Test"));
+
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn lenient_demotes_nested_attribute() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let mut method = class.method(MethodAccessFlags::STATIC, "run", "()V");
+    let name = method.constants().utf8("LineNumberTable");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Return);
+    method
+        .code(0, 0, asm.finish().unwrap())
+        // one entry announced, but none present
+        .attribute(Attribute::Unknown {
+            name,
+            value: vec![0, 1],
+        });
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::Io(_)));
+
+    let (class_file, diagnostics) = parse_class_file_lenient(&bytes[..]).unwrap();
+    match &class_file.methods()[0].attributes[..] {
+        [Attribute::Code(code)] => assert!(matches!(
+            &code.attributes[..],
+            [Attribute::Unknown { value, .. }] if value == &[0, 1]
+        )),
+        attributes => panic!("unexpected attributes: {:?}", attributes),
+    }
+    assert_eq!(diagnostics.len(), 1);
+    let location = diagnostics[0].location().unwrap();
+    // end of the LineNumberTable, which is followed by the class attribute count
+    assert_eq!(location.offset(), bytes.len() as u64 - 2);
+    assert_eq!(
+        location.path(),
+        "methods[0].attributes[Code].attributes[LineNumberTable]"
+    );
+    assert_eq!(location.offset(), error.location().unwrap().offset());
+}
+
+#[test]
+fn lenient_without_errors() {
+    let (bytes, _) = class_bytes();
+    let (class_file, diagnostics) = parse_class_file_lenient(&bytes[..]).unwrap();
+    assert!(diagnostics.is_empty());
+    assert!(matches!(
+        class_file.methods()[0].attributes[..],
+        [Attribute::Code(_)]
+    ));
+}
+
+#[test]
+fn trailing_attribute_bytes() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    class.source_file("Test.java");
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    // the SourceFile attribute is last: name, length, sourcefile_index
+    let length = bytes.len() - 6;
+    bytes[length..length + 4].copy_from_slice(&[0, 0, 0, 4]);
+    bytes.extend_from_slice(&[0, 0]);
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::InvalidFormat(_)));
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), bytes.len() as u64 - 2);
+    assert_eq!(location.path(), "attributes[SourceFile]");
+
+    let (class_file, diagnostics) = parse_class_file_lenient(&bytes[..]).unwrap();
+    assert!(matches!(
+        class_file.attributes(),
+        [Attribute::Unknown { value, .. }] if value.len() == 4
+    ));
+    assert_eq!(diagnostics.len(), 1);
+
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
@@ -0,0 +1,175 @@
+use classfile::build::ClassBuilder;
+use classfile::error::JvmParseError;
+use classfile::model::annotations::{Annotation, ConstKind, ElementValue, ElementValuePair};
+use classfile::model::{Attribute, ClassAccessFlags, MethodAccessFlags};
+use classfile::parse::{parse_class_file, parse_class_file_with, ParseLimits, ParseOptions};
+use classfile::write::write_class_file;
+use rustjvm_opcode::{Assembler, Opcode};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::PathBuf;
+
+const HEADER: [u8; 8] = [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34];
+
+fn write(builder: ClassBuilder) -> Vec<u8> {
+    let mut bytes = vec![];
+    write_class_file(&builder.build().unwrap(), &mut bytes).unwrap();
+    bytes
+}
+
+fn with_limits(limits: ParseLimits) -> ParseOptions {
+    ParseOptions {
+        lenient: false,
+        limits,
+    }
+}
+
+fn assert_limit_exceeded(error: &JvmParseError, expected: &str) {
+    match error.kind() {
+        JvmParseError::LimitExceeded { what, .. } => assert_eq!(*what, expected),
+        error => panic!("unexpected error {}", error),
+    }
+}
+
+#[test]
+fn zero_constant_pool_count() {
+    let mut bytes = HEADER.to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00]);
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert_eq!(error.location().unwrap().offset(), 8);
+    assert!(matches!(error.kind(), JvmParseError::InvalidFormat(_)));
+}
+
+#[test]
+fn long_constant_in_last_slot() {
+    let mut bytes = HEADER.to_vec();
+    bytes.extend_from_slice(&[0x00, 0x02, 0x05, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert!(matches!(error.kind(), JvmParseError::InvalidFormat(_)));
+}
+
+#[test]
+fn huge_attribute_length() {
+    let mut builder = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    builder.source_file("Test.java");
+    let mut bytes = write(builder);
+    // the SourceFile attribute is last: name, length, sourcefile_index
+    let length = bytes.len() - 6;
+    bytes[length..length + 4].copy_from_slice(&[0xff; 4]);
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert_limit_exceeded(&error, "attribute length");
+    let location = error.location().unwrap();
+    assert_eq!(location.offset(), length as u64);
+    assert_eq!(location.path(), "attributes[SourceFile]");
+
+    let limits = ParseLimits {
+        max_attribute_length: u32::MAX,
+        ..ParseLimits::default()
+    };
+    let error = parse_class_file_with(&bytes[..], &with_limits(limits))
+        .err()
+        .unwrap();
+    assert!(matches!(error.kind(), JvmParseError::Io(_)));
+}
+
+#[test]
+fn code_length() {
+    let mut builder = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let mut method = builder.method(MethodAccessFlags::STATIC, "run", "()V");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Iconst0);
+    asm.push(Opcode::Pop);
+    asm.push(Opcode::Return);
+    method.code(1, 0, asm.finish().unwrap());
+    let bytes = write(builder);
+
+    let limits = ParseLimits {
+        max_code_length: 2,
+        ..ParseLimits::default()
+    };
+    let error = parse_class_file_with(&bytes[..], &with_limits(limits))
+        .err()
+        .unwrap();
+    assert_limit_exceeded(&error, "code length");
+    assert_eq!(
+        error.location().unwrap().path(),
+        "methods[0].attributes[Code]"
+    );
+
+    let options = ParseOptions {
+        lenient: true,
+        limits: ParseLimits {
+            max_code_length: 2,
+            ..ParseLimits::default()
+        },
+    };
+    let (_, diagnostics) = parse_class_file_with(&bytes[..], &options).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_limit_exceeded(&diagnostics[0], "code length");
+}
+
+#[test]
+fn constant_pool_count() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes/JavaHelloWorld.class");
+    let limits = ParseLimits {
+        max_constants: 10,
+        ..ParseLimits::default()
+    };
+    let error = parse_class_file_with(File::open(path).unwrap(), &with_limits(limits))
+        .err()
+        .unwrap();
+    assert_limit_exceeded(&error, "constant pool count");
+    assert_eq!(error.location().unwrap().offset(), 8);
+}
+
+#[test]
+fn nesting_depth() {
+    let mut builder = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    let type_index = builder.constants().utf8("LNested;");
+    let name_index = builder.constants().utf8("value");
+    let mut value = ElementValue::Const(ConstKind::Int, builder.constants().integer(0));
+    for _ in 0..100 {
+        value = ElementValue::Array(vec![value]);
+    }
+    builder.attribute(Attribute::RuntimeVisibleAnnotations(vec![Annotation {
+        type_index,
+        elements: vec![ElementValuePair { name_index, value }],
+    }]));
+    let bytes = write(builder);
+
+    let error = parse_class_file(&bytes[..]).err().unwrap();
+    assert_limit_exceeded(&error, "nesting depth");
+
+    let limits = ParseLimits {
+        max_depth: 128,
+        ..ParseLimits::default()
+    };
+    parse_class_file_with(&bytes[..], &with_limits(limits)).unwrap();
+}
+
+#[test]
+fn malformed_classes_do_not_panic() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    for entry in fs::read_dir(path).unwrap() {
+        let mut bytes = vec![];
+        File::open(entry.unwrap().path())
+            .unwrap()
+            .read_to_end(&mut bytes)
+            .unwrap();
+        parse_class_file(&bytes[..]).unwrap();
+
+        for length in 0..bytes.len() {
+            assert!(parse_class_file(&bytes[..length]).is_err());
+        }
+        for i in 0..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[i] = 0xff;
+            let _ = parse_class_file(&corrupted[..]);
+        }
+    }
+}
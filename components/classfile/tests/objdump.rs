@@ -1,4 +1,4 @@
-use classfile::descriptor::parse_field_descriptor;
+use classfile::error::JvmParseResult;
 use classfile::model::ClassFile;
 use classfile::parse::parse_class_file;
 use std::fs::File;
@@ -17,10 +17,21 @@ fn private_final_field() {
 
     let mut file = File::open(resource).unwrap();
     let class_file = parse_class_file(&mut file).unwrap();
-    objdump(&class_file);
+    objdump(&class_file).unwrap();
 }
 
-fn objdump(class_file: &ClassFile) {
+#[test]
+fn interfaces_and_modules() {
+    for resource in &["Shapes$Circle.class", "module-info.class"] {
+        let mut file = File::open(test_resource(resource)).unwrap();
+        let class_file = parse_class_file(&mut file).unwrap();
+        objdump(&class_file).unwrap();
+    }
+}
+
+fn objdump(class_file: &ClassFile) -> JvmParseResult<()> {
+    let class = class_file.view();
+
     println!(
         "VERSION: {}.{} (Java {})",
         class_file.major_version(),
@@ -38,69 +49,44 @@ fn objdump(class_file: &ClassFile) {
     println!(
         "THIS CLASS: {:?} ({})",
         class_file.this_class(),
-        constant_pool
-            .resolve_utf8(
-                constant_pool
-                    .resolve_class(class_file.this_class())
-                    .unwrap()
-            )
-            .unwrap()
-    );
-    println!(
-        "SUPER CLASS: {:?} ({})",
-        class_file.super_class(),
-        constant_pool
-            .resolve_utf8(
-                constant_pool
-                    .resolve_class(class_file.super_class())
-                    .unwrap()
-            )
-            .unwrap()
+        class.class_name()?
     );
-
-    for interface in class_file.interfaces() {
+    if let Some(super_name) = class.super_name()? {
         println!(
-            "INTERFACE: {:?} -> {:?}",
-            interface,
-            constant_pool.resolve_utf8(*interface).unwrap()
+            "SUPER CLASS: {:?} ({})",
+            class_file.super_class(),
+            super_name
         );
     }
 
-    for field in class_file.fields() {
+    for (interface, name) in class_file.interfaces().iter().zip(class.interface_names()?) {
+        println!("INTERFACE: {:?} -> {}", interface, name);
+    }
+
+    for field in class.fields()? {
         println!("FIELD:",);
-        println!(
-            "  NAME: {:?} -> {}",
-            field.name_index,
-            constant_pool.resolve_utf8(field.name_index).unwrap()
-        );
+        println!("  NAME: {:?} -> {}", field.field.name_index, field.name);
         println!("  FLAGS: {:?}", field.access_flags);
         println!(
             "  DESCRIPTOR: {:?} -> {:?}",
-            field.descriptor_index,
-            parse_field_descriptor(constant_pool.resolve_utf8(field.descriptor_index).unwrap())
-                .unwrap()
+            field.field.descriptor_index, field.descriptor
         );
         println!("  ATTRIBUTES:");
-        for attribute in &field.attributes {
+        for attribute in &field.field.attributes {
             println!("    {:#?}", attribute);
         }
     }
 
-    for method in class_file.methods() {
+    for method in class.methods()? {
         println!("METHOD:",);
-        println!(
-            "  NAME: {:?} -> {}",
-            method.name_index,
-            constant_pool.resolve_utf8(method.name_index).unwrap()
-        );
+        println!("  NAME: {:?} -> {}", method.method.name_index, method.name);
         println!("  FLAGS: {:?}", method.access_flags);
         println!(
-            "  DESCRIPTOR: {:?} -> {}",
-            method.descriptor_index,
-            constant_pool.resolve_utf8(method.descriptor_index).unwrap()
+            "  DESCRIPTOR: {:?} -> {:?}",
+            method.method.descriptor_index, method.descriptor
         );
         println!("  ATTRIBUTES:");
-        for attribute in &method.attributes {
+        for attribute in &method.method.attributes {
             println!("    {:#?}", attribute);
         }
     }
@@ -109,4 +95,5 @@ fn objdump(class_file: &ClassFile) {
     for attribute in class_file.attributes() {
         println!("    {:#?}", attribute);
     }
+    Ok(())
 }
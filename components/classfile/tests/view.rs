@@ -0,0 +1,89 @@
+use classfile::descriptor::{parse_field_descriptor, parse_method_descriptor};
+use classfile::model::{ClassFile, FieldAccessFlags, MethodAccessFlags};
+use classfile::parse::parse_class_file;
+use std::fs::File;
+use std::path::PathBuf;
+
+fn load_class(resource: &str) -> ClassFile {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    path.push(resource);
+    parse_class_file(File::open(path).unwrap()).unwrap()
+}
+
+#[test]
+fn class_names() {
+    let class_file = load_class("Shapes$Circle.class");
+    let class = class_file.view();
+    assert_eq!(
+        class.class_name().unwrap(),
+        "de/richardliebscher/rustjvm/Shapes$Circle"
+    );
+    assert_eq!(class.super_name().unwrap(), Some("java/lang/Record"));
+    assert_eq!(
+        class.interface_names().unwrap(),
+        vec!["de/richardliebscher/rustjvm/Shapes$Shape"]
+    );
+
+    let class_file = load_class("module-info.class");
+    let class = class_file.view();
+    assert_eq!(class.class_name().unwrap(), "module-info");
+    assert_eq!(class.super_name().unwrap(), None);
+    assert!(class.interface_names().unwrap().is_empty());
+}
+
+#[test]
+fn fields() {
+    let class_file = load_class("MethodInfo.class");
+    let fields = class_file.view().fields().unwrap();
+    let names: Vec<_> = fields.iter().map(|field| field.name).collect();
+    assert_eq!(names, vec!["counter", "cache", "LEGACY"]);
+    assert_eq!(
+        fields[1].descriptor,
+        parse_field_descriptor("Ljava/lang/Object;").unwrap()
+    );
+    assert_eq!(
+        fields[1].access_flags,
+        FieldAccessFlags::PRIVATE | FieldAccessFlags::TRANSIENT
+    );
+}
+
+#[test]
+fn methods() {
+    let class_file = load_class("MethodInfo.class");
+    let methods = class_file.view().methods().unwrap();
+    let names: Vec<_> = methods.iter().map(|method| method.name).collect();
+    assert_eq!(names, vec!["<init>", "call", "read", "call"]);
+    assert!(methods.iter().all(|method| method.code.is_some()));
+
+    let shape = load_class("Shapes$Shape.class");
+    assert!(shape.view().methods().unwrap().is_empty());
+}
+
+#[test]
+fn find_members() {
+    let class_file = load_class("MethodInfo.class");
+    let class = class_file.view();
+
+    let read = class
+        .find_method("read", "(Ljava/lang/String;[I)V")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        read.descriptor,
+        parse_method_descriptor("(Ljava/lang/String;[I)V").unwrap()
+    );
+    assert!(read.access_flags.contains(MethodAccessFlags::VARARGS));
+    assert_eq!(read.code.unwrap().max_locals, 3);
+
+    let bridge = class
+        .find_method("call", "()Ljava/lang/Object;")
+        .unwrap()
+        .unwrap();
+    assert!(bridge.access_flags.contains(MethodAccessFlags::BRIDGE));
+    assert!(class.find_method("call", "()V").unwrap().is_none());
+
+    let counter = class.find_field("counter", "I").unwrap().unwrap();
+    assert!(counter.access_flags.contains(FieldAccessFlags::VOLATILE));
+    assert!(class.find_field("counter", "J").unwrap().is_none());
+}
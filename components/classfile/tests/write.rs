@@ -0,0 +1,141 @@
+use classfile::build::ClassBuilder;
+use classfile::model::{ClassAccessFlags, FieldAccessFlags, MethodAccessFlags};
+use classfile::parse::parse_class_file;
+use classfile::write::write_class_file;
+use rustjvm_opcode::{Assembler, Opcode};
+use std::fs;
+use std::path::PathBuf;
+
+fn test_resource(resource: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/classes");
+    path.push(resource);
+    path
+}
+
+fn assert_round_trip(resource: &str) {
+    let bytes = fs::read(test_resource(resource)).unwrap();
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn round_trip_everything_class() {
+    assert_round_trip("EverythingClass.class");
+}
+
+#[test]
+fn round_trip_hello_world() {
+    assert_round_trip("JavaHelloWorld.class");
+}
+
+#[test]
+fn round_trip_stack_maps() {
+    assert_round_trip("StackMaps.class");
+}
+
+#[test]
+fn round_trip_debug_info() {
+    assert_round_trip("DebugInfo.class");
+}
+
+#[test]
+fn round_trip_generics() {
+    assert_round_trip("Generics.class");
+}
+
+#[test]
+fn round_trip_annotations() {
+    assert_round_trip("Annotated.class");
+    assert_round_trip("Annotated$Info.class");
+}
+
+#[test]
+fn round_trip_type_annotations() {
+    assert_round_trip("TypeAnnotated.class");
+}
+
+#[test]
+fn round_trip_bootstrap_methods() {
+    assert_round_trip("Indy.class");
+}
+
+#[test]
+fn round_trip_module_info() {
+    assert_round_trip("module-info.class");
+}
+
+#[test]
+fn round_trip_nested_classes() {
+    assert_round_trip("Shapes.class");
+    assert_round_trip("Shapes$1.class");
+    assert_round_trip("Shapes$Circle.class");
+    assert_round_trip("Shapes$Polygon.class");
+    assert_round_trip("Shapes$Shape.class");
+}
+
+#[test]
+fn round_trip_method_info() {
+    assert_round_trip("MethodInfo.class");
+}
+
+#[test]
+fn round_trip_modified_utf8() {
+    assert_round_trip("Strings.class");
+}
+
+#[test]
+fn round_trip_unpaired_surrogate_name() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    class.field(FieldAccessFlags::PRIVATE, "___", "I");
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let name = bytes
+        .windows(3)
+        .position(|window| window == b"___")
+        .unwrap();
+    bytes[name..name + 3].copy_from_slice(&[0xED, 0xA0, 0x80]);
+
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let cpool = class_file.constant_pool();
+    let name_index = class_file.fields()[0].name_index;
+    assert!(cpool.resolve_utf8(name_index).is_err());
+    assert_eq!(
+        cpool
+            .resolve_java_string(name_index)
+            .unwrap()
+            .to_string_lossy(),
+        "\u{FFFD}"
+    );
+
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn round_trip_duplicate_attribute_name() {
+    let mut class = ClassBuilder::new(ClassAccessFlags::PUBLIC, "Test");
+    // becomes a second "Code" constant ahead of the one the method uses
+    class.constants().utf8("Cxde");
+    let mut asm = Assembler::new();
+    asm.push(Opcode::Return);
+    class
+        .method(MethodAccessFlags::STATIC, "run", "()V")
+        .code(0, 0, asm.finish().unwrap());
+    let mut bytes = vec![];
+    write_class_file(&class.build().unwrap(), &mut bytes).unwrap();
+    let name = bytes
+        .windows(4)
+        .position(|window| window == b"Cxde")
+        .unwrap();
+    bytes[name..name + 4].copy_from_slice(b"Code");
+
+    let class_file = parse_class_file(&bytes[..]).unwrap();
+    let mut written = vec![];
+    write_class_file(&class_file, &mut written).unwrap();
+    assert_eq!(written, bytes);
+}
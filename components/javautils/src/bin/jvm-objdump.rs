@@ -2,10 +2,10 @@ use std::fs::File;
 
 use clap::{App, Arg};
 
-use classfile::descriptor::parse_field_descriptor;
+use classfile::error::{JvmParseError, JvmParseResult};
 use classfile::parse::parse_class_file;
 
-fn main() {
+fn main() -> Result<(), JvmParseError> {
     let matches = App::new("JVM objdump")
         .version("0.1")
         .author("Richard Liebscher <richard.liebscher@gmail.com>")
@@ -25,8 +25,9 @@ fn main() {
         .get_matches();
 
     let input = matches.value_of("INPUT").unwrap();
-    let mut file = File::open(input).unwrap();
-    let class_file = parse_class_file(&mut file).unwrap();
+    let mut file = File::open(input)?;
+    let class_file = parse_class_file(&mut file)?;
+    let class = class_file.view();
 
     println!(
         "VERSION: {}.{} (Java {})",
@@ -43,65 +44,76 @@ fn main() {
     }
 
     println!(
-        "THIS CLASS: {:?} ({:?})",
+        "THIS CLASS: {:?} -> {}",
         class_file.this_class(),
-        constant_pool.get(class_file.this_class()).unwrap()
+        class.class_name()?
     );
-    println!(
-        "SUPER CLASS: {:?} ({:?})",
-        class_file.super_class(),
-        constant_pool.get(class_file.super_class()).unwrap()
-    );
-
-    for interface in class_file.interfaces() {
+    if let Some(super_name) = class.super_name()? {
         println!(
-            "INTERFACE: {:?} -> {:?}",
-            interface,
-            constant_pool.resolve_utf8(*interface).unwrap()
+            "SUPER CLASS: {:?} -> {}",
+            class_file.super_class(),
+            super_name
         );
     }
 
-    for field in class_file.fields() {
+    for (interface, name) in class_file.interfaces().iter().zip(class.interface_names()?) {
+        println!("INTERFACE: {:?} -> {}", interface, name);
+    }
+
+    for field in class.fields()? {
         println!("FIELD:",);
-        println!(
-            "  NAME: {:?} -> {}",
-            field.name_index,
-            constant_pool.resolve_utf8(field.name_index).unwrap()
-        );
+        println!("  NAME: {:?} -> {}", field.field.name_index, field.name);
         println!("  FLAGS: {:?}", field.access_flags);
         println!(
             "  DESCRIPTOR: {:?} -> {:?}",
-            field.descriptor_index,
-            parse_field_descriptor(constant_pool.resolve_utf8(field.descriptor_index).unwrap())
-                .unwrap()
+            field.field.descriptor_index, field.descriptor
         );
         println!("  ATTRIBUTES:");
-        for attribute in &field.attributes {
+        for attribute in &field.field.attributes {
             println!("    {:#?}", attribute);
         }
     }
 
-    for method in class_file.methods() {
+    for method in class.methods()? {
         println!("METHOD:",);
-        println!(
-            "  NAME: {:?} -> {}",
-            method.name_index,
-            constant_pool.resolve_utf8(method.name_index).unwrap()
-        );
+        println!("  NAME: {:?} -> {}", method.method.name_index, method.name);
         println!("  FLAGS: {:?}", method.access_flags);
         println!(
-            "  DESCRIPTOR: {:?} -> {}",
-            method.descriptor_index,
-            constant_pool.resolve_utf8(method.descriptor_index).unwrap()
+            "  DESCRIPTOR: {:?} -> {:?}",
+            method.method.descriptor_index, method.descriptor
         );
         println!("  ATTRIBUTES:");
-        for attribute in &method.attributes {
+        for attribute in &method.method.attributes {
             println!("    {:#?}", attribute);
         }
+
+        if let Some(code) = method.code {
+            println!("  CODE:");
+            let mut line = None;
+            for instruction in &code.code {
+                let instruction_line = code.line_for_pc(instruction.offset);
+                if instruction_line != line {
+                    if let Some(line_number) = instruction_line {
+                        println!("    LINE {}:", line_number);
+                    }
+                    line = instruction_line;
+                }
+                let locals = code
+                    .locals_at(instruction.offset)
+                    .map(|local| constant_pool.resolve_utf8(local.name_index))
+                    .collect::<JvmParseResult<Vec<_>>>()?;
+                println!(
+                    "      {:>5}: {:?} {:?}",
+                    instruction.offset, instruction.opcode, locals
+                );
+            }
+        }
     }
 
     println!("ATTRIBUTES:");
     for attribute in class_file.attributes() {
         println!("    {:#?}", attribute);
     }
+
+    Ok(())
 }
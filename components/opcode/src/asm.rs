@@ -0,0 +1,852 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{Bytecode, Instruction, Opcode, WideOpcode};
+
+#[derive(Debug, Clone)]
+pub enum AsmError {
+    /// Branch target does not refer to the offset of an instruction.
+    UnknownTarget(u16),
+    /// Label was never bound to an instruction.
+    UnboundLabel(u16),
+    /// Label was not created by the assembler it is used with.
+    UnknownLabel(u16),
+    /// Conditional branch at the given offset cannot reach its target with a
+    /// 16 bit offset.
+    BranchOutOfRange(u16),
+    /// Offset of an instruction is not greater than the one of the
+    /// instruction before.
+    InvalidOffset(u16),
+    /// More labels than can be referred to with 16 bit branch targets.
+    TooManyLabels,
+    CodeTooLong,
+}
+
+/// Type of a local variable for choosing load and store instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalKind {
+    Int,
+    Long,
+    Float,
+    Double,
+    Reference,
+}
+
+impl Opcode {
+    /// Shortest instruction loading the local variable `index` onto the stack.
+    pub fn load(kind: LocalKind, index: u16) -> Opcode {
+        match (kind, index) {
+            (LocalKind::Int, 0) => Opcode::Iload0,
+            (LocalKind::Int, 1) => Opcode::Iload1,
+            (LocalKind::Int, 2) => Opcode::Iload2,
+            (LocalKind::Int, 3) => Opcode::Iload3,
+            (LocalKind::Long, 0) => Opcode::Lload0,
+            (LocalKind::Long, 1) => Opcode::Lload1,
+            (LocalKind::Long, 2) => Opcode::Lload2,
+            (LocalKind::Long, 3) => Opcode::Lload3,
+            (LocalKind::Float, 0) => Opcode::Fload0,
+            (LocalKind::Float, 1) => Opcode::Fload1,
+            (LocalKind::Float, 2) => Opcode::Fload2,
+            (LocalKind::Float, 3) => Opcode::Fload3,
+            (LocalKind::Double, 0) => Opcode::Dload0,
+            (LocalKind::Double, 1) => Opcode::Dload1,
+            (LocalKind::Double, 2) => Opcode::Dload2,
+            (LocalKind::Double, 3) => Opcode::Dload3,
+            (LocalKind::Reference, 0) => Opcode::Aload0,
+            (LocalKind::Reference, 1) => Opcode::Aload1,
+            (LocalKind::Reference, 2) => Opcode::Aload2,
+            (LocalKind::Reference, 3) => Opcode::Aload3,
+            (kind, index) => match u8::try_from(index) {
+                Ok(index) => match kind {
+                    LocalKind::Int => Opcode::Iload(index),
+                    LocalKind::Long => Opcode::Lload(index),
+                    LocalKind::Float => Opcode::Fload(index),
+                    LocalKind::Double => Opcode::Dload(index),
+                    LocalKind::Reference => Opcode::Aload(index),
+                },
+                Err(_) => Opcode::Wide(match kind {
+                    LocalKind::Int => WideOpcode::Iload(index),
+                    LocalKind::Long => WideOpcode::Lload(index),
+                    LocalKind::Float => WideOpcode::Fload(index),
+                    LocalKind::Double => WideOpcode::Dload(index),
+                    LocalKind::Reference => WideOpcode::Aload(index),
+                }),
+            },
+        }
+    }
+
+    /// Shortest instruction storing the stack top into the local variable `index`.
+    pub fn store(kind: LocalKind, index: u16) -> Opcode {
+        match (kind, index) {
+            (LocalKind::Int, 0) => Opcode::Istore0,
+            (LocalKind::Int, 1) => Opcode::Istore1,
+            (LocalKind::Int, 2) => Opcode::Istore2,
+            (LocalKind::Int, 3) => Opcode::Istore3,
+            (LocalKind::Long, 0) => Opcode::Lstore0,
+            (LocalKind::Long, 1) => Opcode::Lstore1,
+            (LocalKind::Long, 2) => Opcode::Lstore2,
+            (LocalKind::Long, 3) => Opcode::Lstore3,
+            (LocalKind::Float, 0) => Opcode::Fstore0,
+            (LocalKind::Float, 1) => Opcode::Fstore1,
+            (LocalKind::Float, 2) => Opcode::Fstore2,
+            (LocalKind::Float, 3) => Opcode::Fstore3,
+            (LocalKind::Double, 0) => Opcode::Dstore0,
+            (LocalKind::Double, 1) => Opcode::Dstore1,
+            (LocalKind::Double, 2) => Opcode::Dstore2,
+            (LocalKind::Double, 3) => Opcode::Dstore3,
+            (LocalKind::Reference, 0) => Opcode::Astore0,
+            (LocalKind::Reference, 1) => Opcode::Astore1,
+            (LocalKind::Reference, 2) => Opcode::Astore2,
+            (LocalKind::Reference, 3) => Opcode::Astore3,
+            (kind, index) => match u8::try_from(index) {
+                Ok(index) => match kind {
+                    LocalKind::Int => Opcode::Istore(index),
+                    LocalKind::Long => Opcode::Lstore(index),
+                    LocalKind::Float => Opcode::Fstore(index),
+                    LocalKind::Double => Opcode::Dstore(index),
+                    LocalKind::Reference => Opcode::Astore(index),
+                },
+                Err(_) => Opcode::Wide(match kind {
+                    LocalKind::Int => WideOpcode::Istore(index),
+                    LocalKind::Long => WideOpcode::Lstore(index),
+                    LocalKind::Float => WideOpcode::Fstore(index),
+                    LocalKind::Double => WideOpcode::Dstore(index),
+                    LocalKind::Reference => WideOpcode::Astore(index),
+                }),
+            },
+        }
+    }
+
+    /// Shortest instruction incrementing the local variable `index` by `value`.
+    pub fn iinc(index: u16, value: i16) -> Opcode {
+        match (u8::try_from(index), i8::try_from(value)) {
+            (Ok(index), Ok(value)) => Opcode::Iinc(index, value),
+            _ => Opcode::Wide(WideOpcode::Iinc(index, value)),
+        }
+    }
+
+    /// Shortest instruction loading the single-word constant at `index`.
+    pub fn ldc(index: u16) -> Opcode {
+        match u8::try_from(index) {
+            Ok(index) => Opcode::Ldc(index),
+            Err(_) => Opcode::LdcW(index),
+        }
+    }
+}
+
+/// Label for a position in the code of an [`Assembler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(u16);
+
+impl Label {
+    /// Value to use as branch target in an [`Opcode`] to jump to this label.
+    pub fn target(self) -> u16 {
+        self.0
+    }
+}
+
+/// Builder for method code with symbolic branch targets.
+///
+/// Branch targets of pushed opcodes are [`Label`]s, which are resolved to
+/// offsets by [`Assembler::finish`].
+#[derive(Debug, Default)]
+pub struct Assembler {
+    opcodes: Vec<Opcode>,
+    labels: Vec<Option<usize>>,
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn new_label(&mut self) -> Result<Label, AsmError> {
+        let label = u16::try_from(self.labels.len()).map_err(|_| AsmError::TooManyLabels)?;
+        self.labels.push(None);
+        Ok(Label(label))
+    }
+
+    /// Binds the label to the next pushed instruction. A label bound after
+    /// the last instruction refers to the end of the code.
+    pub fn bind(&mut self, label: Label) -> Result<&mut Self, AsmError> {
+        let index = self.opcodes.len();
+        match self.labels.get_mut(label.0 as usize) {
+            Some(slot) => *slot = Some(index),
+            None => return Err(AsmError::UnknownLabel(label.0)),
+        }
+        Ok(self)
+    }
+
+    pub fn push(&mut self, opcode: Opcode) -> &mut Self {
+        self.opcodes.push(opcode);
+        self
+    }
+
+    /// Lays out the instructions and resolves the labels.
+    pub fn finish(self) -> Result<Bytecode, AsmError> {
+        if self.opcodes.len() > u16::MAX as usize {
+            return Err(AsmError::CodeTooLong);
+        }
+
+        let count = self.opcodes.len();
+        for opcode in &self.opcodes {
+            for label in opcode.branch_targets() {
+                match self.labels.get(label as usize) {
+                    Some(Some(index)) if *index <= count => {}
+                    _ => return Err(AsmError::UnboundLabel(label)),
+                }
+            }
+        }
+
+        // use instruction indices as preliminary offsets, and the instruction
+        // count for labels bound at the end
+        let labels = self.labels;
+        let instructions: Vec<Instruction> = self
+            .opcodes
+            .iter()
+            .enumerate()
+            .map(|(index, opcode)| Instruction {
+                offset: index as u16,
+                opcode: opcode.map_targets(|label| labels[label as usize].unwrap() as u16),
+            })
+            .collect();
+        Ok(Bytecode::new(layout(&instructions, Some(count as u16))?))
+    }
+}
+
+/// Encodes instructions into the code array of a method.
+///
+/// The offsets of the instructions only serve as branch targets and are
+/// recomputed. `goto` and `jsr` are widened to `goto_w` and `jsr_w` when the
+/// target is out of reach of a 16 bit offset. A target just past the last
+/// instruction refers to the end of the code.
+pub fn assemble(instructions: &[Instruction]) -> Result<Vec<u8>, AsmError> {
+    let end = instructions.last().and_then(|last| {
+        u16::try_from(last.offset as usize + last.opcode.encoded_len(last.offset as usize)).ok()
+    });
+    let mut bytes = vec![];
+    for instruction in layout(instructions, end)? {
+        encode(&instruction.opcode, instruction.offset, &mut bytes);
+    }
+    Ok(bytes)
+}
+
+fn fits_i16(from: usize, to: usize) -> bool {
+    i16::try_from(to as i64 - from as i64).is_ok()
+}
+
+/// Computes the final offsets of the instructions and resolves branch targets
+/// to them. Branches to `end` resolve to the length of the code.
+fn layout(instructions: &[Instruction], end: Option<u16>) -> Result<Vec<Instruction>, AsmError> {
+    for pair in instructions.windows(2) {
+        if pair[1].offset <= pair[0].offset {
+            return Err(AsmError::InvalidOffset(pair[1].offset));
+        }
+    }
+    let mut indices: HashMap<u16, usize> = instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| (instruction.offset, index))
+        .collect();
+    if let Some(end) = end {
+        indices.insert(end, instructions.len());
+    }
+    for instruction in instructions {
+        for target in instruction.opcode.branch_targets() {
+            if !indices.contains_key(&target) {
+                return Err(AsmError::UnknownTarget(target));
+            }
+        }
+    }
+
+    // widening only makes instructions longer, so this terminates
+    let mut widened = vec![false; instructions.len()];
+    let mut offsets = vec![0usize; instructions.len() + 1];
+    loop {
+        let mut offset = 0usize;
+        for (index, instruction) in instructions.iter().enumerate() {
+            offsets[index] = offset;
+            offset += if widened[index] {
+                5
+            } else {
+                instruction.opcode.encoded_len(offset)
+            };
+        }
+        offsets[instructions.len()] = offset;
+        if offset > u16::MAX as usize {
+            return Err(AsmError::CodeTooLong);
+        }
+
+        let mut changed = false;
+        for (index, instruction) in instructions.iter().enumerate() {
+            if let Opcode::Goto(target) | Opcode::Jsr(target) = instruction.opcode {
+                if !widened[index] && !fits_i16(offsets[index], offsets[indices[&target]]) {
+                    widened[index] = true;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let offset = offsets[index];
+            let opcode = match instruction.opcode {
+                Opcode::Goto(target) if widened[index] => Opcode::GotoW(target),
+                Opcode::Jsr(target) if widened[index] => Opcode::JsrW(target),
+                ref opcode => opcode.clone(),
+            };
+
+            let short_branch = !matches!(
+                opcode,
+                Opcode::GotoW(_)
+                    | Opcode::JsrW(_)
+                    | Opcode::Lookupswitch(_)
+                    | Opcode::Tableswitch(_)
+            );
+            for target in opcode.branch_targets() {
+                if short_branch && !fits_i16(offset, offsets[indices[&target]]) {
+                    return Err(AsmError::BranchOutOfRange(instruction.offset));
+                }
+            }
+
+            Ok(Instruction {
+                offset: offset as u16,
+                opcode: opcode.map_targets(|target| offsets[indices[&target]] as u16),
+            })
+        })
+        .collect()
+}
+
+fn encode(opcode: &Opcode, offset: u16, out: &mut Vec<u8>) {
+    let relative = |target: u16| target as i32 - offset as i32;
+
+    out.push(opcode.code());
+    match opcode {
+        Opcode::Aload(index)
+        | Opcode::Astore(index)
+        | Opcode::Dload(index)
+        | Opcode::Dstore(index)
+        | Opcode::Fload(index)
+        | Opcode::Fstore(index)
+        | Opcode::Iload(index)
+        | Opcode::Istore(index)
+        | Opcode::Ldc(index)
+        | Opcode::Lload(index)
+        | Opcode::Lstore(index)
+        | Opcode::Ret(index) => out.push(*index),
+        Opcode::Bipush(value) => out.push(*value as u8),
+        Opcode::Newarray(ty) => out.push(*ty as u8),
+        Opcode::Sipush(value) => out.extend_from_slice(&value.to_be_bytes()),
+        Opcode::Anewarray(index)
+        | Opcode::Checkcast(index)
+        | Opcode::Getfield(index)
+        | Opcode::Getstatic(index)
+        | Opcode::Instanceof(index)
+        | Opcode::Invokespecial(index)
+        | Opcode::Invokestatic(index)
+        | Opcode::Invokevirtual(index)
+        | Opcode::LdcW(index)
+        | Opcode::Ldc2W(index)
+        | Opcode::New(index)
+        | Opcode::Putfield(index)
+        | Opcode::Putstatic(index) => out.extend_from_slice(&index.to_be_bytes()),
+        Opcode::Iinc(index, value) => {
+            out.push(*index);
+            out.push(*value as u8);
+        }
+        Opcode::Invokedynamic(index) => {
+            out.extend_from_slice(&index.to_be_bytes());
+            out.extend_from_slice(&[0, 0]);
+        }
+        Opcode::Invokeinterface(index, count) => {
+            out.extend_from_slice(&index.to_be_bytes());
+            out.push(*count);
+            out.push(0);
+        }
+        Opcode::Multianewarray(index, dimensions) => {
+            out.extend_from_slice(&index.to_be_bytes());
+            out.push(*dimensions);
+        }
+        Opcode::Goto(target)
+        | Opcode::IfAcmpeq(target)
+        | Opcode::IfAcmpne(target)
+        | Opcode::IfIcmpeq(target)
+        | Opcode::IfIcmpge(target)
+        | Opcode::IfIcmpgt(target)
+        | Opcode::IfIcmple(target)
+        | Opcode::IfIcmplt(target)
+        | Opcode::IfIcmpne(target)
+        | Opcode::Ifeq(target)
+        | Opcode::Ifge(target)
+        | Opcode::Ifgt(target)
+        | Opcode::Ifle(target)
+        | Opcode::Iflt(target)
+        | Opcode::Ifne(target)
+        | Opcode::Ifnonnull(target)
+        | Opcode::Ifnull(target)
+        | Opcode::Jsr(target) => out.extend_from_slice(&(relative(*target) as i16).to_be_bytes()),
+        Opcode::GotoW(target) | Opcode::JsrW(target) => {
+            out.extend_from_slice(&relative(*target).to_be_bytes())
+        }
+        Opcode::Lookupswitch(lookup) => {
+            out.resize(out.len() + (4 - (offset as usize + 1) % 4) % 4, 0);
+            out.extend_from_slice(&relative(lookup.default).to_be_bytes());
+            out.extend_from_slice(&(lookup.pairs.len() as i32).to_be_bytes());
+            for (key, target) in &lookup.pairs {
+                out.extend_from_slice(&key.to_be_bytes());
+                out.extend_from_slice(&relative(*target).to_be_bytes());
+            }
+        }
+        Opcode::Tableswitch(table) => {
+            out.resize(out.len() + (4 - (offset as usize + 1) % 4) % 4, 0);
+            out.extend_from_slice(&relative(table.default).to_be_bytes());
+            out.extend_from_slice(&table.low.to_be_bytes());
+            out.extend_from_slice(&table.high.to_be_bytes());
+            for target in &table.offsets {
+                out.extend_from_slice(&relative(*target).to_be_bytes());
+            }
+        }
+        Opcode::Wide(wide) => match wide {
+            WideOpcode::Iload(index) => encode_wide(0x15, *index, out),
+            WideOpcode::Lload(index) => encode_wide(0x16, *index, out),
+            WideOpcode::Fload(index) => encode_wide(0x17, *index, out),
+            WideOpcode::Dload(index) => encode_wide(0x18, *index, out),
+            WideOpcode::Aload(index) => encode_wide(0x19, *index, out),
+            WideOpcode::Istore(index) => encode_wide(0x36, *index, out),
+            WideOpcode::Lstore(index) => encode_wide(0x37, *index, out),
+            WideOpcode::Fstore(index) => encode_wide(0x38, *index, out),
+            WideOpcode::Dstore(index) => encode_wide(0x39, *index, out),
+            WideOpcode::Astore(index) => encode_wide(0x3a, *index, out),
+            WideOpcode::Ret(index) => encode_wide(0xa9, *index, out),
+            WideOpcode::Iinc(index, value) => {
+                encode_wide(0x84, *index, out);
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        },
+        _ => {}
+    }
+}
+
+fn encode_wide(code: u8, index: u16, out: &mut Vec<u8>) {
+    out.push(code);
+    out.extend_from_slice(&index.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{disasm, ArrayType, LookupSwitch, TableSwitch};
+
+    fn every_opcode(start: Label, end: Label) -> Vec<Opcode> {
+        vec![
+            Opcode::Aaload,
+            Opcode::Aastore,
+            Opcode::AconstNull,
+            Opcode::Aload(4),
+            Opcode::Aload0,
+            Opcode::Aload1,
+            Opcode::Aload2,
+            Opcode::Aload3,
+            Opcode::Anewarray(0x0102),
+            Opcode::Areturn,
+            Opcode::Arraylength,
+            Opcode::Astore(5),
+            Opcode::Astore0,
+            Opcode::Astore1,
+            Opcode::Astore2,
+            Opcode::Astore3,
+            Opcode::Athrow,
+            Opcode::Baload,
+            Opcode::Bastore,
+            Opcode::Bipush(-100),
+            Opcode::Breakpoint,
+            Opcode::Caload,
+            Opcode::Castore,
+            Opcode::Checkcast(0x0203),
+            Opcode::D2f,
+            Opcode::D2i,
+            Opcode::D2l,
+            Opcode::Dadd,
+            Opcode::Daload,
+            Opcode::Dastore,
+            Opcode::Dcmpg,
+            Opcode::Dcmpl,
+            Opcode::Dconst0,
+            Opcode::Dconst1,
+            Opcode::Ddiv,
+            Opcode::Dload(6),
+            Opcode::Dload0,
+            Opcode::Dload1,
+            Opcode::Dload2,
+            Opcode::Dload3,
+            Opcode::Dmul,
+            Opcode::Dneg,
+            Opcode::Drem,
+            Opcode::Dreturn,
+            Opcode::Dstore(7),
+            Opcode::Dstore0,
+            Opcode::Dstore1,
+            Opcode::Dstore2,
+            Opcode::Dstore3,
+            Opcode::Dsub,
+            Opcode::Dup,
+            Opcode::DupX1,
+            Opcode::DupX2,
+            Opcode::Dup2,
+            Opcode::Dup2X1,
+            Opcode::Dup2X2,
+            Opcode::F2d,
+            Opcode::F2i,
+            Opcode::F2l,
+            Opcode::Fadd,
+            Opcode::Faload,
+            Opcode::Fastore,
+            Opcode::Fcmpg,
+            Opcode::Fcmpl,
+            Opcode::Fconst0,
+            Opcode::Fconst1,
+            Opcode::Fconst2,
+            Opcode::Fdiv,
+            Opcode::Fload(8),
+            Opcode::Fload0,
+            Opcode::Fload1,
+            Opcode::Fload2,
+            Opcode::Fload3,
+            Opcode::Fmul,
+            Opcode::Fneg,
+            Opcode::Frem,
+            Opcode::Freturn,
+            Opcode::Fstore(9),
+            Opcode::Fstore0,
+            Opcode::Fstore1,
+            Opcode::Fstore2,
+            Opcode::Fstore3,
+            Opcode::Fsub,
+            Opcode::Getfield(0x0304),
+            Opcode::Getstatic(0x0405),
+            Opcode::Goto(start.target()),
+            Opcode::GotoW(end.target()),
+            Opcode::I2b,
+            Opcode::I2c,
+            Opcode::I2d,
+            Opcode::I2f,
+            Opcode::I2l,
+            Opcode::I2s,
+            Opcode::Iadd,
+            Opcode::Iaload,
+            Opcode::Iand,
+            Opcode::Iastore,
+            Opcode::IconstM1,
+            Opcode::Iconst0,
+            Opcode::Iconst1,
+            Opcode::Iconst2,
+            Opcode::Iconst3,
+            Opcode::Iconst4,
+            Opcode::Iconst5,
+            Opcode::Idiv,
+            Opcode::IfAcmpeq(start.target()),
+            Opcode::IfAcmpne(end.target()),
+            Opcode::IfIcmpeq(start.target()),
+            Opcode::IfIcmpge(end.target()),
+            Opcode::IfIcmpgt(start.target()),
+            Opcode::IfIcmple(end.target()),
+            Opcode::IfIcmplt(start.target()),
+            Opcode::IfIcmpne(end.target()),
+            Opcode::Ifeq(start.target()),
+            Opcode::Ifge(end.target()),
+            Opcode::Ifgt(start.target()),
+            Opcode::Ifle(end.target()),
+            Opcode::Iflt(start.target()),
+            Opcode::Ifne(end.target()),
+            Opcode::Ifnonnull(start.target()),
+            Opcode::Ifnull(end.target()),
+            Opcode::Iinc(3, -7),
+            Opcode::Iload(10),
+            Opcode::Iload0,
+            Opcode::Iload1,
+            Opcode::Iload2,
+            Opcode::Iload3,
+            Opcode::Impdep1,
+            Opcode::Impdep2,
+            Opcode::Imul,
+            Opcode::Ineg,
+            Opcode::Instanceof(0x0506),
+            Opcode::Invokedynamic(0x0607),
+            Opcode::Invokeinterface(0x1011, 2),
+            Opcode::Invokespecial(0x0708),
+            Opcode::Invokestatic(0x0809),
+            Opcode::Invokevirtual(0x090a),
+            Opcode::Ior,
+            Opcode::Irem,
+            Opcode::Ireturn,
+            Opcode::Ishl,
+            Opcode::Ishr,
+            Opcode::Istore(11),
+            Opcode::Istore0,
+            Opcode::Istore1,
+            Opcode::Istore2,
+            Opcode::Istore3,
+            Opcode::Isub,
+            Opcode::Iushr,
+            Opcode::Ixor,
+            Opcode::Jsr(end.target()),
+            Opcode::JsrW(start.target()),
+            Opcode::L2d,
+            Opcode::L2f,
+            Opcode::L2i,
+            Opcode::Ladd,
+            Opcode::Laload,
+            Opcode::Land,
+            Opcode::Lastore,
+            Opcode::Lcmp,
+            Opcode::Lconst0,
+            Opcode::Lconst1,
+            Opcode::Ldc(14),
+            Opcode::LdcW(0x0a0b),
+            Opcode::Ldc2W(0x0b0c),
+            Opcode::Ldiv,
+            Opcode::Lload(12),
+            Opcode::Lload0,
+            Opcode::Lload1,
+            Opcode::Lload2,
+            Opcode::Lload3,
+            Opcode::Lmul,
+            Opcode::Lneg,
+            Opcode::Lookupswitch(LookupSwitch {
+                default: start.target(),
+                pairs: vec![(-1, start.target()), (7, end.target())],
+            }),
+            Opcode::Lor,
+            Opcode::Lrem,
+            Opcode::Lreturn,
+            Opcode::Lshl,
+            Opcode::Lshr,
+            Opcode::Lstore(13),
+            Opcode::Lstore0,
+            Opcode::Lstore1,
+            Opcode::Lstore2,
+            Opcode::Lstore3,
+            Opcode::Lsub,
+            Opcode::Lushr,
+            Opcode::Lxor,
+            Opcode::Monitorenter,
+            Opcode::Monitorexit,
+            Opcode::Multianewarray(0x1213, 3),
+            Opcode::New(0x0c0d),
+            Opcode::Newarray(ArrayType::LONG),
+            Opcode::Nop,
+            Opcode::Pop,
+            Opcode::Pop2,
+            Opcode::Putfield(0x0d0e),
+            Opcode::Putstatic(0x0e0f),
+            Opcode::Ret(15),
+            Opcode::Return,
+            Opcode::Saload,
+            Opcode::Sastore,
+            Opcode::Sipush(-30000),
+            Opcode::Swap,
+            Opcode::Tableswitch(TableSwitch {
+                default: end.target(),
+                low: -1,
+                high: 1,
+                offsets: vec![start.target(), end.target(), start.target()],
+            }),
+            Opcode::Wide(WideOpcode::Iload(256)),
+            Opcode::Wide(WideOpcode::Lload(257)),
+            Opcode::Wide(WideOpcode::Fload(258)),
+            Opcode::Wide(WideOpcode::Dload(259)),
+            Opcode::Wide(WideOpcode::Aload(260)),
+            Opcode::Wide(WideOpcode::Istore(261)),
+            Opcode::Wide(WideOpcode::Lstore(262)),
+            Opcode::Wide(WideOpcode::Fstore(263)),
+            Opcode::Wide(WideOpcode::Dstore(264)),
+            Opcode::Wide(WideOpcode::Astore(265)),
+            Opcode::Wide(WideOpcode::Ret(266)),
+            Opcode::Wide(WideOpcode::Iinc(267, -300)),
+        ]
+    }
+
+    #[test]
+    fn roundtrip_every_opcode() {
+        let mut asm = Assembler::new();
+        let start = asm.new_label().unwrap();
+        let end = asm.new_label().unwrap();
+        asm.bind(start).unwrap();
+        for opcode in every_opcode(start, end) {
+            asm.push(opcode);
+        }
+        asm.bind(end).unwrap().push(Opcode::Return);
+        let bytecode = asm.finish().unwrap();
+
+        let bytes = assemble(bytecode.instructions()).unwrap();
+        let disassembled = disasm(&bytes).unwrap();
+        assert_eq!(disassembled, bytecode);
+        for pair in bytecode.instructions().windows(2) {
+            let offset = pair[0].offset as usize;
+            assert_eq!(
+                offset + pair[0].opcode.encoded_len(offset),
+                pair[1].offset as usize
+            );
+        }
+        assert_eq!(assemble(disassembled.instructions()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn roundtrip_bytes() {
+        let code = [
+            0x2b, // aload_1
+            0xb3, 0x00, 0x01, // putstatic #1
+            0xa9, 0x02, // ret 2
+            0x84, 0x01, 0xff, // iinc 1 -1
+            0xc8, 0xff, 0xff, 0xff, 0xf7, // goto_w -9
+        ];
+        let bytecode = disasm(&code).unwrap();
+        let opcodes: Vec<Opcode> = bytecode.iter().map(|i| i.opcode.clone()).collect();
+        assert_eq!(
+            opcodes,
+            vec![
+                Opcode::Aload1,
+                Opcode::Putstatic(1),
+                Opcode::Ret(2),
+                Opcode::Iinc(1, -1),
+                Opcode::GotoW(0)
+            ]
+        );
+        assert_eq!(assemble(bytecode.instructions()).unwrap(), code);
+    }
+
+    #[test]
+    fn switch_padding() {
+        let mut asm = Assembler::new();
+        let target = asm.new_label().unwrap();
+        asm.push(Opcode::Iconst0)
+            .push(Opcode::Iconst1)
+            .push(Opcode::Tableswitch(TableSwitch {
+                default: target.target(),
+                low: 0,
+                high: 0,
+                offsets: vec![target.target()],
+            }))
+            .bind(target)
+            .unwrap()
+            .push(Opcode::Return);
+        let bytecode = asm.finish().unwrap();
+        assert_eq!(bytecode.get(3).unwrap().offset, 20);
+
+        let bytes = assemble(bytecode.instructions()).unwrap();
+        assert_eq!(
+            &bytes[..12],
+            &[0x03, 0x04, 0xaa, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn widen_goto() {
+        let mut asm = Assembler::new();
+        let end = asm.new_label().unwrap();
+        asm.push(Opcode::Goto(end.target()));
+        for _ in 0..40000 {
+            asm.push(Opcode::Nop);
+        }
+        asm.bind(end).unwrap().push(Opcode::Return);
+
+        let bytecode = asm.finish().unwrap();
+        assert_eq!(bytecode.get(0).unwrap().opcode, Opcode::GotoW(40005));
+        assert_eq!(bytecode.get(40001).unwrap().offset, 40005);
+    }
+
+    #[test]
+    fn conditional_branch_out_of_range() {
+        let mut asm = Assembler::new();
+        let end = asm.new_label().unwrap();
+        asm.push(Opcode::Iconst0).push(Opcode::Ifeq(end.target()));
+        for _ in 0..40000 {
+            asm.push(Opcode::Nop);
+        }
+        asm.bind(end).unwrap().push(Opcode::Return);
+
+        assert!(matches!(asm.finish(), Err(AsmError::BranchOutOfRange(1))));
+    }
+
+    #[test]
+    fn unbound_label() {
+        let mut asm = Assembler::new();
+        let label = asm.new_label().unwrap();
+        asm.push(Opcode::Goto(label.target()));
+        assert!(matches!(asm.finish(), Err(AsmError::UnboundLabel(0))));
+    }
+
+    #[test]
+    fn unknown_label() {
+        let mut other = Assembler::new();
+        other.new_label().unwrap();
+        let label = other.new_label().unwrap();
+        let mut asm = Assembler::new();
+        asm.new_label().unwrap();
+        assert!(matches!(asm.bind(label), Err(AsmError::UnknownLabel(1))));
+    }
+
+    #[test]
+    fn label_at_end() {
+        let mut asm = Assembler::new();
+        let end = asm.new_label().unwrap();
+        asm.push(Opcode::Iconst0)
+            .push(Opcode::Ifeq(end.target()))
+            .push(Opcode::Return)
+            .bind(end)
+            .unwrap();
+        let bytecode = asm.finish().unwrap();
+        assert_eq!(bytecode.get(1).unwrap().opcode, Opcode::Ifeq(5));
+        assert_eq!(assemble(bytecode.instructions()).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn duplicate_offsets() {
+        let instructions = [
+            Instruction {
+                offset: 0,
+                opcode: Opcode::Goto(1),
+            },
+            Instruction {
+                offset: 1,
+                opcode: Opcode::Nop,
+            },
+            Instruction {
+                offset: 1,
+                opcode: Opcode::Return,
+            },
+        ];
+        assert!(matches!(
+            assemble(&instructions),
+            Err(AsmError::InvalidOffset(1))
+        ));
+    }
+
+    #[test]
+    fn too_many_labels() {
+        let mut asm = Assembler::new();
+        for _ in 0..=u16::MAX {
+            asm.new_label().unwrap();
+        }
+        assert!(matches!(asm.new_label(), Err(AsmError::TooManyLabels)));
+    }
+
+    #[test]
+    fn shortest_forms() {
+        assert_eq!(Opcode::load(LocalKind::Int, 2), Opcode::Iload2);
+        assert_eq!(Opcode::load(LocalKind::Reference, 4), Opcode::Aload(4));
+        assert_eq!(
+            Opcode::store(LocalKind::Double, 300),
+            Opcode::Wide(WideOpcode::Dstore(300))
+        );
+        assert_eq!(Opcode::iinc(1, 5), Opcode::Iinc(1, 5));
+        assert_eq!(Opcode::iinc(1, 500), Opcode::Wide(WideOpcode::Iinc(1, 500)));
+        assert_eq!(Opcode::ldc(300), Opcode::LdcW(300));
+    }
+}
@@ -0,0 +1,77 @@
+use std::slice;
+
+use crate::Opcode;
+
+/// Decoded instruction together with its position in the code array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// Byte offset of the opcode within the code array.
+    pub offset: u16,
+    pub opcode: Opcode,
+}
+
+/// Instruction stream of a method body.
+///
+/// Instructions are ordered by their offset, so that branch targets, exception
+/// handler ranges and line numbers can be mapped to instructions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bytecode {
+    instructions: Vec<Instruction>,
+}
+
+impl Bytecode {
+    /// Creates a bytecode from instructions which must be ordered by offset.
+    /// Only the assembler and disassembler build bytecode, so the order holds.
+    pub(crate) fn new(instructions: Vec<Instruction>) -> Self {
+        debug_assert!(instructions
+            .windows(2)
+            .all(|pair| pair[0].offset < pair[1].offset));
+        Self { instructions }
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn into_instructions(self) -> Vec<Instruction> {
+        self.instructions
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Instruction> {
+        self.instructions.iter()
+    }
+
+    /// Instruction at the given index.
+    pub fn get(&self, index: usize) -> Option<&Instruction> {
+        self.instructions.get(index)
+    }
+
+    /// Index of the instruction starting at the given byte offset.
+    pub fn index_of(&self, offset: u16) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&offset, |instruction| instruction.offset)
+            .ok()
+    }
+
+    /// Instruction starting at the given byte offset.
+    pub fn at(&self, offset: u16) -> Option<&Instruction> {
+        self.index_of(offset).map(|index| &self.instructions[index])
+    }
+}
+
+impl<'a> IntoIterator for &'a Bytecode {
+    type Item = &'a Instruction;
+    type IntoIter = slice::Iter<'a, Instruction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instructions.iter()
+    }
+}
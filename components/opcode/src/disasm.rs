@@ -1,17 +1,60 @@
-use crate::{ArrayType, Opcode};
+use std::error::Error;
+use std::fmt;
 
-#[derive(Debug, Clone)]
-pub enum DisasmError {
-    UnknownOpcode(u8),
+use crate::{ArrayType, Bytecode, Instruction, LookupSwitch, Opcode, TableSwitch, WideOpcode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisasmErrorKind {
+    UnknownOpcode,
     MissingArgument,
     InvalidArgument,
     InvalidArrayType,
+    /// Opcode following `wide` which can not be widened.
+    InvalidWideOpcode(u8),
+    InvalidSwitchTable,
+    InvalidBranchTarget,
+    CodeTooLong,
+}
+
+/// Error while disassembling the instruction at `pc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisasmError {
+    pub kind: DisasmErrorKind,
+    /// Offset of the failing instruction. For `CodeTooLong` this is the
+    /// first offset beyond the limit.
+    pub pc: usize,
+    /// Opcode byte of the failing instruction, `None` for `CodeTooLong`.
+    pub opcode: Option<u8>,
+}
+
+impl fmt::Display for DisasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DisasmErrorKind::UnknownOpcode => f.write_str("unknown opcode")?,
+            DisasmErrorKind::MissingArgument => f.write_str("missing argument")?,
+            DisasmErrorKind::InvalidArgument => f.write_str("invalid argument")?,
+            DisasmErrorKind::InvalidArrayType => f.write_str("invalid array type")?,
+            DisasmErrorKind::InvalidWideOpcode(opcode) => {
+                write!(f, "invalid wide opcode {:#04x}", opcode)?
+            }
+            DisasmErrorKind::InvalidSwitchTable => f.write_str("invalid switch table")?,
+            DisasmErrorKind::InvalidBranchTarget => f.write_str("invalid branch target")?,
+            DisasmErrorKind::CodeTooLong => f.write_str("code too long")?,
+        }
+        match self.opcode {
+            Some(opcode) => write!(f, " at pc {} (opcode {:#04x})", self.pc, opcode),
+            None => Ok(()),
+        }
+    }
 }
 
+impl Error for DisasmError {}
+
 struct Disasm<'a> {
     bytes: &'a [u8],
     index: usize,
-    opcodes: Vec<Opcode>,
+    start: usize,
+    instructions: Vec<Instruction>,
 }
 
 fn compose_u16(one: u8, two: u8) -> u16 {
@@ -19,10 +62,10 @@ fn compose_u16(one: u8, two: u8) -> u16 {
 }
 
 fn compose_u32(one: u8, two: u8, three: u8, four: u8) -> u32 {
-    ((one as u32) << 8u16) | ((two as u32) << 8u16) | ((three as u32) << 8u16) | (four as u32)
+    ((one as u32) << 24u32) | ((two as u32) << 16u32) | ((three as u32) << 8u32) | (four as u32)
 }
 
-fn parse_array_type(index: u8) -> Result<ArrayType, DisasmError> {
+fn parse_array_type(index: u8) -> Result<ArrayType, DisasmErrorKind> {
     Ok(match index {
         4 => ArrayType::BOOLEAN,
         5 => ArrayType::CHAR,
@@ -32,7 +75,7 @@ fn parse_array_type(index: u8) -> Result<ArrayType, DisasmError> {
         9 => ArrayType::SHORT,
         10 => ArrayType::INT,
         11 => ArrayType::LONG,
-        _ => return Err(DisasmError::InvalidArrayType),
+        _ => return Err(DisasmErrorKind::InvalidArrayType),
     })
 }
 
@@ -41,20 +84,21 @@ impl<'a> Disasm<'a> {
         Self {
             bytes,
             index: 0,
-            opcodes: vec![],
+            start: 0,
+            instructions: vec![],
         }
     }
 
-    fn argument(&mut self) -> Result<u8, DisasmError> {
+    fn argument(&mut self) -> Result<u8, DisasmErrorKind> {
         self.index += 1;
         if self.index < self.bytes.len() {
             Ok(self.bytes[self.index])
         } else {
-            Err(DisasmError::MissingArgument)
+            Err(DisasmErrorKind::MissingArgument)
         }
     }
 
-    fn argument_u16(&mut self) -> Result<u16, DisasmError> {
+    fn argument_u16(&mut self) -> Result<u16, DisasmErrorKind> {
         self.index += 2;
         if self.index < self.bytes.len() {
             Ok(compose_u16(
@@ -62,11 +106,11 @@ impl<'a> Disasm<'a> {
                 self.bytes[self.index],
             ))
         } else {
-            Err(DisasmError::MissingArgument)
+            Err(DisasmErrorKind::MissingArgument)
         }
     }
 
-    fn argument_u32(&mut self) -> Result<u32, DisasmError> {
+    fn argument_u32(&mut self) -> Result<u32, DisasmErrorKind> {
         self.index += 4;
         if self.index < self.bytes.len() {
             Ok(compose_u32(
@@ -76,234 +120,572 @@ impl<'a> Disasm<'a> {
                 self.bytes[self.index],
             ))
         } else {
-            Err(DisasmError::MissingArgument)
+            Err(DisasmErrorKind::MissingArgument)
+        }
+    }
+
+    fn argument_i32(&mut self) -> Result<i32, DisasmErrorKind> {
+        Ok(self.argument_u32()? as i32)
+    }
+
+    /// Absolute target of a branch relative to the current instruction.
+    fn target(&self, relative: i32) -> Result<u16, DisasmErrorKind> {
+        let target = self.start as i64 + relative as i64;
+        if target < 0 || target >= self.bytes.len() as i64 {
+            Err(DisasmErrorKind::InvalidBranchTarget)
+        } else {
+            Ok(target as u16)
         }
     }
 
+    fn branch(&mut self) -> Result<u16, DisasmErrorKind> {
+        let relative = self.argument_u16()? as i16;
+        self.target(relative as i32)
+    }
+
+    fn branch_wide(&mut self) -> Result<u16, DisasmErrorKind> {
+        let relative = self.argument_i32()?;
+        self.target(relative)
+    }
+
+    /// Number of bytes after the current position.
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.index - 1
+    }
+
+    /// Skip the padding so that the next argument starts at an address that
+    /// is a multiple of four.
+    fn skip_padding(&mut self) -> Result<(), DisasmErrorKind> {
+        let padding = (4 - (self.index + 1) % 4) % 4;
+        if padding > self.remaining() {
+            return Err(DisasmErrorKind::MissingArgument);
+        }
+        self.index += padding;
+        Ok(())
+    }
+
+    fn lookupswitch(&mut self) -> Result<LookupSwitch, DisasmErrorKind> {
+        self.skip_padding()?;
+        let default = self.branch_wide()?;
+        let npairs = self.argument_i32()?;
+        if npairs < 0 || npairs as usize > self.remaining() / 8 {
+            return Err(DisasmErrorKind::InvalidSwitchTable);
+        }
+
+        let mut pairs: Vec<(i32, u16)> = Vec::with_capacity(npairs as usize);
+        for _ in 0..npairs {
+            let key = self.argument_i32()?;
+            if let Some((last_key, _)) = pairs.last() {
+                if *last_key >= key {
+                    return Err(DisasmErrorKind::InvalidSwitchTable);
+                }
+            }
+            pairs.push((key, self.branch_wide()?));
+        }
+
+        Ok(LookupSwitch { default, pairs })
+    }
+
+    fn tableswitch(&mut self) -> Result<TableSwitch, DisasmErrorKind> {
+        self.skip_padding()?;
+        let default = self.branch_wide()?;
+        let low = self.argument_i32()?;
+        let high = self.argument_i32()?;
+        if low > high {
+            return Err(DisasmErrorKind::InvalidSwitchTable);
+        }
+
+        let count = (high as i64 - low as i64 + 1) as usize;
+        if count > self.remaining() / 4 {
+            return Err(DisasmErrorKind::InvalidSwitchTable);
+        }
+
+        let offsets = (0..count)
+            .map(|_| self.branch_wide())
+            .collect::<Result<Vec<u16>, DisasmErrorKind>>()?;
+        Ok(TableSwitch {
+            default,
+            low,
+            high,
+            offsets,
+        })
+    }
+
+    fn wide(&mut self) -> Result<WideOpcode, DisasmErrorKind> {
+        Ok(match self.argument()? {
+            0x15 => WideOpcode::Iload(self.argument_u16()?),
+            0x16 => WideOpcode::Lload(self.argument_u16()?),
+            0x17 => WideOpcode::Fload(self.argument_u16()?),
+            0x18 => WideOpcode::Dload(self.argument_u16()?),
+            0x19 => WideOpcode::Aload(self.argument_u16()?),
+            0x36 => WideOpcode::Istore(self.argument_u16()?),
+            0x37 => WideOpcode::Lstore(self.argument_u16()?),
+            0x38 => WideOpcode::Fstore(self.argument_u16()?),
+            0x39 => WideOpcode::Dstore(self.argument_u16()?),
+            0x3a => WideOpcode::Astore(self.argument_u16()?),
+            0xa9 => WideOpcode::Ret(self.argument_u16()?),
+            0x84 => WideOpcode::Iinc(self.argument_u16()?, self.argument_u16()? as i16),
+            tag => return Err(DisasmErrorKind::InvalidWideOpcode(tag)),
+        })
+    }
+
     pub fn process(&mut self) -> Result<(), DisasmError> {
         while self.index < self.bytes.len() {
-            let opcode = match self.bytes[self.index] {
-                0x32 => Opcode::Aaload,
-                0x53 => Opcode::Aastore,
-                0x01 => Opcode::AconstNull,
-                0x19 => Opcode::Aload(self.argument()?),
-                0x2a => Opcode::Aload0,
-                0x2b => Opcode::Aload0,
-                0x2c => Opcode::Aload2,
-                0x2d => Opcode::Aload3,
-                0xbd => Opcode::Anewarray(self.argument_u16()?),
-                0xb0 => Opcode::Areturn,
-                0xbe => Opcode::Arraylength,
-                0x3a => Opcode::Astore(self.argument()?),
-                0x4b => Opcode::Astore0,
-                0x4c => Opcode::Astore1,
-                0x4d => Opcode::Astore2,
-                0x4e => Opcode::Astore3,
-                0xbf => Opcode::Athrow,
-                0x33 => Opcode::Baload,
-                0x54 => Opcode::Bastore,
-                0x10 => Opcode::Bipush(self.argument()? as i8),
-                0x34 => Opcode::Caload,
-                0x55 => Opcode::Castore,
-                0xc0 => Opcode::Checkcast(self.argument_u16()?),
-                0x90 => Opcode::D2f,
-                0x8e => Opcode::D2i,
-                0x8f => Opcode::D2l,
-                0x63 => Opcode::Dadd,
-                0x31 => Opcode::Daload,
-                0x52 => Opcode::Dastore,
-                0x98 => Opcode::Dcmpg,
-                0x97 => Opcode::Dcmpl,
-                0x0e => Opcode::Dconst0,
-                0x0f => Opcode::Dconst1,
-                0x6f => Opcode::Ddiv,
-                0x18 => Opcode::Dload(self.argument()?),
-                0x26 => Opcode::Dload0,
-                0x27 => Opcode::Dload1,
-                0x28 => Opcode::Dload2,
-                0x29 => Opcode::Dload3,
-                0x6b => Opcode::Dmul,
-                0x77 => Opcode::Dneg,
-                0x73 => Opcode::Drem,
-                0xaf => Opcode::Dreturn,
-                0x39 => Opcode::Dstore(self.argument()?),
-                0x47 => Opcode::Dstore0,
-                0x48 => Opcode::Dstore1,
-                0x49 => Opcode::Dstore2,
-                0x4a => Opcode::Dstore3,
-                0x67 => Opcode::Dsub,
-                0x59 => Opcode::Dup,
-                0x5a => Opcode::DupX1,
-                0x5b => Opcode::DupX2,
-                0x5c => Opcode::Dup2,
-                0x5d => Opcode::Dup2X1,
-                0x5e => Opcode::Dup2X2,
-                0x8d => Opcode::F2d,
-                0x8b => Opcode::F2i,
-                0x8c => Opcode::F2l,
-                0x62 => Opcode::Fadd,
-                0x30 => Opcode::Faload,
-                0x51 => Opcode::Fastore,
-                0x96 => Opcode::Fcmpg,
-                0x95 => Opcode::Fcmpl,
-                0x0b => Opcode::Fconst0,
-                0x0c => Opcode::Fconst1,
-                0x0d => Opcode::Fconst2,
-                0x6e => Opcode::Fdiv,
-                0x17 => Opcode::Fload(self.argument()?),
-                0x22 => Opcode::Fload0,
-                0x23 => Opcode::Fload1,
-                0x24 => Opcode::Fload2,
-                0x25 => Opcode::Fload3,
-                0x6a => Opcode::Fmul,
-                0x76 => Opcode::Fneg,
-                0x72 => Opcode::Frem,
-                0xae => Opcode::Freturn,
-                0x38 => Opcode::Fstore(self.argument()?),
-                0x43 => Opcode::Fstore0,
-                0x44 => Opcode::Fstore1,
-                0x45 => Opcode::Fstore2,
-                0x46 => Opcode::Fstore3,
-                0x66 => Opcode::Fsub,
-                0xb4 => Opcode::Getfield(self.argument_u16()?),
-                0xa7 => Opcode::Goto(self.argument_u16()?),
-                0xc8 => Opcode::GotoW(self.argument_u32()?),
-                0x91 => Opcode::I2b,
-                0x92 => Opcode::I2c,
-                0x87 => Opcode::I2d,
-                0x86 => Opcode::I2f,
-                0x85 => Opcode::I2l,
-                0x93 => Opcode::I2s,
-                0x60 => Opcode::Iadd,
-                0x2e => Opcode::Iaload,
-                0x7e => Opcode::Iand,
-                0x4f => Opcode::Iastore,
-                0x02 => Opcode::IconstM1,
-                0x03 => Opcode::Iconst0,
-                0x04 => Opcode::Iconst1,
-                0x05 => Opcode::Iconst2,
-                0x06 => Opcode::Iconst3,
-                0x07 => Opcode::Iconst4,
-                0x08 => Opcode::Iconst5,
-                0x6c => Opcode::Idiv,
-                0xa5 => Opcode::IfAcmpeq(self.argument_u16()?),
-                0xa6 => Opcode::IfAcmpne(self.argument_u16()?),
-                0x9f => Opcode::IfIcmpeq(self.argument_u16()?),
-                0xa0 => Opcode::IfIcmpne(self.argument_u16()?),
-                0xa1 => Opcode::IfIcmplt(self.argument_u16()?),
-                0xa2 => Opcode::IfIcmpge(self.argument_u16()?),
-                0xa3 => Opcode::IfIcmpgt(self.argument_u16()?),
-                0xa4 => Opcode::IfIcmple(self.argument_u16()?),
-                0x99 => Opcode::Ifeq(self.argument_u16()?),
-                0x9a => Opcode::Ifne(self.argument_u16()?),
-                0x9b => Opcode::Iflt(self.argument_u16()?),
-                0x9c => Opcode::Ifge(self.argument_u16()?),
-                0x9d => Opcode::Ifgt(self.argument_u16()?),
-                0x9e => Opcode::Ifle(self.argument_u16()?),
-                0xc7 => Opcode::Ifnonnull(self.argument_u16()?),
-                0xc6 => Opcode::Ifnull(self.argument_u16()?),
-                0x84 => Opcode::Iinc(self.argument()?, self.argument()?),
-                0x15 => Opcode::Iload(self.argument()?),
-                0x1a => Opcode::Iload0,
-                0x1b => Opcode::Iload1,
-                0x1c => Opcode::Iload2,
-                0x1d => Opcode::Iload3,
-                0x68 => Opcode::Imul,
-                0x74 => Opcode::Ineg,
-                0xba => {
-                    let opcode = Opcode::Invokedynamic(self.argument_u16()?);
-                    if self.argument_u16()? != 0 {
-                        return Err(DisasmError::InvalidArgument);
-                    }
-                    opcode
-                }
-                0xb9 => {
-                    let opcode = Opcode::Invokeinterface(self.argument_u16()?, self.argument()?);
-                    if self.argument()? != 0 {
-                        return Err(DisasmError::InvalidArgument);
-                    }
-                    opcode
-                }
-                0xb7 => Opcode::Invokespecial(self.argument_u16()?),
-                0xb8 => Opcode::Invokestatic(self.argument_u16()?),
-                0xb6 => Opcode::Invokevirtual(self.argument_u16()?),
-                0x80 => Opcode::Ior,
-                0x70 => Opcode::Irem,
-                0xac => Opcode::Ireturn,
-                0x78 => Opcode::Ishl,
-                0x7a => Opcode::Ishr,
-                0x36 => Opcode::Istore(self.argument()?),
-                0x3b => Opcode::Istore0,
-                0x3c => Opcode::Istore1,
-                0x3d => Opcode::Istore2,
-                0x3e => Opcode::Istore3,
-                0x64 => Opcode::Isub,
-                0x7c => Opcode::Iushr,
-                0x82 => Opcode::Ixor,
-                0xa8 => Opcode::Jsr(self.argument_u16()?),
-                0xc9 => Opcode::JsrW(self.argument_u32()?),
-                0x8a => Opcode::L2d,
-                0x89 => Opcode::L2f,
-                0x88 => Opcode::L2i,
-                0x61 => Opcode::Ladd,
-                0x2f => Opcode::Laload,
-                0x7f => Opcode::Land,
-                0x50 => Opcode::Lastore,
-                0x94 => Opcode::Lcmp,
-                0x09 => Opcode::Lconst0,
-                0x0a => Opcode::Lconst1,
-                0x12 => Opcode::Ldc(self.argument()?),
-                0x13 => Opcode::LdcW(self.argument_u16()?),
-                0x14 => Opcode::Ldc2W(self.argument_u16()?),
-                0x6d => Opcode::Ldiv,
-                0x16 => Opcode::Lload(self.argument()?),
-                0x1e => Opcode::Lload0,
-                0x1f => Opcode::Lload1,
-                0x20 => Opcode::Lload2,
-                0x21 => Opcode::Lload3,
-                0x69 => Opcode::Lmul,
-                0x75 => Opcode::Lneg,
-                0xab => unimplemented!(), // Opcode::Lookupswitch(),
-                0x81 => Opcode::Lor,
-                0x71 => Opcode::Lrem,
-                0xad => Opcode::Lreturn,
-                0x79 => Opcode::Lshl,
-                0x7b => Opcode::Lshr,
-                0x37 => Opcode::Lstore(self.argument()?),
-                0x3f => Opcode::Lstore0,
-                0x40 => Opcode::Lstore1,
-                0x41 => Opcode::Lstore2,
-                0x42 => Opcode::Lstore3,
-                0x65 => Opcode::Lsub,
-                0x7d => Opcode::Lushr,
-                0x83 => Opcode::Lxor,
-                0xc2 => Opcode::Monitorenter,
-                0xc3 => Opcode::Monitorexit,
-                0xbb => Opcode::New(self.argument_u16()?),
-                0xbc => Opcode::Newarray(parse_array_type(self.argument()?)?),
-                0x00 => Opcode::Nop,
-                0x57 => Opcode::Pop,
-                0x58 => Opcode::Pop2,
-                0xb5 => Opcode::Putfield(self.argument_u16()?),
-                0xb3 => Opcode::Ret(self.argument()?),
-                0xb1 => Opcode::Return,
-                0x35 => Opcode::Saload,
-                0x56 => Opcode::Sastore,
-                0x11 => Opcode::Sipush(self.argument_u16()? as i16),
-                0xaa => unimplemented!(), //Opcode::Tableswitch(),
-                0xc4 => unimplemented!(), // Opcode::Wide(),
-
-                tag => return Err(DisasmError::UnknownOpcode(tag)),
-            };
-            self.opcodes.push(opcode);
+            self.start = self.index;
+            let opcode = self.opcode().map_err(|kind| DisasmError {
+                kind,
+                pc: self.start,
+                opcode: Some(self.bytes[self.start]),
+            })?;
+            self.instructions.push(Instruction {
+                offset: self.start as u16,
+                opcode,
+            });
             self.index += 1;
         }
         Ok(())
     }
+
+    fn opcode(&mut self) -> Result<Opcode, DisasmErrorKind> {
+        Ok(match self.bytes[self.index] {
+            0x32 => Opcode::Aaload,
+            0x53 => Opcode::Aastore,
+            0x01 => Opcode::AconstNull,
+            0x19 => Opcode::Aload(self.argument()?),
+            0x2a => Opcode::Aload0,
+            0x2b => Opcode::Aload1,
+            0x2c => Opcode::Aload2,
+            0x2d => Opcode::Aload3,
+            0xbd => Opcode::Anewarray(self.argument_u16()?),
+            0xb0 => Opcode::Areturn,
+            0xbe => Opcode::Arraylength,
+            0x3a => Opcode::Astore(self.argument()?),
+            0x4b => Opcode::Astore0,
+            0x4c => Opcode::Astore1,
+            0x4d => Opcode::Astore2,
+            0x4e => Opcode::Astore3,
+            0xbf => Opcode::Athrow,
+            0x33 => Opcode::Baload,
+            0x54 => Opcode::Bastore,
+            0x10 => Opcode::Bipush(self.argument()? as i8),
+            0xca => Opcode::Breakpoint,
+            0x34 => Opcode::Caload,
+            0x55 => Opcode::Castore,
+            0xc0 => Opcode::Checkcast(self.argument_u16()?),
+            0x90 => Opcode::D2f,
+            0x8e => Opcode::D2i,
+            0x8f => Opcode::D2l,
+            0x63 => Opcode::Dadd,
+            0x31 => Opcode::Daload,
+            0x52 => Opcode::Dastore,
+            0x98 => Opcode::Dcmpg,
+            0x97 => Opcode::Dcmpl,
+            0x0e => Opcode::Dconst0,
+            0x0f => Opcode::Dconst1,
+            0x6f => Opcode::Ddiv,
+            0x18 => Opcode::Dload(self.argument()?),
+            0x26 => Opcode::Dload0,
+            0x27 => Opcode::Dload1,
+            0x28 => Opcode::Dload2,
+            0x29 => Opcode::Dload3,
+            0x6b => Opcode::Dmul,
+            0x77 => Opcode::Dneg,
+            0x73 => Opcode::Drem,
+            0xaf => Opcode::Dreturn,
+            0x39 => Opcode::Dstore(self.argument()?),
+            0x47 => Opcode::Dstore0,
+            0x48 => Opcode::Dstore1,
+            0x49 => Opcode::Dstore2,
+            0x4a => Opcode::Dstore3,
+            0x67 => Opcode::Dsub,
+            0x59 => Opcode::Dup,
+            0x5a => Opcode::DupX1,
+            0x5b => Opcode::DupX2,
+            0x5c => Opcode::Dup2,
+            0x5d => Opcode::Dup2X1,
+            0x5e => Opcode::Dup2X2,
+            0x8d => Opcode::F2d,
+            0x8b => Opcode::F2i,
+            0x8c => Opcode::F2l,
+            0x62 => Opcode::Fadd,
+            0x30 => Opcode::Faload,
+            0x51 => Opcode::Fastore,
+            0x96 => Opcode::Fcmpg,
+            0x95 => Opcode::Fcmpl,
+            0x0b => Opcode::Fconst0,
+            0x0c => Opcode::Fconst1,
+            0x0d => Opcode::Fconst2,
+            0x6e => Opcode::Fdiv,
+            0x17 => Opcode::Fload(self.argument()?),
+            0x22 => Opcode::Fload0,
+            0x23 => Opcode::Fload1,
+            0x24 => Opcode::Fload2,
+            0x25 => Opcode::Fload3,
+            0x6a => Opcode::Fmul,
+            0x76 => Opcode::Fneg,
+            0x72 => Opcode::Frem,
+            0xae => Opcode::Freturn,
+            0x38 => Opcode::Fstore(self.argument()?),
+            0x43 => Opcode::Fstore0,
+            0x44 => Opcode::Fstore1,
+            0x45 => Opcode::Fstore2,
+            0x46 => Opcode::Fstore3,
+            0x66 => Opcode::Fsub,
+            0xb4 => Opcode::Getfield(self.argument_u16()?),
+            0xb2 => Opcode::Getstatic(self.argument_u16()?),
+            0xa7 => Opcode::Goto(self.branch()?),
+            0xc8 => Opcode::GotoW(self.branch_wide()?),
+            0x91 => Opcode::I2b,
+            0x92 => Opcode::I2c,
+            0x87 => Opcode::I2d,
+            0x86 => Opcode::I2f,
+            0x85 => Opcode::I2l,
+            0x93 => Opcode::I2s,
+            0x60 => Opcode::Iadd,
+            0x2e => Opcode::Iaload,
+            0x7e => Opcode::Iand,
+            0x4f => Opcode::Iastore,
+            0x02 => Opcode::IconstM1,
+            0x03 => Opcode::Iconst0,
+            0x04 => Opcode::Iconst1,
+            0x05 => Opcode::Iconst2,
+            0x06 => Opcode::Iconst3,
+            0x07 => Opcode::Iconst4,
+            0x08 => Opcode::Iconst5,
+            0x6c => Opcode::Idiv,
+            0xa5 => Opcode::IfAcmpeq(self.branch()?),
+            0xa6 => Opcode::IfAcmpne(self.branch()?),
+            0x9f => Opcode::IfIcmpeq(self.branch()?),
+            0xa0 => Opcode::IfIcmpne(self.branch()?),
+            0xa1 => Opcode::IfIcmplt(self.branch()?),
+            0xa2 => Opcode::IfIcmpge(self.branch()?),
+            0xa3 => Opcode::IfIcmpgt(self.branch()?),
+            0xa4 => Opcode::IfIcmple(self.branch()?),
+            0x99 => Opcode::Ifeq(self.branch()?),
+            0x9a => Opcode::Ifne(self.branch()?),
+            0x9b => Opcode::Iflt(self.branch()?),
+            0x9c => Opcode::Ifge(self.branch()?),
+            0x9d => Opcode::Ifgt(self.branch()?),
+            0x9e => Opcode::Ifle(self.branch()?),
+            0xc7 => Opcode::Ifnonnull(self.branch()?),
+            0xc6 => Opcode::Ifnull(self.branch()?),
+            0x84 => Opcode::Iinc(self.argument()?, self.argument()? as i8),
+            0x15 => Opcode::Iload(self.argument()?),
+            0x1a => Opcode::Iload0,
+            0x1b => Opcode::Iload1,
+            0x1c => Opcode::Iload2,
+            0x1d => Opcode::Iload3,
+            0x68 => Opcode::Imul,
+            0x74 => Opcode::Ineg,
+            0xfe => Opcode::Impdep1,
+            0xff => Opcode::Impdep2,
+            0xc1 => Opcode::Instanceof(self.argument_u16()?),
+            0xba => {
+                let opcode = Opcode::Invokedynamic(self.argument_u16()?);
+                if self.argument_u16()? != 0 {
+                    return Err(DisasmErrorKind::InvalidArgument);
+                }
+                opcode
+            }
+            0xb9 => {
+                let opcode = Opcode::Invokeinterface(self.argument_u16()?, self.argument()?);
+                if self.argument()? != 0 {
+                    return Err(DisasmErrorKind::InvalidArgument);
+                }
+                opcode
+            }
+            0xb7 => Opcode::Invokespecial(self.argument_u16()?),
+            0xb8 => Opcode::Invokestatic(self.argument_u16()?),
+            0xb6 => Opcode::Invokevirtual(self.argument_u16()?),
+            0x80 => Opcode::Ior,
+            0x70 => Opcode::Irem,
+            0xac => Opcode::Ireturn,
+            0x78 => Opcode::Ishl,
+            0x7a => Opcode::Ishr,
+            0x36 => Opcode::Istore(self.argument()?),
+            0x3b => Opcode::Istore0,
+            0x3c => Opcode::Istore1,
+            0x3d => Opcode::Istore2,
+            0x3e => Opcode::Istore3,
+            0x64 => Opcode::Isub,
+            0x7c => Opcode::Iushr,
+            0x82 => Opcode::Ixor,
+            0xa8 => Opcode::Jsr(self.branch()?),
+            0xc9 => Opcode::JsrW(self.branch_wide()?),
+            0x8a => Opcode::L2d,
+            0x89 => Opcode::L2f,
+            0x88 => Opcode::L2i,
+            0x61 => Opcode::Ladd,
+            0x2f => Opcode::Laload,
+            0x7f => Opcode::Land,
+            0x50 => Opcode::Lastore,
+            0x94 => Opcode::Lcmp,
+            0x09 => Opcode::Lconst0,
+            0x0a => Opcode::Lconst1,
+            0x12 => Opcode::Ldc(self.argument()?),
+            0x13 => Opcode::LdcW(self.argument_u16()?),
+            0x14 => Opcode::Ldc2W(self.argument_u16()?),
+            0x6d => Opcode::Ldiv,
+            0x16 => Opcode::Lload(self.argument()?),
+            0x1e => Opcode::Lload0,
+            0x1f => Opcode::Lload1,
+            0x20 => Opcode::Lload2,
+            0x21 => Opcode::Lload3,
+            0x69 => Opcode::Lmul,
+            0x75 => Opcode::Lneg,
+            0xab => Opcode::Lookupswitch(self.lookupswitch()?),
+            0x81 => Opcode::Lor,
+            0x71 => Opcode::Lrem,
+            0xad => Opcode::Lreturn,
+            0x79 => Opcode::Lshl,
+            0x7b => Opcode::Lshr,
+            0x37 => Opcode::Lstore(self.argument()?),
+            0x3f => Opcode::Lstore0,
+            0x40 => Opcode::Lstore1,
+            0x41 => Opcode::Lstore2,
+            0x42 => Opcode::Lstore3,
+            0x65 => Opcode::Lsub,
+            0x7d => Opcode::Lushr,
+            0x83 => Opcode::Lxor,
+            0xc2 => Opcode::Monitorenter,
+            0xc3 => Opcode::Monitorexit,
+            0xc5 => Opcode::Multianewarray(self.argument_u16()?, self.argument()?),
+            0xbb => Opcode::New(self.argument_u16()?),
+            0xbc => Opcode::Newarray(parse_array_type(self.argument()?)?),
+            0x00 => Opcode::Nop,
+            0x57 => Opcode::Pop,
+            0x58 => Opcode::Pop2,
+            0xb5 => Opcode::Putfield(self.argument_u16()?),
+            0xb3 => Opcode::Putstatic(self.argument_u16()?),
+            0xa9 => Opcode::Ret(self.argument()?),
+            0xb1 => Opcode::Return,
+            0x35 => Opcode::Saload,
+            0x56 => Opcode::Sastore,
+            0x5f => Opcode::Swap,
+            0x11 => Opcode::Sipush(self.argument_u16()? as i16),
+            0xaa => Opcode::Tableswitch(self.tableswitch()?),
+            0xc4 => Opcode::Wide(self.wide()?),
+
+            _ => return Err(DisasmErrorKind::UnknownOpcode),
+        })
+    }
 }
 
-pub fn disasm(bytes: &[u8]) -> Result<Vec<Opcode>, DisasmError> {
+pub fn disasm(bytes: &[u8]) -> Result<Bytecode, DisasmError> {
+    if bytes.len() > u16::MAX as usize {
+        return Err(DisasmError {
+            kind: DisasmErrorKind::CodeTooLong,
+            pc: u16::MAX as usize,
+            opcode: None,
+        });
+    }
+
     let mut disasm = Disasm::new(bytes);
-    disasm.process();
-    Ok(disasm.opcodes)
+    disasm.process()?;
+    let bytecode = Bytecode::new(disasm.instructions);
+
+    // branches must not jump into the middle of an instruction
+    for instruction in &bytecode {
+        for target in instruction.opcode.branch_targets() {
+            if bytecode.index_of(target).is_none() {
+                return Err(DisasmError {
+                    kind: DisasmErrorKind::InvalidBranchTarget,
+                    pc: instruction.offset as usize,
+                    opcode: Some(bytes[instruction.offset as usize]),
+                });
+            }
+        }
+    }
+
+    Ok(bytecode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tableswitch() {
+        let code = [
+            0x1a, // iload_0
+            0xaa, 0x00, 0x00, // tableswitch + padding
+            0x00, 0x00, 0x00, 0x17, // default
+            0x00, 0x00, 0x00, 0x01, // low
+            0x00, 0x00, 0x00, 0x02, // high
+            0x00, 0x00, 0x00, 0x17, // 1
+            0xff, 0xff, 0xff, 0xff, // 2
+            0xb1, // return
+        ];
+        let bytecode = disasm(&code).unwrap();
+        assert_eq!(bytecode.len(), 3);
+        match &bytecode.at(1).unwrap().opcode {
+            Opcode::Tableswitch(table) => {
+                assert_eq!(table.default, 24);
+                assert_eq!(table.low, 1);
+                assert_eq!(table.high, 2);
+                assert_eq!(table.offsets, vec![24, 0]);
+            }
+            opcode => panic!("unexpected opcode {:?}", opcode),
+        }
+        assert!(matches!(bytecode.at(24).unwrap().opcode, Opcode::Return));
+    }
+
+    #[test]
+    fn lookupswitch() {
+        let code = [
+            0xab, 0x00, 0x00, 0x00, // lookupswitch + padding
+            0x00, 0x00, 0x00, 0x1c, // default
+            0x00, 0x00, 0x00, 0x02, // npairs
+            0xff, 0xff, 0xff, 0xf6, 0x00, 0x00, 0x00, 0x1c, // -10 => 28
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 65536 => 0
+            0xb1, // return
+        ];
+        let bytecode = disasm(&code).unwrap();
+        assert_eq!(bytecode.len(), 2);
+        assert_eq!(bytecode.index_of(28), Some(1));
+        match &bytecode.at(0).unwrap().opcode {
+            Opcode::Lookupswitch(lookup) => {
+                assert_eq!(lookup.default, 28);
+                assert_eq!(lookup.pairs, vec![(-10, 28), (65536, 0)]);
+            }
+            opcode => panic!("unexpected opcode {:?}", opcode),
+        }
+    }
+
+    #[test]
+    fn unsorted_lookupswitch() {
+        let code = [
+            0xab, 0x00, 0x00, 0x00, // lookupswitch + padding
+            0x00, 0x00, 0x00, 0x14, // default
+            0x00, 0x00, 0x00, 0x02, // npairs
+            0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x10, // 2 => 16
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x12, // 1 => 18
+        ];
+        assert!(matches!(
+            disasm(&code),
+            Err(DisasmError {
+                kind: DisasmErrorKind::InvalidSwitchTable,
+                pc: 0,
+                opcode: Some(0xab),
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_tableswitch() {
+        let code = [
+            0xaa, 0x00, 0x00, 0x00, // tableswitch + padding
+            0x00, 0x00, 0x00, 0x00, // default
+            0x00, 0x00, 0x00, 0x00, // low
+            0x7f, 0xff, 0xff, 0xff, // high
+            0x00, 0x00, 0x00, 0x17, // 0
+        ];
+        assert!(matches!(
+            disasm(&code),
+            Err(DisasmError {
+                kind: DisasmErrorKind::InvalidSwitchTable,
+                pc: 0,
+                opcode: Some(0xaa),
+            })
+        ));
+    }
+
+    #[test]
+    fn wide() {
+        let code = [
+            0xc4, 0x15, 0x01, 0x00, // wide iload 256
+            0xc4, 0x84, 0x01, 0x01, 0xff, 0x00, // wide iinc 257 -256
+            0xb1, // return
+        ];
+        let bytecode = disasm(&code).unwrap();
+        assert_eq!(bytecode.len(), 3);
+        assert!(matches!(
+            bytecode.at(0).unwrap().opcode,
+            Opcode::Wide(WideOpcode::Iload(256))
+        ));
+        assert!(matches!(
+            bytecode.at(4).unwrap().opcode,
+            Opcode::Wide(WideOpcode::Iinc(257, -256))
+        ));
+        assert_eq!(bytecode.index_of(10), Some(2));
+    }
+
+    #[test]
+    fn invalid_wide() {
+        let code = [0xc4, 0xb1];
+        assert!(matches!(
+            disasm(&code),
+            Err(DisasmError {
+                kind: DisasmErrorKind::InvalidWideOpcode(0xb1),
+                pc: 0,
+                opcode: Some(0xc4),
+            })
+        ));
+    }
+
+    #[test]
+    fn branch_targets() {
+        let code = [
+            0x03, // iconst_0
+            0x99, 0x00, 0x06, // ifeq +6
+            0xa7, 0xff, 0xfc, // goto -4
+            0xb1, // return
+        ];
+        let bytecode = disasm(&code).unwrap();
+        let offsets: Vec<u16> = bytecode.iter().map(|i| i.offset).collect();
+        assert_eq!(offsets, vec![0, 1, 4, 7]);
+        assert!(matches!(bytecode.at(1).unwrap().opcode, Opcode::Ifeq(7)));
+        assert!(matches!(bytecode.at(4).unwrap().opcode, Opcode::Goto(0)));
+        assert_eq!(bytecode.index_of(2), None);
+    }
+
+    #[test]
+    fn branch_into_instruction() {
+        let code = [
+            0xa7, 0x00, 0x01, // goto +1
+            0xb1, // return
+        ];
+        assert!(matches!(
+            disasm(&code),
+            Err(DisasmError {
+                kind: DisasmErrorKind::InvalidBranchTarget,
+                pc: 0,
+                opcode: Some(0xa7),
+            })
+        ));
+    }
+
+    #[test]
+    fn branch_out_of_code() {
+        let code = [
+            0xa7, 0xff, 0xff, // goto -1
+        ];
+        assert!(matches!(
+            disasm(&code),
+            Err(DisasmError {
+                kind: DisasmErrorKind::InvalidBranchTarget,
+                pc: 0,
+                opcode: Some(0xa7),
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let code = [
+            0x03, // iconst_0
+            0x57, // pop
+            0xcb, // unassigned
+        ];
+        let error = disasm(&code).unwrap_err();
+        assert_eq!(
+            error,
+            DisasmError {
+                kind: DisasmErrorKind::UnknownOpcode,
+                pc: 2,
+                opcode: Some(0xcb),
+            }
+        );
+        assert_eq!(error.to_string(), "unknown opcode at pc 2 (opcode 0xcb)");
+    }
 }
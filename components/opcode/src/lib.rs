@@ -1,8 +1,14 @@
+mod asm;
+mod bytecode;
 mod disasm;
+mod metadata;
 
-pub use disasm::{disasm, DisasmError};
+pub use asm::{assemble, AsmError, Assembler, Label, LocalKind};
+pub use bytecode::{Bytecode, Instruction};
+pub use disasm::{disasm, DisasmError, DisasmErrorKind};
+pub use metadata::{Category, FlowKind, StackEffect};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ArrayType {
     BOOLEAN = 4,
     CHAR = 5,
@@ -14,7 +20,47 @@ pub enum ArrayType {
     LONG = 11,
 }
 
-#[derive(Debug)]
+/// Operands of a `lookupswitch` instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupSwitch {
+    pub default: u16,
+    /// Match-target pairs, sorted by match in increasing order.
+    pub pairs: Vec<(i32, u16)>,
+}
+
+/// Operands of a `tableswitch` instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSwitch {
+    pub default: u16,
+    pub low: i32,
+    pub high: i32,
+    /// Jump targets for the indices `low..=high`.
+    pub offsets: Vec<u16>,
+}
+
+/// Instructions that can be modified by a `wide` prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WideOpcode {
+    Iload(u16),
+    Lload(u16),
+    Fload(u16),
+    Dload(u16),
+    Aload(u16),
+    Istore(u16),
+    Lstore(u16),
+    Fstore(u16),
+    Dstore(u16),
+    Astore(u16),
+    Ret(u16),
+    Iinc(u16, i16),
+}
+
+/// JVM instruction with its operands.
+///
+/// Branch and switch operands are absolute offsets within the code array, not
+/// the relative offsets used in the encoded form.
+#[allow(clippy::tabs_in_doc_comments)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Opcode {
     ///  		arrayref, index → value 	load onto the stack a reference from an array
     Aaload,
@@ -275,7 +321,7 @@ pub enum Opcode {
     Goto(u16),
 
     ///  	4: branchbyte1, branchbyte2, branchbyte3, branchbyte4 	[no change] 	goes to another instruction at branchoffset (signed int constructed from unsigned bytes branchbyte1 << 24 | branchbyte2 << 16 | branchbyte3 << 8 | branchbyte4)
-    GotoW(u32),
+    GotoW(u16),
 
     ///  		value → result 	convert an int into a byte
     I2b,
@@ -380,7 +426,7 @@ pub enum Opcode {
     Ifnull(u16),
 
     ///  	2: index, const 	[No change] 	increment local variable #index by signed byte const
-    Iinc(u8, u8),
+    Iinc(u8, i8),
 
     ///  	1: index 	→ value 	load an int value from a local variable #index
     Iload(u8),
@@ -470,7 +516,7 @@ pub enum Opcode {
     Jsr(u16),
 
     ///  	4: branchbyte1, branchbyte2, branchbyte3, branchbyte4 	→ address 	jump to subroutine at branchoffset (signed int constructed from unsigned bytes branchbyte1 << 24 | branchbyte2 << 16 | branchbyte3 << 8 | branchbyte4) and place the return address on the stack
-    JsrW(u32),
+    JsrW(u16),
 
     ///  		value → result 	convert a long to a double
     L2d,
@@ -536,7 +582,7 @@ pub enum Opcode {
     Lneg,
 
     ///  	8+: <0–3 bytes padding>, defaultbyte1, defaultbyte2, defaultbyte3, defaultbyte4, npairs1, npairs2, npairs3, npairs4, match-offset pairs... 	key → 	a target address is looked up from a table using a key and execution continues from the instruction at that address
-    Lookupswitch(),
+    Lookupswitch(LookupSwitch),
 
     ///  		value1, value2 → result 	bitwise OR of two longs
     Lor,
@@ -626,8 +672,298 @@ pub enum Opcode {
     Swap,
 
     ///  	16+: [0–3 bytes padding], defaultbyte1, defaultbyte2, defaultbyte3, defaultbyte4, lowbyte1, lowbyte2, lowbyte3, lowbyte4, highbyte1, highbyte2, highbyte3, highbyte4, jump offsets... 	index → 	continue execution from an address in the table at offset index
-    Tableswitch(),
+    Tableswitch(TableSwitch),
 
     ///  	3/5: opcode, indexbyte1, indexbyte2
-    Wide(),
+    ///  	or
+    ///  	iinc, indexbyte1, indexbyte2, countbyte1, countbyte2 	[same as for corresponding instructions] 	execute opcode, where opcode is either iload, fload, aload, lload, dload, istore, fstore, astore, lstore, dstore, or ret, but assume the index is 16 bit; or execute iinc, where the index is 16 bits and the constant to increment by is a signed 16 bit short
+    Wide(WideOpcode),
+}
+
+impl Opcode {
+    /// Opcode byte of the instruction.
+    pub fn code(&self) -> u8 {
+        match self {
+            Opcode::Aaload => 0x32,
+            Opcode::Aastore => 0x53,
+            Opcode::AconstNull => 0x01,
+            Opcode::Aload(..) => 0x19,
+            Opcode::Aload0 => 0x2a,
+            Opcode::Aload1 => 0x2b,
+            Opcode::Aload2 => 0x2c,
+            Opcode::Aload3 => 0x2d,
+            Opcode::Anewarray(..) => 0xbd,
+            Opcode::Areturn => 0xb0,
+            Opcode::Arraylength => 0xbe,
+            Opcode::Astore(..) => 0x3a,
+            Opcode::Astore0 => 0x4b,
+            Opcode::Astore1 => 0x4c,
+            Opcode::Astore2 => 0x4d,
+            Opcode::Astore3 => 0x4e,
+            Opcode::Athrow => 0xbf,
+            Opcode::Baload => 0x33,
+            Opcode::Bastore => 0x54,
+            Opcode::Bipush(..) => 0x10,
+            Opcode::Breakpoint => 0xca,
+            Opcode::Caload => 0x34,
+            Opcode::Castore => 0x55,
+            Opcode::Checkcast(..) => 0xc0,
+            Opcode::D2f => 0x90,
+            Opcode::D2i => 0x8e,
+            Opcode::D2l => 0x8f,
+            Opcode::Dadd => 0x63,
+            Opcode::Daload => 0x31,
+            Opcode::Dastore => 0x52,
+            Opcode::Dcmpg => 0x98,
+            Opcode::Dcmpl => 0x97,
+            Opcode::Dconst0 => 0x0e,
+            Opcode::Dconst1 => 0x0f,
+            Opcode::Ddiv => 0x6f,
+            Opcode::Dload(..) => 0x18,
+            Opcode::Dload0 => 0x26,
+            Opcode::Dload1 => 0x27,
+            Opcode::Dload2 => 0x28,
+            Opcode::Dload3 => 0x29,
+            Opcode::Dmul => 0x6b,
+            Opcode::Dneg => 0x77,
+            Opcode::Drem => 0x73,
+            Opcode::Dreturn => 0xaf,
+            Opcode::Dstore(..) => 0x39,
+            Opcode::Dstore0 => 0x47,
+            Opcode::Dstore1 => 0x48,
+            Opcode::Dstore2 => 0x49,
+            Opcode::Dstore3 => 0x4a,
+            Opcode::Dsub => 0x67,
+            Opcode::Dup => 0x59,
+            Opcode::DupX1 => 0x5a,
+            Opcode::DupX2 => 0x5b,
+            Opcode::Dup2 => 0x5c,
+            Opcode::Dup2X1 => 0x5d,
+            Opcode::Dup2X2 => 0x5e,
+            Opcode::F2d => 0x8d,
+            Opcode::F2i => 0x8b,
+            Opcode::F2l => 0x8c,
+            Opcode::Fadd => 0x62,
+            Opcode::Faload => 0x30,
+            Opcode::Fastore => 0x51,
+            Opcode::Fcmpg => 0x96,
+            Opcode::Fcmpl => 0x95,
+            Opcode::Fconst0 => 0x0b,
+            Opcode::Fconst1 => 0x0c,
+            Opcode::Fconst2 => 0x0d,
+            Opcode::Fdiv => 0x6e,
+            Opcode::Fload(..) => 0x17,
+            Opcode::Fload0 => 0x22,
+            Opcode::Fload1 => 0x23,
+            Opcode::Fload2 => 0x24,
+            Opcode::Fload3 => 0x25,
+            Opcode::Fmul => 0x6a,
+            Opcode::Fneg => 0x76,
+            Opcode::Frem => 0x72,
+            Opcode::Freturn => 0xae,
+            Opcode::Fstore(..) => 0x38,
+            Opcode::Fstore0 => 0x43,
+            Opcode::Fstore1 => 0x44,
+            Opcode::Fstore2 => 0x45,
+            Opcode::Fstore3 => 0x46,
+            Opcode::Fsub => 0x66,
+            Opcode::Getfield(..) => 0xb4,
+            Opcode::Getstatic(..) => 0xb2,
+            Opcode::Goto(..) => 0xa7,
+            Opcode::GotoW(..) => 0xc8,
+            Opcode::I2b => 0x91,
+            Opcode::I2c => 0x92,
+            Opcode::I2d => 0x87,
+            Opcode::I2f => 0x86,
+            Opcode::I2l => 0x85,
+            Opcode::I2s => 0x93,
+            Opcode::Iadd => 0x60,
+            Opcode::Iaload => 0x2e,
+            Opcode::Iand => 0x7e,
+            Opcode::Iastore => 0x4f,
+            Opcode::IconstM1 => 0x02,
+            Opcode::Iconst0 => 0x03,
+            Opcode::Iconst1 => 0x04,
+            Opcode::Iconst2 => 0x05,
+            Opcode::Iconst3 => 0x06,
+            Opcode::Iconst4 => 0x07,
+            Opcode::Iconst5 => 0x08,
+            Opcode::Idiv => 0x6c,
+            Opcode::IfAcmpeq(..) => 0xa5,
+            Opcode::IfAcmpne(..) => 0xa6,
+            Opcode::IfIcmpeq(..) => 0x9f,
+            Opcode::IfIcmpge(..) => 0xa2,
+            Opcode::IfIcmpgt(..) => 0xa3,
+            Opcode::IfIcmple(..) => 0xa4,
+            Opcode::IfIcmplt(..) => 0xa1,
+            Opcode::IfIcmpne(..) => 0xa0,
+            Opcode::Ifeq(..) => 0x99,
+            Opcode::Ifge(..) => 0x9c,
+            Opcode::Ifgt(..) => 0x9d,
+            Opcode::Ifle(..) => 0x9e,
+            Opcode::Iflt(..) => 0x9b,
+            Opcode::Ifne(..) => 0x9a,
+            Opcode::Ifnonnull(..) => 0xc7,
+            Opcode::Ifnull(..) => 0xc6,
+            Opcode::Iinc(..) => 0x84,
+            Opcode::Iload(..) => 0x15,
+            Opcode::Iload0 => 0x1a,
+            Opcode::Iload1 => 0x1b,
+            Opcode::Iload2 => 0x1c,
+            Opcode::Iload3 => 0x1d,
+            Opcode::Impdep1 => 0xfe,
+            Opcode::Impdep2 => 0xff,
+            Opcode::Imul => 0x68,
+            Opcode::Ineg => 0x74,
+            Opcode::Instanceof(..) => 0xc1,
+            Opcode::Invokedynamic(..) => 0xba,
+            Opcode::Invokeinterface(..) => 0xb9,
+            Opcode::Invokespecial(..) => 0xb7,
+            Opcode::Invokestatic(..) => 0xb8,
+            Opcode::Invokevirtual(..) => 0xb6,
+            Opcode::Ior => 0x80,
+            Opcode::Irem => 0x70,
+            Opcode::Ireturn => 0xac,
+            Opcode::Ishl => 0x78,
+            Opcode::Ishr => 0x7a,
+            Opcode::Istore(..) => 0x36,
+            Opcode::Istore0 => 0x3b,
+            Opcode::Istore1 => 0x3c,
+            Opcode::Istore2 => 0x3d,
+            Opcode::Istore3 => 0x3e,
+            Opcode::Isub => 0x64,
+            Opcode::Iushr => 0x7c,
+            Opcode::Ixor => 0x82,
+            Opcode::Jsr(..) => 0xa8,
+            Opcode::JsrW(..) => 0xc9,
+            Opcode::L2d => 0x8a,
+            Opcode::L2f => 0x89,
+            Opcode::L2i => 0x88,
+            Opcode::Ladd => 0x61,
+            Opcode::Laload => 0x2f,
+            Opcode::Land => 0x7f,
+            Opcode::Lastore => 0x50,
+            Opcode::Lcmp => 0x94,
+            Opcode::Lconst0 => 0x09,
+            Opcode::Lconst1 => 0x0a,
+            Opcode::Ldc(..) => 0x12,
+            Opcode::LdcW(..) => 0x13,
+            Opcode::Ldc2W(..) => 0x14,
+            Opcode::Ldiv => 0x6d,
+            Opcode::Lload(..) => 0x16,
+            Opcode::Lload0 => 0x1e,
+            Opcode::Lload1 => 0x1f,
+            Opcode::Lload2 => 0x20,
+            Opcode::Lload3 => 0x21,
+            Opcode::Lmul => 0x69,
+            Opcode::Lneg => 0x75,
+            Opcode::Lookupswitch(..) => 0xab,
+            Opcode::Lor => 0x81,
+            Opcode::Lrem => 0x71,
+            Opcode::Lreturn => 0xad,
+            Opcode::Lshl => 0x79,
+            Opcode::Lshr => 0x7b,
+            Opcode::Lstore(..) => 0x37,
+            Opcode::Lstore0 => 0x3f,
+            Opcode::Lstore1 => 0x40,
+            Opcode::Lstore2 => 0x41,
+            Opcode::Lstore3 => 0x42,
+            Opcode::Lsub => 0x65,
+            Opcode::Lushr => 0x7d,
+            Opcode::Lxor => 0x83,
+            Opcode::Monitorenter => 0xc2,
+            Opcode::Monitorexit => 0xc3,
+            Opcode::Multianewarray(..) => 0xc5,
+            Opcode::New(..) => 0xbb,
+            Opcode::Newarray(..) => 0xbc,
+            Opcode::Nop => 0x00,
+            Opcode::Pop => 0x57,
+            Opcode::Pop2 => 0x58,
+            Opcode::Putfield(..) => 0xb5,
+            Opcode::Putstatic(..) => 0xb3,
+            Opcode::Ret(..) => 0xa9,
+            Opcode::Return => 0xb1,
+            Opcode::Saload => 0x35,
+            Opcode::Sastore => 0x56,
+            Opcode::Sipush(..) => 0x11,
+            Opcode::Swap => 0x5f,
+            Opcode::Tableswitch(..) => 0xaa,
+            Opcode::Wide(..) => 0xc4,
+        }
+    }
+
+    /// Absolute offsets this instruction can jump to, excluding the fall through to
+    /// the next instruction.
+    pub fn branch_targets(&self) -> Vec<u16> {
+        match self {
+            Opcode::Goto(target)
+            | Opcode::GotoW(target)
+            | Opcode::IfAcmpeq(target)
+            | Opcode::IfAcmpne(target)
+            | Opcode::IfIcmpeq(target)
+            | Opcode::IfIcmpge(target)
+            | Opcode::IfIcmpgt(target)
+            | Opcode::IfIcmple(target)
+            | Opcode::IfIcmplt(target)
+            | Opcode::IfIcmpne(target)
+            | Opcode::Ifeq(target)
+            | Opcode::Ifge(target)
+            | Opcode::Ifgt(target)
+            | Opcode::Ifle(target)
+            | Opcode::Iflt(target)
+            | Opcode::Ifne(target)
+            | Opcode::Ifnonnull(target)
+            | Opcode::Ifnull(target)
+            | Opcode::Jsr(target)
+            | Opcode::JsrW(target) => vec![*target],
+            Opcode::Lookupswitch(lookup) => std::iter::once(lookup.default)
+                .chain(lookup.pairs.iter().map(|(_, target)| *target))
+                .collect(),
+            Opcode::Tableswitch(table) => std::iter::once(table.default)
+                .chain(table.offsets.iter().copied())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Copy of this instruction with every branch target replaced by `f(target)`.
+    pub fn map_targets<F: FnMut(u16) -> u16>(&self, mut f: F) -> Opcode {
+        match self {
+            Opcode::Goto(target) => Opcode::Goto(f(*target)),
+            Opcode::GotoW(target) => Opcode::GotoW(f(*target)),
+            Opcode::IfAcmpeq(target) => Opcode::IfAcmpeq(f(*target)),
+            Opcode::IfAcmpne(target) => Opcode::IfAcmpne(f(*target)),
+            Opcode::IfIcmpeq(target) => Opcode::IfIcmpeq(f(*target)),
+            Opcode::IfIcmpge(target) => Opcode::IfIcmpge(f(*target)),
+            Opcode::IfIcmpgt(target) => Opcode::IfIcmpgt(f(*target)),
+            Opcode::IfIcmple(target) => Opcode::IfIcmple(f(*target)),
+            Opcode::IfIcmplt(target) => Opcode::IfIcmplt(f(*target)),
+            Opcode::IfIcmpne(target) => Opcode::IfIcmpne(f(*target)),
+            Opcode::Ifeq(target) => Opcode::Ifeq(f(*target)),
+            Opcode::Ifge(target) => Opcode::Ifge(f(*target)),
+            Opcode::Ifgt(target) => Opcode::Ifgt(f(*target)),
+            Opcode::Ifle(target) => Opcode::Ifle(f(*target)),
+            Opcode::Iflt(target) => Opcode::Iflt(f(*target)),
+            Opcode::Ifne(target) => Opcode::Ifne(f(*target)),
+            Opcode::Ifnonnull(target) => Opcode::Ifnonnull(f(*target)),
+            Opcode::Ifnull(target) => Opcode::Ifnull(f(*target)),
+            Opcode::Jsr(target) => Opcode::Jsr(f(*target)),
+            Opcode::JsrW(target) => Opcode::JsrW(f(*target)),
+            Opcode::Lookupswitch(lookup) => Opcode::Lookupswitch(LookupSwitch {
+                default: f(lookup.default),
+                pairs: lookup
+                    .pairs
+                    .iter()
+                    .map(|(key, target)| (*key, f(*target)))
+                    .collect(),
+            }),
+            Opcode::Tableswitch(table) => Opcode::Tableswitch(TableSwitch {
+                default: f(table.default),
+                low: table.low,
+                high: table.high,
+                offsets: table.offsets.iter().map(|target| f(*target)).collect(),
+            }),
+            opcode => opcode.clone(),
+        }
+    }
 }
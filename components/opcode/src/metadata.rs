@@ -0,0 +1,877 @@
+use crate::{Opcode, WideOpcode};
+
+/// Computational type category of a value on the operand stack.
+///
+/// `long` and `double` values are of category 2 and take two stack slots, all
+/// other values are of category 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    One,
+    Two,
+}
+
+impl Category {
+    /// Number of stack or local variable slots taken by a value.
+    pub fn slots(self) -> usize {
+        match self {
+            Category::One => 1,
+            Category::Two => 2,
+        }
+    }
+}
+
+/// Values taken from and put onto the operand stack by an instruction.
+///
+/// Values are listed from the bottom to the top of the stack. The `dup`, `pop2`
+/// and `swap` families operate on stack slots, so their values are reported as
+/// category 1 values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackEffect {
+    pub pops: Vec<Category>,
+    pub pushes: Vec<Category>,
+}
+
+impl StackEffect {
+    fn new(pops: &[Category], pushes: &[Category]) -> Self {
+        Self {
+            pops: pops.to_vec(),
+            pushes: pushes.to_vec(),
+        }
+    }
+
+    pub fn pop_slots(&self) -> usize {
+        self.pops.iter().map(|category| category.slots()).sum()
+    }
+
+    pub fn push_slots(&self) -> usize {
+        self.pushes.iter().map(|category| category.slots()).sum()
+    }
+
+    /// Change of the stack depth in slots.
+    pub fn delta(&self) -> isize {
+        self.push_slots() as isize - self.pop_slots() as isize
+    }
+}
+
+/// How control continues after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowKind {
+    /// Continues with the next instruction.
+    Next,
+    /// Unconditional jump (`goto`, `goto_w`).
+    Jump,
+    /// Jumps or continues with the next instruction (`if*`).
+    ConditionalBranch,
+    /// Jumps to one of multiple targets (`tableswitch`, `lookupswitch`).
+    Switch,
+    /// Jumps to a subroutine (`jsr`, `jsr_w`).
+    Subroutine,
+    /// Returns from a subroutine (`ret`).
+    SubroutineReturn,
+    /// Returns from the method (`*return`).
+    Return,
+    /// Throws an exception (`athrow`).
+    Throw,
+}
+
+const ONE: Category = Category::One;
+const TWO: Category = Category::Two;
+
+/// Maximum number of array dimensions allowed by the JVM specification.
+const MAX_ARRAY_DIMENSIONS: usize = 255;
+
+/// Category of the value of a field descriptor and the rest of the descriptor.
+fn parse_category(descriptor: &str) -> Option<(Category, &str)> {
+    let dimensions = descriptor.find(|c| c != '[')?;
+    if dimensions > MAX_ARRAY_DIMENSIONS {
+        return None;
+    }
+    let descriptor = &descriptor[dimensions..];
+    let mut chars = descriptor.chars();
+    let category = match chars.next()? {
+        'J' | 'D' => TWO,
+        'B' | 'C' | 'F' | 'I' | 'S' | 'Z' => ONE,
+        'L' => {
+            let end = descriptor.find(';')?;
+            if end == 1 {
+                return None;
+            }
+            return Some((ONE, &descriptor[end + 1..]));
+        }
+        _ => return None,
+    };
+    match dimensions {
+        0 => Some((category, chars.as_str())),
+        _ => Some((ONE, chars.as_str())),
+    }
+}
+
+fn field_category(descriptor: &str) -> Option<Category> {
+    match parse_category(descriptor)? {
+        (category, "") => Some(category),
+        _ => None,
+    }
+}
+
+/// Categories of the parameters and of the return value of a method descriptor.
+fn method_categories(descriptor: &str) -> Option<(Vec<Category>, Option<Category>)> {
+    let mut rest = descriptor.strip_prefix('(')?;
+    let mut params = vec![];
+    while !rest.starts_with(')') {
+        let (category, tail) = parse_category(rest)?;
+        params.push(category);
+        rest = tail;
+    }
+
+    match &rest[1..] {
+        "V" => Some((params, None)),
+        ret => Some((params, Some(field_category(ret)?))),
+    }
+}
+
+impl Opcode {
+    /// Name of the instruction as used in the JVM specification.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Opcode::Aaload => "aaload",
+            Opcode::Aastore => "aastore",
+            Opcode::AconstNull => "aconst_null",
+            Opcode::Aload(..) => "aload",
+            Opcode::Aload0 => "aload_0",
+            Opcode::Aload1 => "aload_1",
+            Opcode::Aload2 => "aload_2",
+            Opcode::Aload3 => "aload_3",
+            Opcode::Anewarray(..) => "anewarray",
+            Opcode::Areturn => "areturn",
+            Opcode::Arraylength => "arraylength",
+            Opcode::Astore(..) => "astore",
+            Opcode::Astore0 => "astore_0",
+            Opcode::Astore1 => "astore_1",
+            Opcode::Astore2 => "astore_2",
+            Opcode::Astore3 => "astore_3",
+            Opcode::Athrow => "athrow",
+            Opcode::Baload => "baload",
+            Opcode::Bastore => "bastore",
+            Opcode::Bipush(..) => "bipush",
+            Opcode::Breakpoint => "breakpoint",
+            Opcode::Caload => "caload",
+            Opcode::Castore => "castore",
+            Opcode::Checkcast(..) => "checkcast",
+            Opcode::D2f => "d2f",
+            Opcode::D2i => "d2i",
+            Opcode::D2l => "d2l",
+            Opcode::Dadd => "dadd",
+            Opcode::Daload => "daload",
+            Opcode::Dastore => "dastore",
+            Opcode::Dcmpg => "dcmpg",
+            Opcode::Dcmpl => "dcmpl",
+            Opcode::Dconst0 => "dconst_0",
+            Opcode::Dconst1 => "dconst_1",
+            Opcode::Ddiv => "ddiv",
+            Opcode::Dload(..) => "dload",
+            Opcode::Dload0 => "dload_0",
+            Opcode::Dload1 => "dload_1",
+            Opcode::Dload2 => "dload_2",
+            Opcode::Dload3 => "dload_3",
+            Opcode::Dmul => "dmul",
+            Opcode::Dneg => "dneg",
+            Opcode::Drem => "drem",
+            Opcode::Dreturn => "dreturn",
+            Opcode::Dstore(..) => "dstore",
+            Opcode::Dstore0 => "dstore_0",
+            Opcode::Dstore1 => "dstore_1",
+            Opcode::Dstore2 => "dstore_2",
+            Opcode::Dstore3 => "dstore_3",
+            Opcode::Dsub => "dsub",
+            Opcode::Dup => "dup",
+            Opcode::DupX1 => "dup_x1",
+            Opcode::DupX2 => "dup_x2",
+            Opcode::Dup2 => "dup2",
+            Opcode::Dup2X1 => "dup2_x1",
+            Opcode::Dup2X2 => "dup2_x2",
+            Opcode::F2d => "f2d",
+            Opcode::F2i => "f2i",
+            Opcode::F2l => "f2l",
+            Opcode::Fadd => "fadd",
+            Opcode::Faload => "faload",
+            Opcode::Fastore => "fastore",
+            Opcode::Fcmpg => "fcmpg",
+            Opcode::Fcmpl => "fcmpl",
+            Opcode::Fconst0 => "fconst_0",
+            Opcode::Fconst1 => "fconst_1",
+            Opcode::Fconst2 => "fconst_2",
+            Opcode::Fdiv => "fdiv",
+            Opcode::Fload(..) => "fload",
+            Opcode::Fload0 => "fload_0",
+            Opcode::Fload1 => "fload_1",
+            Opcode::Fload2 => "fload_2",
+            Opcode::Fload3 => "fload_3",
+            Opcode::Fmul => "fmul",
+            Opcode::Fneg => "fneg",
+            Opcode::Frem => "frem",
+            Opcode::Freturn => "freturn",
+            Opcode::Fstore(..) => "fstore",
+            Opcode::Fstore0 => "fstore_0",
+            Opcode::Fstore1 => "fstore_1",
+            Opcode::Fstore2 => "fstore_2",
+            Opcode::Fstore3 => "fstore_3",
+            Opcode::Fsub => "fsub",
+            Opcode::Getfield(..) => "getfield",
+            Opcode::Getstatic(..) => "getstatic",
+            Opcode::Goto(..) => "goto",
+            Opcode::GotoW(..) => "goto_w",
+            Opcode::I2b => "i2b",
+            Opcode::I2c => "i2c",
+            Opcode::I2d => "i2d",
+            Opcode::I2f => "i2f",
+            Opcode::I2l => "i2l",
+            Opcode::I2s => "i2s",
+            Opcode::Iadd => "iadd",
+            Opcode::Iaload => "iaload",
+            Opcode::Iand => "iand",
+            Opcode::Iastore => "iastore",
+            Opcode::IconstM1 => "iconst_m1",
+            Opcode::Iconst0 => "iconst_0",
+            Opcode::Iconst1 => "iconst_1",
+            Opcode::Iconst2 => "iconst_2",
+            Opcode::Iconst3 => "iconst_3",
+            Opcode::Iconst4 => "iconst_4",
+            Opcode::Iconst5 => "iconst_5",
+            Opcode::Idiv => "idiv",
+            Opcode::IfAcmpeq(..) => "if_acmpeq",
+            Opcode::IfAcmpne(..) => "if_acmpne",
+            Opcode::IfIcmpeq(..) => "if_icmpeq",
+            Opcode::IfIcmpge(..) => "if_icmpge",
+            Opcode::IfIcmpgt(..) => "if_icmpgt",
+            Opcode::IfIcmple(..) => "if_icmple",
+            Opcode::IfIcmplt(..) => "if_icmplt",
+            Opcode::IfIcmpne(..) => "if_icmpne",
+            Opcode::Ifeq(..) => "ifeq",
+            Opcode::Ifge(..) => "ifge",
+            Opcode::Ifgt(..) => "ifgt",
+            Opcode::Ifle(..) => "ifle",
+            Opcode::Iflt(..) => "iflt",
+            Opcode::Ifne(..) => "ifne",
+            Opcode::Ifnonnull(..) => "ifnonnull",
+            Opcode::Ifnull(..) => "ifnull",
+            Opcode::Iinc(..) => "iinc",
+            Opcode::Iload(..) => "iload",
+            Opcode::Iload0 => "iload_0",
+            Opcode::Iload1 => "iload_1",
+            Opcode::Iload2 => "iload_2",
+            Opcode::Iload3 => "iload_3",
+            Opcode::Impdep1 => "impdep1",
+            Opcode::Impdep2 => "impdep2",
+            Opcode::Imul => "imul",
+            Opcode::Ineg => "ineg",
+            Opcode::Instanceof(..) => "instanceof",
+            Opcode::Invokedynamic(..) => "invokedynamic",
+            Opcode::Invokeinterface(..) => "invokeinterface",
+            Opcode::Invokespecial(..) => "invokespecial",
+            Opcode::Invokestatic(..) => "invokestatic",
+            Opcode::Invokevirtual(..) => "invokevirtual",
+            Opcode::Ior => "ior",
+            Opcode::Irem => "irem",
+            Opcode::Ireturn => "ireturn",
+            Opcode::Ishl => "ishl",
+            Opcode::Ishr => "ishr",
+            Opcode::Istore(..) => "istore",
+            Opcode::Istore0 => "istore_0",
+            Opcode::Istore1 => "istore_1",
+            Opcode::Istore2 => "istore_2",
+            Opcode::Istore3 => "istore_3",
+            Opcode::Isub => "isub",
+            Opcode::Iushr => "iushr",
+            Opcode::Ixor => "ixor",
+            Opcode::Jsr(..) => "jsr",
+            Opcode::JsrW(..) => "jsr_w",
+            Opcode::L2d => "l2d",
+            Opcode::L2f => "l2f",
+            Opcode::L2i => "l2i",
+            Opcode::Ladd => "ladd",
+            Opcode::Laload => "laload",
+            Opcode::Land => "land",
+            Opcode::Lastore => "lastore",
+            Opcode::Lcmp => "lcmp",
+            Opcode::Lconst0 => "lconst_0",
+            Opcode::Lconst1 => "lconst_1",
+            Opcode::Ldc(..) => "ldc",
+            Opcode::LdcW(..) => "ldc_w",
+            Opcode::Ldc2W(..) => "ldc2_w",
+            Opcode::Ldiv => "ldiv",
+            Opcode::Lload(..) => "lload",
+            Opcode::Lload0 => "lload_0",
+            Opcode::Lload1 => "lload_1",
+            Opcode::Lload2 => "lload_2",
+            Opcode::Lload3 => "lload_3",
+            Opcode::Lmul => "lmul",
+            Opcode::Lneg => "lneg",
+            Opcode::Lookupswitch(..) => "lookupswitch",
+            Opcode::Lor => "lor",
+            Opcode::Lrem => "lrem",
+            Opcode::Lreturn => "lreturn",
+            Opcode::Lshl => "lshl",
+            Opcode::Lshr => "lshr",
+            Opcode::Lstore(..) => "lstore",
+            Opcode::Lstore0 => "lstore_0",
+            Opcode::Lstore1 => "lstore_1",
+            Opcode::Lstore2 => "lstore_2",
+            Opcode::Lstore3 => "lstore_3",
+            Opcode::Lsub => "lsub",
+            Opcode::Lushr => "lushr",
+            Opcode::Lxor => "lxor",
+            Opcode::Monitorenter => "monitorenter",
+            Opcode::Monitorexit => "monitorexit",
+            Opcode::Multianewarray(..) => "multianewarray",
+            Opcode::New(..) => "new",
+            Opcode::Newarray(..) => "newarray",
+            Opcode::Nop => "nop",
+            Opcode::Pop => "pop",
+            Opcode::Pop2 => "pop2",
+            Opcode::Putfield(..) => "putfield",
+            Opcode::Putstatic(..) => "putstatic",
+            Opcode::Ret(..) => "ret",
+            Opcode::Return => "return",
+            Opcode::Saload => "saload",
+            Opcode::Sastore => "sastore",
+            Opcode::Sipush(..) => "sipush",
+            Opcode::Swap => "swap",
+            Opcode::Tableswitch(..) => "tableswitch",
+            Opcode::Wide(..) => "wide",
+        }
+    }
+
+    /// Number of bytes of the encoded instruction when placed at `offset`.
+    pub fn encoded_len(&self, offset: usize) -> usize {
+        let padding = (4 - (offset + 1) % 4) % 4;
+        match self {
+            Opcode::Lookupswitch(lookup) => 1 + padding + 8 + 8 * lookup.pairs.len(),
+            Opcode::Tableswitch(table) => 1 + padding + 12 + 4 * table.offsets.len(),
+            Opcode::Wide(WideOpcode::Iinc(..)) => 6,
+            Opcode::Wide(_) => 4,
+            Opcode::GotoW(_)
+            | Opcode::JsrW(_)
+            | Opcode::Invokedynamic(_)
+            | Opcode::Invokeinterface(..) => 5,
+            Opcode::Multianewarray(..) => 4,
+            Opcode::Goto(_)
+            | Opcode::IfAcmpeq(_)
+            | Opcode::IfAcmpne(_)
+            | Opcode::IfIcmpeq(_)
+            | Opcode::IfIcmpge(_)
+            | Opcode::IfIcmpgt(_)
+            | Opcode::IfIcmple(_)
+            | Opcode::IfIcmplt(_)
+            | Opcode::IfIcmpne(_)
+            | Opcode::Ifeq(_)
+            | Opcode::Ifge(_)
+            | Opcode::Ifgt(_)
+            | Opcode::Ifle(_)
+            | Opcode::Iflt(_)
+            | Opcode::Ifne(_)
+            | Opcode::Ifnonnull(_)
+            | Opcode::Ifnull(_)
+            | Opcode::Jsr(_)
+            | Opcode::Iinc(..)
+            | Opcode::Anewarray(_)
+            | Opcode::Checkcast(_)
+            | Opcode::Getfield(_)
+            | Opcode::Getstatic(_)
+            | Opcode::Instanceof(_)
+            | Opcode::Invokespecial(_)
+            | Opcode::Invokestatic(_)
+            | Opcode::Invokevirtual(_)
+            | Opcode::LdcW(_)
+            | Opcode::Ldc2W(_)
+            | Opcode::New(_)
+            | Opcode::Putfield(_)
+            | Opcode::Putstatic(_)
+            | Opcode::Sipush(_) => 3,
+            Opcode::Aload(_)
+            | Opcode::Astore(_)
+            | Opcode::Bipush(_)
+            | Opcode::Dload(_)
+            | Opcode::Dstore(_)
+            | Opcode::Fload(_)
+            | Opcode::Fstore(_)
+            | Opcode::Iload(_)
+            | Opcode::Istore(_)
+            | Opcode::Ldc(_)
+            | Opcode::Lload(_)
+            | Opcode::Lstore(_)
+            | Opcode::Newarray(_)
+            | Opcode::Ret(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Effect on the operand stack, or `None` for field access and invoke
+    /// instructions, whose effect depends on a descriptor from the constant pool.
+    ///
+    /// See [`Opcode::stack_effect_with_descriptor`].
+    pub fn stack_effect(&self) -> Option<StackEffect> {
+        Some(match self {
+            Opcode::Nop
+            | Opcode::Goto(_)
+            | Opcode::GotoW(_)
+            | Opcode::Ret(_)
+            | Opcode::Iinc(..)
+            | Opcode::Wide(WideOpcode::Ret(_))
+            | Opcode::Wide(WideOpcode::Iinc(..))
+            | Opcode::Return
+            | Opcode::Breakpoint
+            | Opcode::Impdep1
+            | Opcode::Impdep2 => StackEffect::new(&[], &[]),
+
+            Opcode::AconstNull
+            | Opcode::IconstM1
+            | Opcode::Iconst0
+            | Opcode::Iconst1
+            | Opcode::Iconst2
+            | Opcode::Iconst3
+            | Opcode::Iconst4
+            | Opcode::Iconst5
+            | Opcode::Fconst0
+            | Opcode::Fconst1
+            | Opcode::Fconst2
+            | Opcode::Bipush(_)
+            | Opcode::Sipush(_)
+            | Opcode::Ldc(_)
+            | Opcode::LdcW(_)
+            | Opcode::Iload(_)
+            | Opcode::Iload0
+            | Opcode::Iload1
+            | Opcode::Iload2
+            | Opcode::Iload3
+            | Opcode::Fload(_)
+            | Opcode::Fload0
+            | Opcode::Fload1
+            | Opcode::Fload2
+            | Opcode::Fload3
+            | Opcode::Aload(_)
+            | Opcode::Aload0
+            | Opcode::Aload1
+            | Opcode::Aload2
+            | Opcode::Aload3
+            | Opcode::Wide(WideOpcode::Iload(_))
+            | Opcode::Wide(WideOpcode::Fload(_))
+            | Opcode::Wide(WideOpcode::Aload(_))
+            | Opcode::New(_)
+            | Opcode::Jsr(_)
+            | Opcode::JsrW(_) => StackEffect::new(&[], &[ONE]),
+
+            Opcode::Lconst0
+            | Opcode::Lconst1
+            | Opcode::Dconst0
+            | Opcode::Dconst1
+            | Opcode::Ldc2W(_)
+            | Opcode::Lload(_)
+            | Opcode::Lload0
+            | Opcode::Lload1
+            | Opcode::Lload2
+            | Opcode::Lload3
+            | Opcode::Dload(_)
+            | Opcode::Dload0
+            | Opcode::Dload1
+            | Opcode::Dload2
+            | Opcode::Dload3
+            | Opcode::Wide(WideOpcode::Lload(_))
+            | Opcode::Wide(WideOpcode::Dload(_)) => StackEffect::new(&[], &[TWO]),
+
+            Opcode::Istore(_)
+            | Opcode::Istore0
+            | Opcode::Istore1
+            | Opcode::Istore2
+            | Opcode::Istore3
+            | Opcode::Fstore(_)
+            | Opcode::Fstore0
+            | Opcode::Fstore1
+            | Opcode::Fstore2
+            | Opcode::Fstore3
+            | Opcode::Astore(_)
+            | Opcode::Astore0
+            | Opcode::Astore1
+            | Opcode::Astore2
+            | Opcode::Astore3
+            | Opcode::Wide(WideOpcode::Istore(_))
+            | Opcode::Wide(WideOpcode::Fstore(_))
+            | Opcode::Wide(WideOpcode::Astore(_))
+            | Opcode::Pop
+            | Opcode::Ifeq(_)
+            | Opcode::Ifne(_)
+            | Opcode::Iflt(_)
+            | Opcode::Ifge(_)
+            | Opcode::Ifgt(_)
+            | Opcode::Ifle(_)
+            | Opcode::Ifnull(_)
+            | Opcode::Ifnonnull(_)
+            | Opcode::Tableswitch(_)
+            | Opcode::Lookupswitch(_)
+            | Opcode::Ireturn
+            | Opcode::Freturn
+            | Opcode::Areturn
+            | Opcode::Athrow
+            | Opcode::Monitorenter
+            | Opcode::Monitorexit => StackEffect::new(&[ONE], &[]),
+
+            Opcode::Lstore(_)
+            | Opcode::Lstore0
+            | Opcode::Lstore1
+            | Opcode::Lstore2
+            | Opcode::Lstore3
+            | Opcode::Dstore(_)
+            | Opcode::Dstore0
+            | Opcode::Dstore1
+            | Opcode::Dstore2
+            | Opcode::Dstore3
+            | Opcode::Wide(WideOpcode::Lstore(_))
+            | Opcode::Wide(WideOpcode::Dstore(_))
+            | Opcode::Lreturn
+            | Opcode::Dreturn => StackEffect::new(&[TWO], &[]),
+
+            Opcode::IfIcmpeq(_)
+            | Opcode::IfIcmpne(_)
+            | Opcode::IfIcmplt(_)
+            | Opcode::IfIcmpge(_)
+            | Opcode::IfIcmpgt(_)
+            | Opcode::IfIcmple(_)
+            | Opcode::IfAcmpeq(_)
+            | Opcode::IfAcmpne(_)
+            | Opcode::Pop2 => StackEffect::new(&[ONE, ONE], &[]),
+
+            Opcode::Iaload
+            | Opcode::Faload
+            | Opcode::Aaload
+            | Opcode::Baload
+            | Opcode::Caload
+            | Opcode::Saload
+            | Opcode::Iadd
+            | Opcode::Isub
+            | Opcode::Imul
+            | Opcode::Idiv
+            | Opcode::Irem
+            | Opcode::Ishl
+            | Opcode::Ishr
+            | Opcode::Iushr
+            | Opcode::Iand
+            | Opcode::Ior
+            | Opcode::Ixor
+            | Opcode::Fadd
+            | Opcode::Fsub
+            | Opcode::Fmul
+            | Opcode::Fdiv
+            | Opcode::Frem
+            | Opcode::Fcmpl
+            | Opcode::Fcmpg => StackEffect::new(&[ONE, ONE], &[ONE]),
+
+            Opcode::Laload | Opcode::Daload => StackEffect::new(&[ONE, ONE], &[TWO]),
+
+            Opcode::Iastore
+            | Opcode::Fastore
+            | Opcode::Aastore
+            | Opcode::Bastore
+            | Opcode::Castore
+            | Opcode::Sastore => StackEffect::new(&[ONE, ONE, ONE], &[]),
+
+            Opcode::Lastore | Opcode::Dastore => StackEffect::new(&[ONE, ONE, TWO], &[]),
+
+            Opcode::Ladd
+            | Opcode::Lsub
+            | Opcode::Lmul
+            | Opcode::Ldiv
+            | Opcode::Lrem
+            | Opcode::Land
+            | Opcode::Lor
+            | Opcode::Lxor
+            | Opcode::Dadd
+            | Opcode::Dsub
+            | Opcode::Dmul
+            | Opcode::Ddiv
+            | Opcode::Drem => StackEffect::new(&[TWO, TWO], &[TWO]),
+
+            Opcode::Lshl | Opcode::Lshr | Opcode::Lushr => StackEffect::new(&[TWO, ONE], &[TWO]),
+
+            Opcode::Lcmp | Opcode::Dcmpl | Opcode::Dcmpg => StackEffect::new(&[TWO, TWO], &[ONE]),
+
+            Opcode::Ineg
+            | Opcode::Fneg
+            | Opcode::I2f
+            | Opcode::F2i
+            | Opcode::I2b
+            | Opcode::I2c
+            | Opcode::I2s
+            | Opcode::Arraylength
+            | Opcode::Checkcast(_)
+            | Opcode::Instanceof(_)
+            | Opcode::Newarray(_)
+            | Opcode::Anewarray(_) => StackEffect::new(&[ONE], &[ONE]),
+
+            Opcode::Lneg | Opcode::Dneg | Opcode::L2d | Opcode::D2l => {
+                StackEffect::new(&[TWO], &[TWO])
+            }
+
+            Opcode::I2l | Opcode::I2d | Opcode::F2l | Opcode::F2d => {
+                StackEffect::new(&[ONE], &[TWO])
+            }
+
+            Opcode::L2i | Opcode::L2f | Opcode::D2i | Opcode::D2f => {
+                StackEffect::new(&[TWO], &[ONE])
+            }
+
+            Opcode::Dup => StackEffect::new(&[ONE], &[ONE, ONE]),
+            Opcode::DupX1 => StackEffect::new(&[ONE, ONE], &[ONE, ONE, ONE]),
+            Opcode::DupX2 => StackEffect::new(&[ONE, ONE, ONE], &[ONE, ONE, ONE, ONE]),
+            Opcode::Dup2 => StackEffect::new(&[ONE, ONE], &[ONE, ONE, ONE, ONE]),
+            Opcode::Dup2X1 => StackEffect::new(&[ONE, ONE, ONE], &[ONE, ONE, ONE, ONE, ONE]),
+            Opcode::Dup2X2 => {
+                StackEffect::new(&[ONE, ONE, ONE, ONE], &[ONE, ONE, ONE, ONE, ONE, ONE])
+            }
+            Opcode::Swap => StackEffect::new(&[ONE, ONE], &[ONE, ONE]),
+
+            Opcode::Multianewarray(_, dimensions) => StackEffect {
+                pops: vec![ONE; *dimensions as usize],
+                pushes: vec![ONE],
+            },
+
+            Opcode::Getstatic(_)
+            | Opcode::Putstatic(_)
+            | Opcode::Getfield(_)
+            | Opcode::Putfield(_)
+            | Opcode::Invokevirtual(_)
+            | Opcode::Invokespecial(_)
+            | Opcode::Invokestatic(_)
+            | Opcode::Invokeinterface(..)
+            | Opcode::Invokedynamic(_) => return None,
+        })
+    }
+
+    /// Effect on the operand stack with `descriptor` being the field descriptor
+    /// of a field access or the method descriptor of an invoke instruction.
+    ///
+    /// Returns `None` if the descriptor is malformed.
+    pub fn stack_effect_with_descriptor(&self, descriptor: &str) -> Option<StackEffect> {
+        let (pops, ret) = match self {
+            Opcode::Getstatic(_) => (vec![], Some(field_category(descriptor)?)),
+            Opcode::Putstatic(_) => (vec![field_category(descriptor)?], None),
+            Opcode::Getfield(_) => (vec![ONE], Some(field_category(descriptor)?)),
+            Opcode::Putfield(_) => (vec![ONE, field_category(descriptor)?], None),
+            Opcode::Invokestatic(_) | Opcode::Invokedynamic(_) => method_categories(descriptor)?,
+            Opcode::Invokevirtual(_) | Opcode::Invokespecial(_) | Opcode::Invokeinterface(..) => {
+                let (params, ret) = method_categories(descriptor)?;
+                let mut pops = vec![ONE];
+                pops.extend(params);
+                (pops, ret)
+            }
+            _ => return self.stack_effect(),
+        };
+        Some(StackEffect {
+            pops,
+            pushes: ret.into_iter().collect(),
+        })
+    }
+
+    /// How control continues after this instruction.
+    pub fn flow_kind(&self) -> FlowKind {
+        match self {
+            Opcode::Goto(_) | Opcode::GotoW(_) => FlowKind::Jump,
+            Opcode::IfAcmpeq(_)
+            | Opcode::IfAcmpne(_)
+            | Opcode::IfIcmpeq(_)
+            | Opcode::IfIcmpge(_)
+            | Opcode::IfIcmpgt(_)
+            | Opcode::IfIcmple(_)
+            | Opcode::IfIcmplt(_)
+            | Opcode::IfIcmpne(_)
+            | Opcode::Ifeq(_)
+            | Opcode::Ifge(_)
+            | Opcode::Ifgt(_)
+            | Opcode::Ifle(_)
+            | Opcode::Iflt(_)
+            | Opcode::Ifne(_)
+            | Opcode::Ifnonnull(_)
+            | Opcode::Ifnull(_) => FlowKind::ConditionalBranch,
+            Opcode::Tableswitch(_) | Opcode::Lookupswitch(_) => FlowKind::Switch,
+            Opcode::Jsr(_) | Opcode::JsrW(_) => FlowKind::Subroutine,
+            Opcode::Ret(_) | Opcode::Wide(WideOpcode::Ret(_)) => FlowKind::SubroutineReturn,
+            Opcode::Ireturn
+            | Opcode::Lreturn
+            | Opcode::Freturn
+            | Opcode::Dreturn
+            | Opcode::Areturn
+            | Opcode::Return => FlowKind::Return,
+            Opcode::Athrow => FlowKind::Throw,
+            _ => FlowKind::Next,
+        }
+    }
+
+    /// Whether the instruction has branch targets.
+    pub fn is_branch(&self) -> bool {
+        matches!(
+            self.flow_kind(),
+            FlowKind::Jump | FlowKind::ConditionalBranch | FlowKind::Switch | FlowKind::Subroutine
+        )
+    }
+
+    /// Whether the instruction never continues with the next instruction, so it
+    /// ends a basic block.
+    pub fn is_terminator(&self) -> bool {
+        matches!(
+            self.flow_kind(),
+            FlowKind::Jump
+                | FlowKind::Switch
+                | FlowKind::SubroutineReturn
+                | FlowKind::Return
+                | FlowKind::Throw
+        )
+    }
+
+    /// Whether the instruction can throw an exception, including linkage errors
+    /// raised when resolving a constant pool entry.
+    pub fn can_throw(&self) -> bool {
+        match self {
+            Opcode::Iaload
+            | Opcode::Laload
+            | Opcode::Faload
+            | Opcode::Daload
+            | Opcode::Aaload
+            | Opcode::Baload
+            | Opcode::Caload
+            | Opcode::Saload
+            | Opcode::Iastore
+            | Opcode::Lastore
+            | Opcode::Fastore
+            | Opcode::Dastore
+            | Opcode::Aastore
+            | Opcode::Bastore
+            | Opcode::Castore
+            | Opcode::Sastore
+            | Opcode::Idiv
+            | Opcode::Irem
+            | Opcode::Ldiv
+            | Opcode::Lrem
+            | Opcode::Ldc(_)
+            | Opcode::LdcW(_)
+            | Opcode::Ldc2W(_)
+            | Opcode::Getstatic(_)
+            | Opcode::Putstatic(_)
+            | Opcode::Getfield(_)
+            | Opcode::Putfield(_)
+            | Opcode::Invokevirtual(_)
+            | Opcode::Invokespecial(_)
+            | Opcode::Invokestatic(_)
+            | Opcode::Invokeinterface(..)
+            | Opcode::Invokedynamic(_)
+            | Opcode::New(_)
+            | Opcode::Newarray(_)
+            | Opcode::Anewarray(_)
+            | Opcode::Multianewarray(..)
+            | Opcode::Arraylength
+            | Opcode::Athrow
+            | Opcode::Checkcast(_)
+            | Opcode::Instanceof(_)
+            | Opcode::Monitorenter
+            | Opcode::Monitorexit => true,
+            // returns throw IllegalMonitorStateException on unbalanced monitors
+            Opcode::Ireturn
+            | Opcode::Lreturn
+            | Opcode::Freturn
+            | Opcode::Dreturn
+            | Opcode::Areturn
+            | Opcode::Return => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableSwitch;
+
+    #[test]
+    fn mnemonic() {
+        assert_eq!(Opcode::IfIcmpge(0).mnemonic(), "if_icmpge");
+        assert_eq!(Opcode::Aload1.mnemonic(), "aload_1");
+        assert_eq!(Opcode::Wide(WideOpcode::Iinc(1, 1)).mnemonic(), "wide");
+    }
+
+    #[test]
+    fn encoded_len() {
+        let table = Opcode::Tableswitch(TableSwitch {
+            default: 0,
+            low: 0,
+            high: 1,
+            offsets: vec![0, 0],
+        });
+        assert_eq!(table.encoded_len(0), 24);
+        assert_eq!(table.encoded_len(3), 21);
+        assert_eq!(Opcode::Invokeinterface(1, 1).encoded_len(7), 5);
+        assert_eq!(Opcode::Wide(WideOpcode::Iinc(1, 1)).encoded_len(0), 6);
+    }
+
+    #[test]
+    fn stack_effect() {
+        let ladd = Opcode::Ladd.stack_effect().unwrap();
+        assert_eq!(ladd.pops, vec![TWO, TWO]);
+        assert_eq!(ladd.pushes, vec![TWO]);
+        assert_eq!(ladd.delta(), -2);
+
+        assert_eq!(Opcode::Dup2X1.stack_effect().unwrap().delta(), 2);
+        assert_eq!(
+            Opcode::Multianewarray(1, 3).stack_effect().unwrap().delta(),
+            -2
+        );
+        assert_eq!(Opcode::Invokevirtual(1).stack_effect(), None);
+    }
+
+    #[test]
+    fn stack_effect_with_descriptor() {
+        let invoke = Opcode::Invokevirtual(1)
+            .stack_effect_with_descriptor("(IJ[Ljava/lang/String;)D")
+            .unwrap();
+        assert_eq!(invoke.pops, vec![ONE, ONE, TWO, ONE]);
+        assert_eq!(invoke.pushes, vec![TWO]);
+
+        let invoke = Opcode::Invokestatic(1)
+            .stack_effect_with_descriptor("()V")
+            .unwrap();
+        assert_eq!(invoke, StackEffect::new(&[], &[]));
+
+        let putfield = Opcode::Putfield(1)
+            .stack_effect_with_descriptor("J")
+            .unwrap();
+        assert_eq!(putfield.pops, vec![ONE, TWO]);
+
+        assert_eq!(
+            Opcode::Getstatic(1).stack_effect_with_descriptor("Ljava/lang/Object"),
+            None
+        );
+        assert_eq!(
+            Opcode::Invokestatic(1).stack_effect_with_descriptor("(I"),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_descriptors() {
+        assert_eq!(field_category(&format!("{}J", "[".repeat(255))), Some(ONE));
+        assert_eq!(field_category(&format!("{}J", "[".repeat(256))), None);
+        assert_eq!(field_category(&"[".repeat(100_000)), None);
+        assert_eq!(field_category("L;"), None);
+        assert_eq!(field_category("[L;"), None);
+        assert_eq!(method_categories("(L;)V"), None);
+    }
+
+    #[test]
+    fn control_flow() {
+        assert_eq!(Opcode::Iadd.flow_kind(), FlowKind::Next);
+        assert!(Opcode::Goto(0).is_branch());
+        assert!(Opcode::Goto(0).is_terminator());
+        assert!(Opcode::Ifeq(0).is_branch());
+        assert!(!Opcode::Ifeq(0).is_terminator());
+        assert!(!Opcode::Athrow.is_branch());
+        assert!(Opcode::Athrow.is_terminator());
+        assert!(Opcode::Idiv.can_throw());
+        assert!(!Opcode::Iadd.can_throw());
+    }
+}
@@ -84,17 +84,17 @@ impl GenJavaCode for JavaClass {
         }
         writer.write_str("{\n")?;
 
-        if let Some(static_init) = &self.static_init {
+        if self.static_init.is_some() {
             writer.write_str("static ")?;
             // TODO: static_init.gen_java_code(writer)?;
         }
 
         for field in &self.fields {
-            field.gen_java_code(writer);
+            field.gen_java_code(writer)?;
         }
 
         for method in &self.methods {
-            method.gen_java_code(writer);
+            method.gen_java_code(writer)?;
         }
 
         writer.write_str("}\n")
@@ -116,7 +116,7 @@ impl GenJavaCode for JavaField {
             writer.write_str("static ")?;
         }
         writer.write_fmt(format_args!("{} {}", self.ty, self.ident))?;
-        if let Some(init) = &self.init {
+        if self.init.is_some() {
             writer.write_str(" = ")?;
             // TODO: init.gen_java_code(writer)?;
         }
@@ -136,13 +136,13 @@ impl GenJavaCode for JavaArgument {
 }
 
 pub struct JavaMethod {
-    vis: Option<JavaVisibility>,
-    static_: bool,
-    final_: bool,
-    rty: String,
-    ident: String,
-    args: Vec<JavaArgument>,
-    body: JavaBlock,
+    pub vis: Option<JavaVisibility>,
+    pub static_: bool,
+    pub final_: bool,
+    pub rty: String,
+    pub ident: String,
+    pub args: Vec<JavaArgument>,
+    pub body: JavaBlock,
 }
 
 fn join<T: GenJavaCode, W: Write>(writer: &mut W, sep: &str, elems: &[T]) -> fmt::Result {
@@ -175,7 +175,7 @@ impl GenJavaCode for JavaMethod {
 }
 
 pub struct JavaBlock {
-    items: Vec<JavaStatement>,
+    pub items: Vec<JavaStatement>,
 }
 
 pub enum JavaStatement {
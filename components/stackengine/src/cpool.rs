@@ -1,3 +0,0 @@
-use crate::JValue;
-
-pub struct RuntimeConstantPool(Vec<JValue>);
@@ -1,3 +0,0 @@
-pub struct JError {}
-
-pub type JResult<T> = Result<T, JError>;
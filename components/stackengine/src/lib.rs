@@ -1,20 +1,13 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::convert::TryFrom;
 use std::sync::Arc;
 
-use classfile::model::constants::{Constant, ConstantIndex, ConstantPool};
-use rustjvm_opcode::Opcode;
+use classfile::descriptor::parse_method_descriptor;
+use classfile::error::JvmParseError;
+use classfile::model::constants::{ConstantIndex, ConstantPool, Loadable};
+use classfile::model::{Attribute, Method, MethodAccessFlags};
+use rustjvm_opcode::{Bytecode, Opcode};
 
-use crate::cpool::RuntimeConstantPool;
-
-mod class_loader;
-mod cpool;
-mod exception;
-
-pub struct JObject {
-    fields: Vec<JValue>,
-}
+pub struct JObject {}
 
 pub type JObjectRef = Option<Arc<RefCell<JObject>>>;
 
@@ -57,62 +50,91 @@ impl JValue {
 
     pub fn as_int(&self) -> JResult<i32> {
         match self {
-            JValue::Int(r) => Ok(r.clone()),
+            JValue::Int(r) => Ok(*r),
             _ => Err(FnError("wrong value type".into())),
         }
     }
 
     pub fn as_long(&self) -> JResult<i64> {
         match self {
-            JValue::Long(r) => Ok(r.clone()),
+            JValue::Long(r) => Ok(*r),
             _ => Err(FnError("wrong value type".into())),
         }
     }
 
     pub fn as_float(&self) -> JResult<f32> {
         match self {
-            JValue::Float(r) => Ok(r.clone()),
+            JValue::Float(r) => Ok(*r),
             _ => Err(FnError("wrong value type".into())),
         }
     }
 
     pub fn as_double(&self) -> JResult<f64> {
         match self {
-            JValue::Double(r) => Ok(r.clone()),
+            JValue::Double(r) => Ok(*r),
             _ => Err(FnError("wrong value type".into())),
         }
     }
 
-    pub fn new_string(s: &str) -> Self {
+    pub fn new_string(_s: &str) -> Self {
         unimplemented!()
     }
 
     pub fn from_constant(index: ConstantIndex, cpool: &ConstantPool) -> JResult<Self> {
-        unimplemented!()
-        // Ok(match value {
-        //     String(index) => match cpool.get(index) {
-        //         Constant::Utf8(s) => JValue::new_string(&s),
-        //     },
-        // })
+        Ok(match cpool.resolve_loadable(index)? {
+            Loadable::Integer(value) => JValue::Int(value),
+            Loadable::Float(value) => JValue::Float(value),
+            Loadable::Long(value) => JValue::Long(value),
+            Loadable::Double(value) => JValue::Double(value),
+            Loadable::String(_) => {
+                return Err(FnError("string constants are not supported".into()))
+            }
+            _ => return Err(FnError("unsupported constant type".into())),
+        })
     }
 }
 
 pub struct LoadedMethod {
-    code: Vec<Opcode>,
+    code: Bytecode,
     max_stack: u16,
     max_locals: u16,
     args: u16,
 }
 
-pub struct LoadedField {
-    id: String,
+impl LoadedMethod {
+    pub fn new(method: &Method, cpool: &ConstantPool) -> JResult<Self> {
+        let descriptor = cpool
+            .resolve_utf8(method.descriptor_index)
+            .ok()
+            .and_then(parse_method_descriptor)
+            .ok_or_else(|| FnError("invalid method descriptor".into()))?;
+        let code = method
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Code(code) => Some(code),
+                _ => None,
+            })
+            .ok_or_else(|| FnError("method has no code".into()))?;
+
+        // instance methods get `this` as first argument
+        let this = if method.access_flags.contains(MethodAccessFlags::STATIC) {
+            0
+        } else {
+            1
+        };
+
+        Ok(Self {
+            code: code.code.clone(),
+            max_stack: code.max_stack,
+            max_locals: code.max_locals,
+            args: descriptor.param_slots() + this,
+        })
+    }
 }
 
 pub struct LoadedClass {
-    id: String,
-    cpool: RuntimeConstantPool,
-    fields: Vec<LoadedField>,
-    methods: HashMap<String, LoadedMethod>,
+    constant_pool: ConstantPool,
 }
 
 pub struct JEngine {
@@ -141,15 +163,7 @@ impl JEngine {
     }
 
     fn top(&self) -> JResult<&JValue> {
-        match self.stack.get(self.stack.len() - 1) {
-            Some(r) => Ok(r),
-            None => Err(FnError("stack underflow".to_string())),
-        }
-    }
-
-    fn top_mut(&mut self) -> JResult<&mut JValue> {
-        let last_index = self.stack.len() - 1;
-        match self.stack.get_mut(last_index) {
+        match self.stack.last() {
             Some(r) => Ok(r),
             None => Err(FnError("stack underflow".to_string())),
         }
@@ -160,23 +174,12 @@ impl JEngine {
             .get(index as usize)
             .ok_or_else(|| FnError("local does not exist".to_string()))
     }
-
-    fn local_mut(&mut self, index: u8) -> JResult<&mut JValue> {
-        self.locals
-            .get_mut(index as usize)
-            .ok_or_else(|| FnError("local does not exist".to_string()))
-    }
-}
-
-pub struct JClass {
-    constant_pool: ConstantPool,
 }
 
 pub struct FnCall {
     cls: Arc<LoadedClass>,
     f: Arc<LoadedMethod>,
     pc: u16,
-    sp: usize,
     lp: usize,
 }
 
@@ -188,7 +191,13 @@ pub enum FnAction {
     Call(u16),
 }
 
-pub struct FnError(String);
+pub struct FnError(pub String);
+
+impl From<JvmParseError> for FnError {
+    fn from(error: JvmParseError) -> Self {
+        FnError(format!("{:?}", error))
+    }
+}
 
 pub type JResult<T> = Result<T, FnError>;
 pub type FnResult = Result<FnAction, FnError>;
@@ -206,27 +215,23 @@ impl FnCall {
             engine.locals.push(JValue::Invalid); // TODO: use right types
         }
 
-        let sp = engine.stack.len();
         engine.stack.reserve(f.max_stack as usize);
 
-        Self {
-            cls,
-            f,
-            pc: 0,
-            sp,
-            lp,
-        }
+        Self { cls, f, pc: 0, lp }
     }
 
     pub fn invoke(&mut self, engine: &mut JEngine) -> FnResult {
         let code = &self.f.code;
 
         loop {
-            match code.get(self.pc as usize).expect("invalid p... counter") {
+            let index = code
+                .index_of(self.pc)
+                .ok_or_else(|| FnError("invalid program counter".into()))?;
+            match &code.instructions()[index].opcode {
                 Opcode::Aaload => {
                     let (array_ref, index) = engine.pop2()?;
                     let index = match index {
-                        JValue::Int(i) => i as i32,
+                        JValue::Int(i) => i,
                         JValue::Short(i) => i as i32,
                         JValue::Byte(i) => i as i32,
                         _ => panic!("wrong index type"),
@@ -246,7 +251,7 @@ impl FnCall {
                 Opcode::Aastore => {
                     let (array_ref, index, value) = engine.pop3()?;
                     let index = match index {
-                        JValue::Int(i) => i as i32,
+                        JValue::Int(i) => i,
                         JValue::Short(i) => i as i32,
                         JValue::Byte(i) => i as i32,
                         _ => return Err(FnError("wrong index type".into())),
@@ -255,7 +260,7 @@ impl FnCall {
 
                     match array_ref {
                         JValue::ObjectArray(arr) => {
-                            if let Some(mut value_ref) = arr.borrow_mut().get_mut(index as usize) {
+                            if let Some(value_ref) = arr.borrow_mut().get_mut(index as usize) {
                                 *value_ref = value.clone();
                             } else {
                                 return Err(FnError("ArrayIndexOutOfBoundsException".into()));
@@ -293,20 +298,13 @@ impl FnCall {
                 Opcode::Fconst1 => engine.push(JValue::Float(1.0))?,
                 Opcode::Fconst2 => engine.push(JValue::Float(2.0))?,
                 Opcode::AconstNull => engine.push(JValue::Reference(None))?,
-                Opcode::Ldc(index) => {
-                    unimplemented!()
-                    // engine.push(
-                    //     self.cls
-                    //         .constant_pool
-                    //         .get(ConstantIndex(*index as u16))
-                    //         .ok_or_else(|| FnError("non-existing constant".into()))?
-                    //         .into(),
-                    // )?;
-                }
-                Opcode::LdcW(index) => {
-                    unimplemented!()
-                    // engine.push(self.f.class.constant_pool.get(ConstantIndex(*index)).into())?;
-                }
+                Opcode::Ldc(index) => engine.push(JValue::from_constant(
+                    ConstantIndex(*index as u16),
+                    &self.cls.constant_pool,
+                )?)?,
+                Opcode::LdcW(index) | Opcode::Ldc2W(index) => engine.push(
+                    JValue::from_constant(ConstantIndex(*index), &self.cls.constant_pool)?,
+                )?,
 
                 Opcode::F2i => {
                     let j_value = JValue::Int(engine.pop()?.as_float()? as i32);
@@ -387,14 +385,30 @@ impl FnCall {
 
                 _ => return Err(FnError("unsupported opcode".into())),
             };
+
+            self.pc = code
+                .get(index + 1)
+                .map(|next| next.offset)
+                .ok_or_else(|| FnError("fell off the end of the code".into()))?;
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use classfile::build::ConstantPoolBuilder;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn string_constant_is_unsupported() {
+        let mut constants = ConstantPoolBuilder::new();
+        let index = constants.string("hello");
+        let cpool = constants.build().unwrap();
+        assert!(JValue::from_constant(index, &cpool).is_err());
+    }
 }
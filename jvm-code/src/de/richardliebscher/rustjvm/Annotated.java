@@ -0,0 +1,48 @@
+package de.richardliebscher.rustjvm;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Annotated.Info(
+        name = "class",
+        count = 3,
+        ratio = 0.5,
+        flag = true,
+        type = String.class,
+        element = ElementType.TYPE,
+        tags = {"a", "b"},
+        nested = @Annotated.Marker)
+@Deprecated
+public class Annotated {
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Info {
+        String name() default "unnamed";
+
+        int count() default 0;
+
+        double ratio() default 1.0;
+
+        boolean flag() default false;
+
+        Class<?> type() default void.class;
+
+        ElementType element() default ElementType.FIELD;
+
+        String[] tags() default {};
+
+        Marker nested() default @Marker;
+    }
+
+    @Retention(RetentionPolicy.CLASS)
+    public @interface Marker {
+        char letter() default 'x';
+    }
+
+    @Marker(letter = 'f')
+    public int field;
+
+    @Info(name = "method")
+    public void method(@Marker int first, int second, @Info @Marker(letter = 't') String third) {
+    }
+}
@@ -0,0 +1,21 @@
+package de.richardliebscher.rustjvm;
+
+import java.util.ArrayList;
+import java.util.List;
+
+public class DebugInfo {
+    public static int sum(int count) {
+        int sum = 0;
+        for (int i = 0; i < count; i++) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    public static List<String> names(String prefix) {
+        List<String> names = new ArrayList<>();
+        names.add(prefix + "a");
+        names.add(prefix + "b");
+        return names;
+    }
+}
@@ -0,0 +1,14 @@
+package de.richardliebscher.rustjvm;
+
+import java.io.Serializable;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Map;
+
+public class Generics<T extends Comparable<? super T>> extends ArrayList<T> implements Serializable {
+    public Map<String, List<? extends Number>> index;
+
+    public <E extends Exception> T first(List<? extends T> list, Map.Entry<String, int[]> entry) throws E {
+        return list.get(0);
+    }
+}
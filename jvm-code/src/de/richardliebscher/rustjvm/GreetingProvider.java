@@ -0,0 +1,10 @@
+package de.richardliebscher.rustjvm;
+
+import java.util.function.Supplier;
+
+public class GreetingProvider implements Supplier<String> {
+    @Override
+    public String get() {
+        return "Hello";
+    }
+}
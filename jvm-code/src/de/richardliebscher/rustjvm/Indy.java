@@ -0,0 +1,9 @@
+package de.richardliebscher.rustjvm;
+
+import java.util.function.Supplier;
+
+public class Indy {
+    public static Supplier<String> greeting(String name) {
+        return () -> "Hello " + name + "!";
+    }
+}
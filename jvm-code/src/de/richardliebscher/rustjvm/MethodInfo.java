@@ -0,0 +1,28 @@
+package de.richardliebscher.rustjvm;
+
+import java.io.IOException;
+import java.util.concurrent.Callable;
+
+public class MethodInfo implements Callable<String> {
+    protected volatile int counter;
+    private transient Object cache;
+
+    /**
+     * @deprecated use {@link #call()}
+     */
+    @Deprecated
+    public static final String LEGACY = "legacy";
+
+    @Override
+    public String call() {
+        return LEGACY;
+    }
+
+    /**
+     * @deprecated use {@link #call()}
+     */
+    @Deprecated
+    public synchronized void read(final String path, int... offsets)
+            throws IOException, InterruptedException {
+    }
+}
@@ -0,0 +1,23 @@
+package de.richardliebscher.rustjvm;
+
+import java.util.List;
+
+public class Shapes {
+    public sealed interface Shape permits Circle, Polygon {
+    }
+
+    public record Circle(double radius) implements Shape {
+    }
+
+    public record Polygon(@Deprecated List<String> points) implements Shape {
+    }
+
+    public static Runnable printer(Shape shape) {
+        return new Runnable() {
+            @Override
+            public void run() {
+                System.out.println(shape);
+            }
+        };
+    }
+}
@@ -0,0 +1,83 @@
+package de.richardliebscher.rustjvm;
+
+public class StackMaps {
+    private final Object value;
+
+    public StackMaps(boolean flag) {
+        this(flag ? "yes" : null);
+    }
+
+    public StackMaps(Object value) {
+        this.value = value;
+    }
+
+    public static StackMaps create(boolean flag) {
+        return new StackMaps(flag ? "yes" : "no");
+    }
+
+    public static long locals(int count, double factor) {
+        long sum = 0;
+        for (int i = 0; i < count; i++) {
+            sum += (long) (i * factor);
+        }
+        if (sum > 100) {
+            String text = String.valueOf(sum);
+            sum = text.length();
+        }
+        return sum;
+    }
+
+    public static int handler(String text) {
+        try {
+            return Integer.parseInt(text);
+        } catch (NumberFormatException e) {
+            return -1;
+        }
+    }
+
+    public static int longJump(int a, int b) {
+        int x = 0;
+        if (a > b) {
+            x = a * b + a * b + a * b + a * b + a * b + a * b + a * b + a * b;
+            x = x * a + x * b + x * a + x * b + x * a + x * b + x * a + x * b;
+        }
+        return x;
+    }
+
+    public static Object full(Object a, int b) {
+        Object result;
+        if (b > 0) {
+            long tmp = b;
+            result = a == null ? Long.valueOf(tmp) : a;
+        } else {
+            result = null;
+        }
+        return result;
+    }
+
+    public static int sameExtended(int a, int b) {
+        if (a > b) {
+            a = a * b + a * b + a * b + a * b + a * b + a * b + a * b + a * b;
+            a = a * b + a * b + a * b + a * b + a * b + a * b + a * b + a * b;
+        }
+        return a;
+    }
+
+    public static int handlerExtended(int a, int b) {
+        try {
+            a = a / b + a / b + a / b + a / b + a / b + a / b + a / b + a / b;
+            a = a / b + a / b + a / b + a / b + a / b + a / b + a / b + a / b;
+        } catch (ArithmeticException e) {
+            a = 0;
+        }
+        return a;
+    }
+
+    public static Object floatsAndNull(float f, boolean flag) {
+        Object o = null;
+        while (f > 1) {
+            f /= 2;
+        }
+        return flag ? o : null;
+    }
+}
@@ -0,0 +1,12 @@
+package de.richardliebscher.rustjvm;
+
+public class Strings {
+    public static final String PLAIN = "plain";
+    public static final String NUL = "a\0b";
+    public static final String SUPPLEMENTARY = "\uD83D\uDE00";
+    public static final String LONE_SURROGATE = "x\uD800y";
+
+    public static String join() {
+        return PLAIN + NUL + SUPPLEMENTARY + LONE_SURROGATE;
+    }
+}
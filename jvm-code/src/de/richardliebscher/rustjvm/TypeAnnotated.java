@@ -0,0 +1,22 @@
+package de.richardliebscher.rustjvm;
+
+import java.io.Serializable;
+import java.util.List;
+import java.util.Map;
+
+public abstract class TypeAnnotated<@Nullable T> implements @Nullable Serializable {
+    public Map<String, @Nullable List<? extends @Nullable Number>> index;
+
+    public @Nullable String @Nullable [] names;
+
+    public abstract @Nullable Object method(@Nullable String first) throws @Nullable Exception;
+
+    public int body(Object value) {
+        @Nullable String text = (@Nullable String) value;
+        if (value instanceof @Nullable Number) {
+            return 1;
+        }
+        Object created = new @Nullable StringBuilder();
+        return text.length() + created.hashCode();
+    }
+}
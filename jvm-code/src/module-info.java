@@ -0,0 +1,7 @@
+module de.richardliebscher.rustjvm {
+    requires transitive java.logging;
+    exports de.richardliebscher.rustjvm;
+    opens de.richardliebscher.rustjvm to java.base;
+    uses java.util.function.Supplier;
+    provides java.util.function.Supplier with de.richardliebscher.rustjvm.GreetingProvider;
+}